@@ -0,0 +1,91 @@
+// The builders return store metadata with empty attributes for the unit tests, which set the
+// attributes they cover on top.
+use crate::db::store::{
+    ColumnMetadata, DatabaseSchemaMetadata, IdentityGeneration, IndexMetadata, SchemaMetadata,
+    TableMetadata,
+};
+
+pub(crate) fn database(schemas: Vec<SchemaMetadata>) -> DatabaseSchemaMetadata {
+    DatabaseSchemaMetadata {
+        name: "test_db".to_string(),
+        schemas,
+        character_set: String::new(),
+        collation: String::new(),
+        extensions: vec![],
+        datashare: false,
+        service_name: String::new(),
+        owner: String::new(),
+    }
+}
+
+pub(crate) fn schema(name: &str, tables: Vec<TableMetadata>) -> SchemaMetadata {
+    SchemaMetadata {
+        name: name.to_string(),
+        tables,
+        external_tables: vec![],
+        views: vec![],
+        functions: vec![],
+        procedures: vec![],
+        materialized_views: vec![],
+        events: vec![],
+        owner: String::new(),
+        comment: String::new(),
+    }
+}
+
+pub(crate) fn table(name: &str, columns: Vec<ColumnMetadata>) -> TableMetadata {
+    TableMetadata {
+        name: name.to_string(),
+        columns,
+        indexes: vec![],
+        engine: String::new(),
+        collation: None,
+        row_count: 0,
+        data_size: 0,
+        index_size: 0,
+        data_free: 0,
+        create_options: String::new(),
+        comment: String::new(),
+        foreign_keys: vec![],
+        owner: String::new(),
+        triggers: vec![],
+        partitioning: None,
+    }
+}
+
+pub(crate) fn column(name: &str, position: i32, r#type: &str, nullable: bool) -> ColumnMetadata {
+    ColumnMetadata {
+        name: name.to_string(),
+        position,
+        default: String::new(),
+        on_update: None,
+        nullable,
+        r#type: r#type.to_string(),
+        character_set: String::new(),
+        collation: String::new(),
+        comment: String::new(),
+        identity_generation: IdentityGeneration::UNSPECIFIED,
+    }
+}
+
+// The index has no key prefix on any of its expressions.
+pub(crate) fn index(
+    name: &str,
+    expressions: &[&str],
+    primary: bool,
+    unique: bool,
+) -> IndexMetadata {
+    IndexMetadata {
+        name: name.to_string(),
+        expressions: expressions.iter().map(|e| e.to_string()).collect(),
+        key_length: vec![-1; expressions.len()],
+        r#type: "BTREE".to_string(),
+        unique,
+        primary,
+        visible: true,
+        comment: String::new(),
+        definition: String::new(),
+        include_columns: vec![],
+        predicate: String::new(),
+    }
+}
@@ -0,0 +1,43 @@
+// Renders store metadata back into DDL statements.
+pub mod mysql;
+pub mod postgres;
+
+use crate::db::store::PartitionStrategy;
+
+// Orders relations so that each one follows the relations of the list it depends on.
+// Relations are visited in key order and a dependency cycle is broken at the first
+// remaining relation.
+pub(crate) fn dependency_order<K: Ord + Copy>(relations: &[(K, Vec<K>)]) -> Vec<K> {
+    let mut pending: Vec<&(K, Vec<K>)> = relations.iter().collect();
+    pending.sort_by_key(|(key, _)| *key);
+
+    let mut ordered = vec![];
+    while !pending.is_empty() {
+        // A dependency is satisfied once it is no longer pending, keys missing from the list never are.
+        let ready = pending
+            .iter()
+            .position(|(key, dependencies)| {
+                dependencies
+                    .iter()
+                    .all(|d| d == key || !pending.iter().any(|(p, _)| p == d))
+            })
+            .unwrap_or(0);
+        ordered.push(pending.remove(ready).0);
+    }
+
+    ordered
+}
+
+// Returns the keywords of a partition strategy as written after PARTITION BY.
+pub(crate) fn partition_strategy(strategy: &PartitionStrategy) -> &'static str {
+    match strategy {
+        PartitionStrategy::Range => "RANGE",
+        PartitionStrategy::RangeColumns => "RANGE COLUMNS",
+        PartitionStrategy::List => "LIST",
+        PartitionStrategy::ListColumns => "LIST COLUMNS",
+        PartitionStrategy::Hash => "HASH",
+        PartitionStrategy::LinearHash => "LINEAR HASH",
+        PartitionStrategy::Key => "KEY",
+        PartitionStrategy::LinearKey => "LINEAR KEY",
+    }
+}
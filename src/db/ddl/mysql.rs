@@ -0,0 +1,592 @@
+use super::partition_strategy;
+use crate::db::store::{
+    ColumnMetadata, ForeignKeyMetadata, IndexMetadata, PartitionMetadata, PartitionStrategy,
+    PartitioningMetadata, TableMetadata, ViewMetadata,
+};
+
+// The default sentinel written by the MySQL driver and parser for AUTO_INCREMENT columns.
+pub(crate) const AUTO_INCREMENT_SYMBOL: &str = "AUTO_INCREMENT";
+
+// Renders a table synced by the MySQL driver as a `CREATE TABLE` statement.
+// The layout follows `SHOW CREATE TABLE`, so the result carries no trailing
+// semicolon and can be compared with the server output directly.
+pub fn create_table(table: &TableMetadata) -> String {
+    let table_collation = table.collation.as_deref().unwrap_or_default();
+    let table_charset = charset_of(table_collation);
+
+    let mut columns: Vec<&ColumnMetadata> = table.columns.iter().collect();
+    columns.sort_by_key(|c| c.position);
+
+    let mut lines: Vec<String> = columns
+        .into_iter()
+        .map(|c| column_definition(c, table_charset, table_collation))
+        .collect();
+
+    // SHOW CREATE TABLE lists the primary key first, then unique keys, then the rest.
+    let mut indexes: Vec<&IndexMetadata> = table.indexes.iter().collect();
+    indexes.sort_by_key(|idx| (!idx.primary, !idx.unique));
+    lines.extend(indexes.into_iter().map(index_definition));
+    lines.extend(table.foreign_keys.iter().map(foreign_key_definition));
+
+    let mut ddl = format!(
+        "CREATE TABLE {} (\n  {}\n)",
+        quote_identifier(&table.name),
+        lines.join(",\n  ")
+    );
+
+    if !table.engine.is_empty() {
+        ddl.push_str(&format!(" ENGINE={}", table.engine));
+    }
+    if !table_collation.is_empty() {
+        ddl.push_str(&format!(
+            " DEFAULT CHARSET={table_charset} COLLATE={table_collation}"
+        ));
+    }
+    ddl.push_str(&table_options(&table.create_options));
+    if !table.comment.is_empty() {
+        ddl.push_str(&format!(" COMMENT={}", quote_string(&table.comment)));
+    }
+    if let Some(partitioning) = &table.partitioning {
+        // SHOW CREATE TABLE wraps the partitioning in a version comment.
+        let version = match partitioning.strategy {
+            PartitionStrategy::RangeColumns | PartitionStrategy::ListColumns => 50500,
+            _ => 50100,
+        };
+        ddl.push_str(&format!(
+            "\n/*!{version} {} */",
+            partition_by(partitioning, &table.engine)
+        ));
+    }
+
+    ddl
+}
+
+// Renders the PARTITION BY clause of a partitioned table. Partitions and
+// subpartitions keeping the names MySQL generates, p0, p1 and so on for
+// partitions and the partition name followed by sp0, sp1 and so on for
+// subpartitions, are counted rather than listed.
+pub(crate) fn partition_by(partitioning: &PartitioningMetadata, engine: &str) -> String {
+    let mut clause = format!(
+        "PARTITION BY {} ({})",
+        partition_strategy(&partitioning.strategy),
+        partitioning.expression
+    );
+    let partitions = &partitioning.partitions;
+    let subpartitioning = partitions.first().and_then(|p| p.subpartitioning.as_ref());
+    if let Some(subpartitioning) = subpartitioning {
+        clause.push_str(&format!(
+            "\nSUBPARTITION BY {} ({})",
+            partition_strategy(&subpartitioning.strategy),
+            subpartitioning.expression
+        ));
+    }
+    let subpartition_count = subpartitioning.map_or(0, |s| s.partitions.len());
+    let default_subpartitions = subpartitioning.is_some()
+        && partitions.iter().all(|p| {
+            p.subpartitioning.as_ref().is_some_and(|s| {
+                s.partitions.len() == subpartition_count
+                    && s.partitions
+                        .iter()
+                        .enumerate()
+                        .all(|(i, sp)| sp.name == format!("{}sp{i}", p.name))
+            })
+        });
+    if default_subpartitions {
+        clause.push_str(&format!("\nSUBPARTITIONS {subpartition_count}"));
+    }
+
+    let default_partitions = subpartitioning.is_none()
+        && partitions
+            .iter()
+            .enumerate()
+            .all(|(i, p)| p.value.is_empty() && p.name == format!("p{i}"));
+    if default_partitions {
+        clause.push_str(&format!("\nPARTITIONS {}", partitions.len()));
+        return clause;
+    }
+
+    let engine = if engine.is_empty() {
+        String::new()
+    } else {
+        format!(" ENGINE = {engine}")
+    };
+    let definitions: Vec<String> = partitions
+        .iter()
+        .map(|p| {
+            let mut definition = format!(
+                "PARTITION {}{}",
+                partition_name(&p.name),
+                partition_values(&partitioning.strategy, p)
+            );
+            match &p.subpartitioning {
+                Some(s) if !default_subpartitions => {
+                    let subpartitions: Vec<String> = s
+                        .partitions
+                        .iter()
+                        .map(|sp| format!("SUBPARTITION {}{engine}", partition_name(&sp.name)))
+                        .collect();
+                    definition.push_str(&format!("\n ({})", subpartitions.join(",\n  ")));
+                }
+                _ => definition.push_str(&engine),
+            }
+            definition
+        })
+        .collect();
+    clause.push_str(&format!("\n({})", definitions.join(",\n ")));
+    clause
+}
+
+// Returns the VALUES clause of a partition preceded by a space, the value is the
+// PARTITION_DESCRIPTION of INFORMATION_SCHEMA.PARTITIONS.
+fn partition_values(strategy: &PartitionStrategy, partition: &PartitionMetadata) -> String {
+    match strategy {
+        PartitionStrategy::Range if partition.value == "MAXVALUE" => {
+            " VALUES LESS THAN MAXVALUE".to_string()
+        }
+        PartitionStrategy::Range | PartitionStrategy::RangeColumns => {
+            format!(" VALUES LESS THAN ({})", partition.value)
+        }
+        PartitionStrategy::List | PartitionStrategy::ListColumns => {
+            format!(" VALUES IN ({})", partition.value)
+        }
+        _ => String::new(),
+    }
+}
+
+// SHOW CREATE TABLE quotes a partition name only when it is not a plain word.
+fn partition_name(name: &str) -> String {
+    if !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        name.to_string()
+    } else {
+        quote_identifier(name)
+    }
+}
+
+// Renders the CREATE_OPTIONS reported by INFORMATION_SCHEMA as table options,
+// each one preceded by a space.
+pub(crate) fn table_options(create_options: &str) -> String {
+    let mut options = String::new();
+    for option in create_options.split_whitespace() {
+        // The partitioned flag is reported by INFORMATION_SCHEMA but is not a table option.
+        if option.eq_ignore_ascii_case("partitioned") {
+            continue;
+        }
+        match option.split_once('=') {
+            Some((key, value)) => options.push_str(&format!(" {}={value}", key.to_uppercase())),
+            None => options.push_str(&format!(" {}", option.to_uppercase())),
+        }
+    }
+    options
+}
+
+// Renders a view synced by the MySQL driver, the definition is the normalized
+// VIEW_DEFINITION so the statement carries no algorithm or definer clause.
+pub(crate) fn create_view(view: &ViewMetadata) -> String {
+    format!(
+        "CREATE VIEW {} AS {}",
+        quote_identifier(&view.name),
+        view.definition
+    )
+}
+
+pub(crate) fn column_definition(
+    column: &ColumnMetadata,
+    table_charset: &str,
+    table_collation: &str,
+) -> String {
+    let mut def = format!("{} {}", quote_identifier(&column.name), column.r#type);
+
+    if !column.character_set.is_empty() && column.character_set != table_charset {
+        def.push_str(&format!(" CHARACTER SET {}", column.character_set));
+    }
+    if !column.collation.is_empty() && column.collation != table_collation {
+        def.push_str(&format!(" COLLATE {}", column.collation));
+    }
+
+    if !column.nullable {
+        def.push_str(" NOT NULL");
+    } else if column.r#type.starts_with("timestamp") {
+        // MySQL spells out NULL for nullable TIMESTAMP columns.
+        def.push_str(" NULL");
+    }
+
+    match column.default.as_str() {
+        "" | AUTO_INCREMENT_SYMBOL => {}
+        "NULL" => {
+            if column.nullable && allows_literal_default(&column.r#type) {
+                def.push_str(" DEFAULT NULL");
+            }
+        }
+        default => def.push_str(&format!(" DEFAULT {}", default_value(default))),
+    }
+
+    if let Some(on_update) = &column.on_update {
+        def.push_str(&format!(" ON UPDATE {on_update}"));
+    }
+    if column.default == AUTO_INCREMENT_SYMBOL {
+        def.push_str(" AUTO_INCREMENT");
+    }
+    if !column.comment.is_empty() {
+        def.push_str(&format!(" COMMENT {}", quote_string(&column.comment)));
+    }
+
+    def
+}
+
+pub(crate) fn index_definition(index: &IndexMetadata) -> String {
+    let parts = index
+        .expressions
+        .iter()
+        .enumerate()
+        .map(|(i, expression)| {
+            if expression.starts_with('(') {
+                // Functional key parts are already stored in their parenthesized form.
+                expression.clone()
+            } else {
+                match index.key_length.get(i) {
+                    Some(length) if *length > 0 => {
+                        format!("{}({length})", quote_identifier(expression))
+                    }
+                    _ => quote_identifier(expression),
+                }
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(",");
+
+    let mut def = if index.primary {
+        format!("PRIMARY KEY ({parts})")
+    } else {
+        let kind = match index.r#type.as_str() {
+            "FULLTEXT" => "FULLTEXT KEY",
+            "SPATIAL" => "SPATIAL KEY",
+            _ if index.unique => "UNIQUE KEY",
+            _ => "KEY",
+        };
+        format!("{kind} {} ({parts})", quote_identifier(&index.name))
+    };
+
+    if index.r#type == "HASH" {
+        def.push_str(" USING HASH");
+    }
+    if !index.comment.is_empty() {
+        def.push_str(&format!(" COMMENT {}", quote_string(&index.comment)));
+    }
+    if !index.visible {
+        def.push_str(" /*!80000 INVISIBLE */");
+    }
+
+    def
+}
+
+pub(crate) fn foreign_key_definition(fk: &ForeignKeyMetadata) -> String {
+    let referenced_table = if fk.referenced_schema.is_empty() {
+        quote_identifier(&fk.referenced_table)
+    } else {
+        format!(
+            "{}.{}",
+            quote_identifier(&fk.referenced_schema),
+            quote_identifier(&fk.referenced_table)
+        )
+    };
+
+    let mut def = format!(
+        "CONSTRAINT {} FOREIGN KEY ({}) REFERENCES {referenced_table} ({})",
+        quote_identifier(&fk.name),
+        quote_identifier_list(&fk.columns),
+        quote_identifier_list(&fk.referenced_columns)
+    );
+
+    // NO ACTION is what INFORMATION_SCHEMA reports when no rule was given.
+    if !fk.on_delete.is_empty() && fk.on_delete != "NO ACTION" {
+        def.push_str(&format!(" ON DELETE {}", fk.on_delete));
+    }
+    if !fk.on_update.is_empty() && fk.on_update != "NO ACTION" {
+        def.push_str(&format!(" ON UPDATE {}", fk.on_update));
+    }
+
+    def
+}
+
+fn default_value(default: &str) -> String {
+    let upper = default.to_uppercase();
+    if default.starts_with('(')
+        || upper.starts_with("CURRENT_TIMESTAMP")
+        || upper.starts_with("B'")
+        || upper.starts_with("X'")
+    {
+        default.to_string()
+    } else {
+        quote_string(default)
+    }
+}
+
+// TEXT, BLOB, JSON and spatial columns cannot carry a literal default, so
+// MySQL omits `DEFAULT NULL` for them.
+fn allows_literal_default(column_type: &str) -> bool {
+    let base = column_type
+        .split(|c: char| c == '(' || c.is_whitespace())
+        .next()
+        .unwrap_or_default();
+    !(base.ends_with("text")
+        || base.ends_with("blob")
+        || matches!(
+            base,
+            "json"
+                | "geometry"
+                | "point"
+                | "linestring"
+                | "polygon"
+                | "multipoint"
+                | "multilinestring"
+                | "multipolygon"
+                | "geometrycollection"
+                | "geomcollection"
+        ))
+}
+
+pub(crate) fn charset_of(collation: &str) -> &str {
+    collation.split('_').next().unwrap_or_default()
+}
+
+pub(crate) fn quote_identifier(name: &str) -> String {
+    format!("`{}`", name.replace('`', "``"))
+}
+
+fn quote_identifier_list(names: &[String]) -> String {
+    names
+        .iter()
+        .map(|n| quote_identifier(n))
+        .collect::<Vec<String>>()
+        .join(",")
+}
+
+pub(crate) fn quote_string(s: &str) -> String {
+    format!("'{}'", s.replace('\\', "\\\\").replace('\'', "''"))
+}
+
+#[cfg(test)]
+mod test {
+    use super::{create_table, partition_by};
+    use crate::db::builder::{self, table};
+    use crate::db::store::{
+        ColumnMetadata, ForeignKeyMetadata, IndexMetadata, PartitionMetadata, PartitionStrategy,
+        PartitioningMetadata, TableMetadata,
+    };
+
+    // The column has a default, and textual columns the charset and collation of the table.
+    fn column(
+        name: &str,
+        position: i32,
+        r#type: &str,
+        nullable: bool,
+        default: &str,
+    ) -> ColumnMetadata {
+        let mut column = builder::column(name, position, r#type, nullable);
+        column.default = default.to_string();
+        if r#type.starts_with("varchar") || r#type == "text" {
+            column.character_set = "utf8mb4".to_string();
+            column.collation = "utf8mb4_unicode_ci".to_string();
+        }
+        column
+    }
+
+    fn index(name: &str, expressions: &[&str], key_length: &[i64], unique: bool) -> IndexMetadata {
+        IndexMetadata {
+            key_length: key_length.to_vec(),
+            ..builder::index(name, expressions, name == "PRIMARY", unique)
+        }
+    }
+
+    #[test]
+    fn test_create_table() {
+        let mut customer_id = column("customer_id", 1, "int", false, "AUTO_INCREMENT");
+        customer_id.comment = "Unique customer identifier".to_string();
+        let mut email = column("email", 2, "varchar(255)", false, "");
+        email.collation = "utf8mb4_bin".to_string();
+        let mut updated_at = column("updated_at", 6, "timestamp", true, "CURRENT_TIMESTAMP");
+        updated_at.on_update = Some("CURRENT_TIMESTAMP".to_string());
+
+        let mut idx_hidden = index("idx_hidden", &["city"], &[-1], false);
+        idx_hidden.visible = false;
+
+        let table = TableMetadata {
+            indexes: vec![
+                index("idx_email_prefix", &["email"], &[10], false),
+                idx_hidden,
+                index("email", &["email"], &[-1], true),
+                index("PRIMARY", &["customer_id"], &[-1], true),
+                index("idx_lower_city", &["(lower(`city`))"], &[-1], false),
+            ],
+            engine: "InnoDB".to_string(),
+            collation: Some("utf8mb4_unicode_ci".to_string()),
+            create_options: "row_format=DYNAMIC".to_string(),
+            comment: "Customer's information".to_string(),
+            ..table(
+                "customers",
+                // Columns are rendered by position regardless of their stored order.
+                vec![
+                    column("address", 3, "text", true, "NULL"),
+                    customer_id,
+                    email,
+                    column("city", 4, "varchar(100)", true, "NULL"),
+                    column("country", 5, "varchar(100)", true, "USA"),
+                    updated_at,
+                    column("is_active", 7, "tinyint(1)", true, "1"),
+                    column("code", 8, "varchar(36)", false, "(uuid())"),
+                ],
+            )
+        };
+
+        assert_eq!(
+            create_table(&table),
+            "CREATE TABLE `customers` (
+  `customer_id` int NOT NULL AUTO_INCREMENT COMMENT 'Unique customer identifier',
+  `email` varchar(255) COLLATE utf8mb4_bin NOT NULL,
+  `address` text,
+  `city` varchar(100) DEFAULT NULL,
+  `country` varchar(100) DEFAULT 'USA',
+  `updated_at` timestamp NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+  `is_active` tinyint(1) DEFAULT '1',
+  `code` varchar(36) NOT NULL DEFAULT (uuid()),
+  PRIMARY KEY (`customer_id`),
+  UNIQUE KEY `email` (`email`),
+  KEY `idx_email_prefix` (`email`(10)),
+  KEY `idx_hidden` (`city`) /*!80000 INVISIBLE */,
+  KEY `idx_lower_city` ((lower(`city`)))
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci ROW_FORMAT=DYNAMIC COMMENT='Customer''s information'"
+        );
+    }
+
+    #[test]
+    fn test_create_table_foreign_keys() {
+        let table = TableMetadata {
+            engine: "InnoDB".to_string(),
+            create_options: "partitioned".to_string(),
+            foreign_keys: vec![
+                ForeignKeyMetadata {
+                    name: "fk_order_items_order".to_string(),
+                    columns: vec!["order_id".to_string()],
+                    referenced_schema: String::new(),
+                    referenced_table: "orders".to_string(),
+                    referenced_columns: vec!["order_id".to_string()],
+                    on_delete: "CASCADE".to_string(),
+                    on_update: "NO ACTION".to_string(),
+                    match_type: "NONE".to_string(),
+                },
+                ForeignKeyMetadata {
+                    name: "fk_order_items_product".to_string(),
+                    columns: vec!["product_id".to_string()],
+                    referenced_schema: "catalog".to_string(),
+                    referenced_table: "products".to_string(),
+                    referenced_columns: vec!["product_id".to_string()],
+                    on_delete: "RESTRICT".to_string(),
+                    on_update: "CASCADE".to_string(),
+                    match_type: "NONE".to_string(),
+                },
+            ],
+            ..table(
+                "order_items",
+                vec![
+                    column("order_id", 1, "int", false, ""),
+                    column("product_id", 2, "int", false, ""),
+                ],
+            )
+        };
+
+        assert_eq!(
+            create_table(&table),
+            "CREATE TABLE `order_items` (
+  `order_id` int NOT NULL,
+  `product_id` int NOT NULL,
+  CONSTRAINT `fk_order_items_order` FOREIGN KEY (`order_id`) REFERENCES `orders` (`order_id`) ON DELETE CASCADE,
+  CONSTRAINT `fk_order_items_product` FOREIGN KEY (`product_id`) REFERENCES `catalog`.`products` (`product_id`) ON DELETE RESTRICT ON UPDATE CASCADE
+) ENGINE=InnoDB"
+        );
+    }
+
+    #[test]
+    fn test_create_table_partitions() {
+        let partition = |name: &str, value: &str, subpartitions: &[&str]| PartitionMetadata {
+            name: name.to_string(),
+            value: value.to_string(),
+            subpartitioning: (!subpartitions.is_empty()).then(|| PartitioningMetadata {
+                strategy: PartitionStrategy::Hash,
+                expression: "`order_id`".to_string(),
+                partitions: subpartitions
+                    .iter()
+                    .map(|name| PartitionMetadata {
+                        name: name.to_string(),
+                        value: String::new(),
+                        subpartitioning: None,
+                        row_count: 0,
+                    })
+                    .collect(),
+            }),
+            row_count: 0,
+        };
+        let table = TableMetadata {
+            engine: "InnoDB".to_string(),
+            create_options: "partitioned".to_string(),
+            partitioning: Some(PartitioningMetadata {
+                strategy: PartitionStrategy::Range,
+                expression: "year(`order_date`)".to_string(),
+                partitions: vec![
+                    partition("p2023", "2024", &["p2023sp0", "p2023sp1"]),
+                    partition("pmax", "MAXVALUE", &["pmaxsp0", "pmaxsp1"]),
+                ],
+            }),
+            ..table(
+                "order_archive",
+                vec![
+                    column("order_id", 1, "int", false, ""),
+                    column("order_date", 2, "date", false, ""),
+                ],
+            )
+        };
+
+        assert_eq!(
+            create_table(&table),
+            "CREATE TABLE `order_archive` (
+  `order_id` int NOT NULL,
+  `order_date` date NOT NULL
+) ENGINE=InnoDB
+/*!50100 PARTITION BY RANGE (year(`order_date`))
+SUBPARTITION BY HASH (`order_id`)
+SUBPARTITIONS 2
+(PARTITION p2023 VALUES LESS THAN (2024) ENGINE = InnoDB,
+ PARTITION pmax VALUES LESS THAN MAXVALUE ENGINE = InnoDB) */"
+        );
+
+        // Subpartitions with names of their own are listed.
+        let partitioning = PartitioningMetadata {
+            strategy: PartitionStrategy::ListColumns,
+            expression: "`region`".to_string(),
+            partitions: vec![
+                partition("east", "'ny','nj'", &["s0", "s1"]),
+                partition("west", "'ca'", &["s2", "s3"]),
+            ],
+        };
+        assert_eq!(
+            partition_by(&partitioning, ""),
+            "PARTITION BY LIST COLUMNS (`region`)
+SUBPARTITION BY HASH (`order_id`)
+(PARTITION east VALUES IN ('ny','nj')
+ (SUBPARTITION s0,
+  SUBPARTITION s1),
+ PARTITION west VALUES IN ('ca')
+ (SUBPARTITION s2,
+  SUBPARTITION s3))"
+        );
+
+        let partitioning = PartitioningMetadata {
+            strategy: PartitionStrategy::Key,
+            expression: "`order_id`".to_string(),
+            partitions: vec![partition("p0", "", &[]), partition("p1", "", &[])],
+        };
+        assert_eq!(
+            partition_by(&partitioning, "InnoDB"),
+            "PARTITION BY KEY (`order_id`)\nPARTITIONS 2"
+        );
+    }
+}
@@ -0,0 +1,588 @@
+use super::partition_strategy;
+use crate::db::store::{
+    ColumnMetadata, DependentColumn, ForeignKeyMetadata, IdentityGeneration, IndexMetadata,
+    MaterializedViewMetadata, PartitionMetadata, PartitioningMetadata, SchemaMetadata,
+    TableMetadata, TriggerMetadata, ViewMetadata,
+};
+
+// Renders a schema synced by the PostgreSQL driver as a list of statements,
+// each terminated by a semicolon, which recreate it in an empty database.
+// Tables, each followed by its partitions, come first, then foreign keys,
+// routines, triggers, and finally views
+// and materialized views ordered so that every relation follows its dependencies.
+// Types, sequence options and extensions are not part of the store and have
+// to exist beforehand, sequences referenced by `nextval` defaults are created
+// with their default options.
+pub fn create_schema(schema: &SchemaMetadata) -> Vec<String> {
+    let mut stmts = vec![format!(
+        "CREATE SCHEMA IF NOT EXISTS {};",
+        quote_identifier(&schema.name)
+    )];
+    if !schema.comment.is_empty() {
+        stmts.push(format!(
+            "COMMENT ON SCHEMA {} IS {};",
+            quote_identifier(&schema.name),
+            quote_string(&schema.comment)
+        ));
+    }
+
+    for table in &schema.tables {
+        stmts.extend(create_table(&schema.name, table));
+    }
+
+    for table in &schema.tables {
+        for fk in &table.foreign_keys {
+            stmts.push(add_foreign_key(&schema.name, &table.name, fk));
+        }
+    }
+
+    for function in &schema.functions {
+        stmts.push(routine_definition(&function.definition));
+        if !function.comment.is_empty() {
+            stmts.push(format!(
+                "COMMENT ON FUNCTION {} IS {};",
+                routine_name(&schema.name, &function.name, &function.signature),
+                quote_string(&function.comment)
+            ));
+        }
+    }
+    for procedure in &schema.procedures {
+        stmts.push(routine_definition(&procedure.definition));
+        if !procedure.comment.is_empty() {
+            stmts.push(format!(
+                "COMMENT ON PROCEDURE {} IS {};",
+                routine_name(&schema.name, &procedure.name, &procedure.signature),
+                quote_string(&procedure.comment)
+            ));
+        }
+    }
+
+    for table in &schema.tables {
+        for trigger in &table.triggers {
+            stmts.extend(create_trigger(&schema.name, &table.name, trigger));
+        }
+    }
+
+    stmts.extend(create_views(schema));
+
+    stmts
+}
+
+// Renders a table with its comments, secondary indexes, partitions and the
+// sequences behind its serial columns.
+pub fn create_table(schema_name: &str, table: &TableMetadata) -> Vec<String> {
+    let table_name = qualified_name(schema_name, &table.name);
+
+    let mut columns: Vec<&ColumnMetadata> = table.columns.iter().collect();
+    columns.sort_by_key(|c| c.position);
+
+    let sequences: Vec<(&str, &str)> = columns
+        .iter()
+        .filter_map(|c| sequence_of(&c.default).map(|seq| (seq, c.name.as_str())))
+        .collect();
+
+    let mut stmts: Vec<String> = sequences
+        .iter()
+        .map(|(seq, _)| format!("CREATE SEQUENCE IF NOT EXISTS {seq};"))
+        .collect();
+
+    let mut lines: Vec<String> = columns.iter().map(|c| column_definition(c)).collect();
+    // The key columns of an index are stored as returned by pg_get_indexdef, so they are already quoted.
+    lines.extend(table.indexes.iter().filter(|idx| idx.primary).map(|idx| {
+        format!(
+            "CONSTRAINT {} PRIMARY KEY ({})",
+            quote_identifier(&idx.name),
+            idx.expressions.join(", ")
+        )
+    }));
+    stmts.push(format!(
+        "CREATE TABLE {table_name} (\n    {}\n){};",
+        lines.join(",\n    "),
+        partition_by(table.partitioning.as_ref())
+    ));
+    if let Some(partitioning) = &table.partitioning {
+        stmts.extend(create_partitions(schema_name, &table.name, partitioning));
+    }
+
+    for (seq, column) in sequences {
+        stmts.push(format!(
+            "ALTER SEQUENCE {seq} OWNED BY {table_name}.{};",
+            quote_identifier(column)
+        ));
+    }
+
+    if !table.comment.is_empty() {
+        stmts.push(format!(
+            "COMMENT ON TABLE {table_name} IS {};",
+            quote_string(&table.comment)
+        ));
+    }
+    for column in columns.iter().filter(|c| !c.comment.is_empty()) {
+        stmts.push(format!(
+            "COMMENT ON COLUMN {table_name}.{} IS {};",
+            quote_identifier(&column.name),
+            quote_string(&column.comment)
+        ));
+    }
+
+    for index in &table.indexes {
+        if !index.primary {
+            stmts.push(create_index(index, table.partitioning.is_some()));
+        }
+        if !index.comment.is_empty() {
+            stmts.push(format!(
+                "COMMENT ON INDEX {} IS {};",
+                qualified_name(schema_name, &index.name),
+                quote_string(&index.comment)
+            ));
+        }
+    }
+
+    stmts
+}
+
+// Returns the PARTITION BY clause of a partitioned table preceded by a space, or
+// nothing for a table that is not partitioned.
+fn partition_by(partitioning: Option<&PartitioningMetadata>) -> String {
+    match partitioning {
+        Some(p) => format!(
+            " PARTITION BY {} ({})",
+            partition_strategy(&p.strategy),
+            p.expression
+        ),
+        None => String::new(),
+    }
+}
+
+// Renders the partitions of a partitioned table as tables attached to it.
+fn create_partitions(
+    schema_name: &str,
+    table_name: &str,
+    partitioning: &PartitioningMetadata,
+) -> Vec<String> {
+    partitioning
+        .partitions
+        .iter()
+        .flat_map(|p| create_partition(schema_name, table_name, p))
+        .collect()
+}
+
+// Renders a partition attached to its parent table, a partitioned partition is
+// followed by its own partitions.
+pub(crate) fn create_partition(
+    schema_name: &str,
+    table_name: &str,
+    partition: &PartitionMetadata,
+) -> Vec<String> {
+    let (schema, name) = partition_name(schema_name, partition);
+    let mut stmts = vec![format!(
+        "CREATE TABLE {} PARTITION OF {} {}{};",
+        qualified_name(schema, name),
+        qualified_name(schema_name, table_name),
+        partition.value,
+        partition_by(partition.subpartitioning.as_ref())
+    )];
+    if let Some(subpartitioning) = &partition.subpartitioning {
+        stmts.extend(create_partitions(schema, name, subpartitioning));
+    }
+    stmts
+}
+
+// Returns the schema and the name of a partition, the name of a partition in
+// another schema than its parent is qualified.
+pub(crate) fn partition_name<'a>(
+    schema_name: &'a str,
+    partition: &'a PartitionMetadata,
+) -> (&'a str, &'a str) {
+    partition
+        .name
+        .split_once('.')
+        .unwrap_or((schema_name, &partition.name))
+}
+
+// pg_get_indexdef names a partitioned table with ONLY, which creates an invalid index that
+// reaches no partition, so ONLY is left out to index the partitions as well.
+pub(crate) fn create_index(index: &IndexMetadata, partitioned: bool) -> String {
+    if partitioned {
+        format!("{};", index.definition.replacen(" ON ONLY ", " ON ", 1))
+    } else {
+        format!("{};", index.definition)
+    }
+}
+
+pub(crate) fn column_definition(column: &ColumnMetadata) -> String {
+    let mut def = format!("{} {}", quote_identifier(&column.name), column.r#type);
+
+    match column.identity_generation {
+        IdentityGeneration::Always => def.push_str(" GENERATED ALWAYS AS IDENTITY"),
+        IdentityGeneration::ByDefault => def.push_str(" GENERATED BY DEFAULT AS IDENTITY"),
+        IdentityGeneration::UNSPECIFIED => {}
+    }
+    if !column.collation.is_empty() {
+        def.push_str(&format!(" COLLATE {}", quote_identifier(&column.collation)));
+    }
+    if !column.nullable {
+        def.push_str(" NOT NULL");
+    }
+    if !column.default.is_empty() {
+        def.push_str(&format!(" DEFAULT {}", column.default));
+    }
+
+    def
+}
+
+pub(crate) fn add_foreign_key(
+    schema_name: &str,
+    table_name: &str,
+    fk: &ForeignKeyMetadata,
+) -> String {
+    let mut def = format!(
+        "ALTER TABLE {} ADD CONSTRAINT {} FOREIGN KEY ({}) REFERENCES {} ({})",
+        qualified_name(schema_name, table_name),
+        quote_identifier(&fk.name),
+        quote_identifier_list(&fk.columns),
+        qualified_name(&fk.referenced_schema, &fk.referenced_table),
+        quote_identifier_list(&fk.referenced_columns)
+    );
+
+    if !fk.match_type.is_empty() && fk.match_type != "SIMPLE" {
+        def.push_str(&format!(" MATCH {}", fk.match_type));
+    }
+    if !fk.on_update.is_empty() && fk.on_update != "NO ACTION" {
+        def.push_str(&format!(" ON UPDATE {}", fk.on_update));
+    }
+    if !fk.on_delete.is_empty() && fk.on_delete != "NO ACTION" {
+        def.push_str(&format!(" ON DELETE {}", fk.on_delete));
+    }
+    def.push(';');
+
+    def
+}
+
+// The definition is the output of pg_get_functiondef or pg_get_triggerdef, which have no
+// terminating semicolon.
+fn routine_definition(definition: &str) -> String {
+    format!("{};", definition.trim_end())
+}
+
+// Renders a trigger from the output of pg_get_triggerdef, a disabled trigger is
+// disabled once created.
+pub(crate) fn create_trigger(
+    schema_name: &str,
+    table_name: &str,
+    trigger: &TriggerMetadata,
+) -> Vec<String> {
+    let mut stmts = vec![routine_definition(&trigger.definition)];
+    if !trigger.enabled {
+        stmts.push(format!(
+            "ALTER TABLE {} DISABLE TRIGGER {};",
+            qualified_name(schema_name, table_name),
+            quote_identifier(&trigger.name)
+        ));
+    }
+    stmts
+}
+
+// Renders views and materialized views, a relation is emitted once every
+// relation of the same schema it depends on has been emitted.
+fn create_views(schema: &SchemaMetadata) -> Vec<String> {
+    let mut relations: Vec<(&str, Vec<&str>)> = schema
+        .views
+        .iter()
+        .map(|v| {
+            (
+                v.name.as_str(),
+                dependencies(&schema.name, &v.dependent_columns),
+            )
+        })
+        .collect();
+    relations.extend(schema.materialized_views.iter().map(|v| {
+        (
+            v.name.as_str(),
+            dependencies(&schema.name, &v.dependent_columns),
+        )
+    }));
+
+    let mut stmts = vec![];
+    for name in super::dependency_order(&relations) {
+        if let Some(view) = schema.views.iter().find(|v| v.name == name) {
+            stmts.extend(create_view(&schema.name, view));
+        } else if let Some(view) = schema.materialized_views.iter().find(|v| v.name == name) {
+            stmts.extend(create_materialized_view(&schema.name, view));
+        }
+    }
+
+    stmts
+}
+
+pub(crate) fn create_view(schema_name: &str, view: &ViewMetadata) -> Vec<String> {
+    view_statements(
+        "VIEW",
+        &qualified_name(schema_name, &view.name),
+        &view.definition,
+        &view.comment,
+    )
+}
+
+pub(crate) fn create_materialized_view(
+    schema_name: &str,
+    view: &MaterializedViewMetadata,
+) -> Vec<String> {
+    view_statements(
+        "MATERIALIZED VIEW",
+        &qualified_name(schema_name, &view.name),
+        &view.definition,
+        &view.comment,
+    )
+}
+
+fn view_statements(kind: &str, view_name: &str, definition: &str, comment: &str) -> Vec<String> {
+    // pg_get_viewdef terminates the query with a semicolon.
+    let query = definition.trim().trim_end_matches(';');
+    let mut stmts = vec![format!("CREATE {kind} {view_name} AS\n{query};")];
+    if !comment.is_empty() {
+        stmts.push(format!(
+            "COMMENT ON {kind} {view_name} IS {};",
+            quote_string(comment)
+        ));
+    }
+    stmts
+}
+
+// Returns the relations of the schema the columns belong to.
+pub(crate) fn dependencies<'a>(schema_name: &str, columns: &'a [DependentColumn]) -> Vec<&'a str> {
+    let mut tables: Vec<&str> = columns
+        .iter()
+        .filter(|c| c.schema == schema_name)
+        .map(|c| c.table.as_str())
+        .collect();
+    tables.sort();
+    tables.dedup();
+    tables
+}
+
+// Returns the sequence name of a `nextval('name'::regclass)` default.
+pub(crate) fn sequence_of(default: &str) -> Option<&str> {
+    default
+        .strip_prefix("nextval('")?
+        .strip_suffix("'::regclass)")
+}
+
+pub(crate) fn qualified_name(schema: &str, name: &str) -> String {
+    if schema.is_empty() {
+        quote_identifier(name)
+    } else {
+        format!("{}.{}", quote_identifier(schema), quote_identifier(name))
+    }
+}
+
+// Renders the name of a routine with the argument types of its signature, which pick an overload.
+pub(crate) fn routine_name(schema: &str, name: &str, signature: &str) -> String {
+    let arguments = signature
+        .strip_prefix(name)
+        .filter(|args| args.starts_with('('))
+        .unwrap_or("()");
+    format!("{}{arguments}", qualified_name(schema, name))
+}
+
+pub(crate) fn quote_identifier(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+pub(crate) fn quote_identifier_list(names: &[String]) -> String {
+    names
+        .iter()
+        .map(|n| quote_identifier(n))
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+pub(crate) fn quote_string(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "''"))
+}
+
+#[cfg(test)]
+mod test {
+    use super::create_schema;
+    use crate::db::builder::{column, index, schema, table};
+    use crate::db::store::{
+        DependentColumn, ForeignKeyMetadata, IdentityGeneration, IndexMetadata,
+        MaterializedViewMetadata, PartitionMetadata, PartitionStrategy, PartitioningMetadata,
+        SchemaMetadata, TableMetadata, TriggerMetadata, ViewMetadata,
+    };
+
+    fn primary_key(name: &str, column: &str) -> IndexMetadata {
+        IndexMetadata {
+            r#type: "btree".to_string(),
+            definition: format!("CREATE UNIQUE INDEX {name} ON sales.t USING btree ({column})"),
+            ..index(name, &[column], true, true)
+        }
+    }
+
+    fn depends_on(table: &str) -> Vec<DependentColumn> {
+        vec![DependentColumn {
+            schema: "sales".to_string(),
+            table: table.to_string(),
+            column: "id".to_string(),
+        }]
+    }
+
+    #[test]
+    fn test_create_schema() {
+        let mut customer_id = column("customer_id", 1, "integer", false);
+        customer_id.default = "nextval('sales.customers_customer_id_seq'::regclass)".to_string();
+        let mut email = column("email", 2, "character varying(255)", false);
+        email.comment = "Customer's email".to_string();
+        email.collation = "C".to_string();
+
+        let mut customers = TableMetadata {
+            indexes: vec![
+                primary_key("customers_pkey", "customer_id"),
+                IndexMetadata {
+                    r#type: "btree".to_string(),
+                    comment: "Lookup by email".to_string(),
+                    definition:
+                        "CREATE INDEX idx_customers_email ON sales.customers USING btree (email)"
+                            .to_string(),
+                    ..index("idx_customers_email", &["email"], false, false)
+                },
+            ],
+            ..table("customers", vec![email, customer_id])
+        };
+        customers.comment = "Customer information".to_string();
+
+        let mut order_id = column("order_id", 1, "bigint", false);
+        order_id.identity_generation = IdentityGeneration::Always;
+        let mut orders = TableMetadata {
+            indexes: vec![primary_key("orders_pkey", "order_id")],
+            ..table(
+                "orders",
+                vec![order_id, column("customer_id", 2, "integer", true)],
+            )
+        };
+        orders.foreign_keys.push(ForeignKeyMetadata {
+            name: "fk_orders_customer".to_string(),
+            columns: vec!["customer_id".to_string()],
+            referenced_schema: "sales".to_string(),
+            referenced_table: "customers".to_string(),
+            referenced_columns: vec!["customer_id".to_string()],
+            on_delete: "CASCADE".to_string(),
+            on_update: "NO ACTION".to_string(),
+            match_type: "SIMPLE".to_string(),
+        });
+        orders.triggers.push(TriggerMetadata {
+            name: "trg_audit".to_string(),
+            timing: "AFTER".to_string(),
+            events: vec!["DELETE".to_string()],
+            for_each_row: true,
+            definition: "CREATE TRIGGER trg_audit AFTER DELETE ON sales.orders FOR EACH ROW EXECUTE FUNCTION sales.audit()".to_string(),
+            enabled: false,
+        });
+
+        let partition = |name: &str, value: &str| PartitionMetadata {
+            name: name.to_string(),
+            value: value.to_string(),
+            subpartitioning: None,
+            row_count: 0,
+        };
+        // pg_get_indexdef names the partitioned table with ONLY.
+        let event_index = IndexMetadata {
+            r#type: "btree".to_string(),
+            definition:
+                "CREATE INDEX idx_order_events_event ON ONLY sales.order_events USING btree (event_id)"
+                    .to_string(),
+            ..index("idx_order_events_event", &["event_id"], false, false)
+        };
+        let mut order_events = TableMetadata {
+            indexes: vec![event_index],
+            ..table(
+                "order_events",
+                vec![column("event_id", 1, "integer", false)],
+            )
+        };
+        order_events.partitioning = Some(PartitioningMetadata {
+            strategy: PartitionStrategy::Range,
+            expression: "event_id".to_string(),
+            partitions: vec![
+                partition(
+                    "archive.order_events_old",
+                    "FOR VALUES FROM (MINVALUE) TO (100)",
+                ),
+                PartitionMetadata {
+                    subpartitioning: Some(PartitioningMetadata {
+                        strategy: PartitionStrategy::Hash,
+                        expression: "event_id".to_string(),
+                        partitions: vec![partition(
+                            "order_events_new_0",
+                            "FOR VALUES WITH (modulus 1, remainder 0)",
+                        )],
+                    }),
+                    ..partition("order_events_new", "DEFAULT")
+                },
+            ],
+        });
+
+        let schema = SchemaMetadata {
+            // The summary sorts first but depends on the totals view.
+            views: vec![
+                ViewMetadata {
+                    name: "order_totals".to_string(),
+                    definition: " SELECT order_id AS id\n   FROM sales.orders;".to_string(),
+                    comment: String::new(),
+                    dependent_columns: depends_on("orders"),
+                },
+                ViewMetadata {
+                    name: "a_summary".to_string(),
+                    definition: " SELECT id\n   FROM sales.order_totals;".to_string(),
+                    comment: "Summary".to_string(),
+                    dependent_columns: depends_on("order_totals"),
+                },
+            ],
+            materialized_views: vec![MaterializedViewMetadata {
+                name: "b_monthly".to_string(),
+                definition: " SELECT id\n   FROM sales.a_summary;".to_string(),
+                comment: String::new(),
+                dependent_columns: depends_on("a_summary"),
+            }],
+            owner: "postgres".to_string(),
+            ..schema("sales", vec![customers, orders, order_events])
+        };
+
+        assert_eq!(
+            create_schema(&schema),
+            vec![
+                r#"CREATE SCHEMA IF NOT EXISTS "sales";"#,
+                "CREATE SEQUENCE IF NOT EXISTS sales.customers_customer_id_seq;",
+                r#"CREATE TABLE "sales"."customers" (
+    "customer_id" integer NOT NULL DEFAULT nextval('sales.customers_customer_id_seq'::regclass),
+    "email" character varying(255) COLLATE "C" NOT NULL,
+    CONSTRAINT "customers_pkey" PRIMARY KEY (customer_id)
+);"#,
+                r#"ALTER SEQUENCE sales.customers_customer_id_seq OWNED BY "sales"."customers"."customer_id";"#,
+                r#"COMMENT ON TABLE "sales"."customers" IS 'Customer information';"#,
+                r#"COMMENT ON COLUMN "sales"."customers"."email" IS 'Customer''s email';"#,
+                "CREATE INDEX idx_customers_email ON sales.customers USING btree (email);",
+                r#"COMMENT ON INDEX "sales"."idx_customers_email" IS 'Lookup by email';"#,
+                r#"CREATE TABLE "sales"."orders" (
+    "order_id" bigint GENERATED ALWAYS AS IDENTITY NOT NULL,
+    "customer_id" integer,
+    CONSTRAINT "orders_pkey" PRIMARY KEY (order_id)
+);"#,
+                r#"CREATE TABLE "sales"."order_events" (
+    "event_id" integer NOT NULL
+) PARTITION BY RANGE (event_id);"#,
+                r#"CREATE TABLE "archive"."order_events_old" PARTITION OF "sales"."order_events" FOR VALUES FROM (MINVALUE) TO (100);"#,
+                r#"CREATE TABLE "sales"."order_events_new" PARTITION OF "sales"."order_events" DEFAULT PARTITION BY HASH (event_id);"#,
+                r#"CREATE TABLE "sales"."order_events_new_0" PARTITION OF "sales"."order_events_new" FOR VALUES WITH (modulus 1, remainder 0);"#,
+                "CREATE INDEX idx_order_events_event ON sales.order_events USING btree (event_id);",
+                r#"ALTER TABLE "sales"."orders" ADD CONSTRAINT "fk_orders_customer" FOREIGN KEY ("customer_id") REFERENCES "sales"."customers" ("customer_id") ON DELETE CASCADE;"#,
+                "CREATE TRIGGER trg_audit AFTER DELETE ON sales.orders FOR EACH ROW EXECUTE FUNCTION sales.audit();",
+                r#"ALTER TABLE "sales"."orders" DISABLE TRIGGER "trg_audit";"#,
+                "CREATE VIEW \"sales\".\"order_totals\" AS\nSELECT order_id AS id\n   FROM sales.orders;",
+                "CREATE VIEW \"sales\".\"a_summary\" AS\nSELECT id\n   FROM sales.order_totals;",
+                r#"COMMENT ON VIEW "sales"."a_summary" IS 'Summary';"#,
+                "CREATE MATERIALIZED VIEW \"sales\".\"b_monthly\" AS\nSELECT id\n   FROM sales.a_summary;",
+            ]
+        );
+    }
+}
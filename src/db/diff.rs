@@ -0,0 +1,1095 @@
+use super::store;
+use std::fmt::{Display, Formatter};
+
+// Change is a single difference between two database schemas.
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Change {
+    // The schema is the schema of the changed object.
+    // It is an empty string for databases without such concept such as MySQL.
+    pub schema: String,
+    // The table is the table, view or materialized view the change belongs to.
+    // It is an empty string for schema level objects such as functions, procedures and events.
+    pub table: String,
+    // The kind is what has changed.
+    pub kind: ChangeKind,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ChangeKind {
+    SchemaAdded,
+    SchemaDropped,
+    TableAdded(Box<store::TableMetadata>),
+    TableDropped(Box<store::TableMetadata>),
+    // A dropped and an added table share the same columns, the table may have been renamed.
+    // The table of the change is the new name.
+    TableRenameCandidate {
+        old: Box<store::TableMetadata>,
+        new: Box<store::TableMetadata>,
+    },
+    TableChanged {
+        old: Box<store::TableMetadata>,
+        new: Box<store::TableMetadata>,
+        attributes: Vec<TableAttribute>,
+    },
+    ColumnAdded(store::ColumnMetadata),
+    ColumnDropped(store::ColumnMetadata),
+    ColumnChanged {
+        old: Box<store::ColumnMetadata>,
+        new: Box<store::ColumnMetadata>,
+        attributes: Vec<ColumnAttribute>,
+    },
+    IndexAdded(store::IndexMetadata),
+    IndexDropped(store::IndexMetadata),
+    IndexChanged {
+        old: Box<store::IndexMetadata>,
+        new: Box<store::IndexMetadata>,
+    },
+    ForeignKeyAdded(store::ForeignKeyMetadata),
+    ForeignKeyDropped(store::ForeignKeyMetadata),
+    ForeignKeyChanged {
+        old: Box<store::ForeignKeyMetadata>,
+        new: Box<store::ForeignKeyMetadata>,
+    },
+    TriggerAdded(store::TriggerMetadata),
+    TriggerDropped(store::TriggerMetadata),
+    TriggerChanged {
+        old: Box<store::TriggerMetadata>,
+        new: Box<store::TriggerMetadata>,
+    },
+    ViewAdded(store::ViewMetadata),
+    ViewDropped(store::ViewMetadata),
+    ViewChanged {
+        old: store::ViewMetadata,
+        new: store::ViewMetadata,
+    },
+    MaterializedViewAdded(store::MaterializedViewMetadata),
+    MaterializedViewDropped(store::MaterializedViewMetadata),
+    MaterializedViewChanged {
+        old: store::MaterializedViewMetadata,
+        new: store::MaterializedViewMetadata,
+    },
+    FunctionAdded(store::FunctionMetadata),
+    FunctionDropped(store::FunctionMetadata),
+    FunctionChanged {
+        old: store::FunctionMetadata,
+        new: store::FunctionMetadata,
+    },
+    ProcedureAdded(store::ProcedureMetadata),
+    ProcedureDropped(store::ProcedureMetadata),
+    ProcedureChanged {
+        old: store::ProcedureMetadata,
+        new: store::ProcedureMetadata,
+    },
+    EventAdded(store::EventMetadata),
+    EventDropped(store::EventMetadata),
+    EventChanged {
+        old: Box<store::EventMetadata>,
+        new: Box<store::EventMetadata>,
+    },
+}
+
+// TableAttribute is a table level attribute compared by the diff.
+// Statistics such as row_count and the owner are not compared.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TableAttribute {
+    Engine,
+    Collation,
+    CreateOptions,
+    Comment,
+    // The partitioning compares the strategy, key and partitions, not their row counts.
+    Partitioning,
+}
+
+// ColumnAttribute is a column attribute compared by the diff.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ColumnAttribute {
+    Type,
+    Default,
+    OnUpdate,
+    Nullable,
+    CharacterSet,
+    Collation,
+    Comment,
+    IdentityGeneration,
+}
+
+// DiffOptions tunes what the diff compares.
+#[derive(Clone, Copy, Default, Debug)]
+pub struct DiffOptions {
+    // The ignore_definitions leaves out the definitions of views, materialized views, routines,
+    // triggers and events, the key expressions of expression indexes and partition keys. A parsed
+    // schema file keeps them as written while the server deparses them, so they differ between a
+    // file and a synced database even when the objects are the same.
+    pub ignore_definitions: bool,
+    // The ignore_owners leaves out the owners of routines and the definers of events, which a
+    // schema file rarely sets.
+    pub ignore_owners: bool,
+}
+
+// diff_database returns the changes that turn the source database into the target database.
+// Objects are matched by name, functions and procedures by signature when it is set.
+pub fn diff_database(
+    source: &store::DatabaseSchemaMetadata,
+    target: &store::DatabaseSchemaMetadata,
+) -> Vec<Change> {
+    diff_database_with(source, target, &DiffOptions::default())
+}
+
+// diff_database_with is diff_database comparing what the options ask for.
+pub fn diff_database_with(
+    source: &store::DatabaseSchemaMetadata,
+    target: &store::DatabaseSchemaMetadata,
+    options: &DiffOptions,
+) -> Vec<Change> {
+    let mut changes = vec![];
+
+    for old in &source.schemas {
+        match target.schemas.iter().find(|s| s.name == old.name) {
+            Some(new) => diff_schema(&mut changes, old, new, options),
+            None => {
+                diff_schema(&mut changes, old, &empty_schema(&old.name), options);
+                changes.push(Change {
+                    schema: old.name.clone(),
+                    table: String::new(),
+                    kind: ChangeKind::SchemaDropped,
+                });
+            }
+        }
+    }
+
+    for new in &target.schemas {
+        if !source.schemas.iter().any(|s| s.name == new.name) {
+            changes.push(Change {
+                schema: new.name.clone(),
+                table: String::new(),
+                kind: ChangeKind::SchemaAdded,
+            });
+            diff_schema(&mut changes, &empty_schema(&new.name), new, options);
+        }
+    }
+
+    changes
+}
+
+fn empty_schema(name: &str) -> store::SchemaMetadata {
+    store::SchemaMetadata {
+        name: name.to_string(),
+        tables: vec![],
+        external_tables: vec![],
+        views: vec![],
+        functions: vec![],
+        procedures: vec![],
+        materialized_views: vec![],
+        events: vec![],
+        owner: String::new(),
+        comment: String::new(),
+    }
+}
+
+fn diff_schema(
+    changes: &mut Vec<Change>,
+    old: &store::SchemaMetadata,
+    new: &store::SchemaMetadata,
+    options: &DiffOptions,
+) {
+    let schema = &new.name;
+    let same_definition = |old: &str, new: &str| options.ignore_definitions || old == new;
+    let change = |table: &str, kind: ChangeKind| Change {
+        schema: schema.clone(),
+        table: table.to_string(),
+        kind,
+    };
+
+    let mut dropped_tables: Vec<&store::TableMetadata> = old
+        .tables
+        .iter()
+        .filter(|t| !new.tables.iter().any(|n| n.name == t.name))
+        .collect();
+    let mut added_tables = vec![];
+    for table in &new.tables {
+        if !old.tables.iter().any(|o| o.name == table.name) {
+            match dropped_tables
+                .iter()
+                .position(|d| same_columns(&d.columns, &table.columns))
+            {
+                Some(idx) => {
+                    let dropped = dropped_tables.remove(idx);
+                    changes.push(change(
+                        &table.name,
+                        ChangeKind::TableRenameCandidate {
+                            old: Box::new(dropped.clone()),
+                            new: Box::new(table.clone()),
+                        },
+                    ));
+                }
+                None => added_tables.push(table),
+            }
+        }
+    }
+    for table in dropped_tables {
+        changes.push(change(
+            &table.name,
+            ChangeKind::TableDropped(Box::new(table.clone())),
+        ));
+    }
+    for table in added_tables {
+        changes.push(change(
+            &table.name,
+            ChangeKind::TableAdded(Box::new(table.clone())),
+        ));
+    }
+    for old_table in &old.tables {
+        if let Some(new_table) = new.tables.iter().find(|t| t.name == old_table.name) {
+            diff_table(changes, schema, old_table, new_table, options);
+        }
+    }
+
+    let (dropped, added, both) = match_by_key(&old.views, &new.views, |v| v.name.clone());
+    for v in dropped {
+        changes.push(change(&v.name, ChangeKind::ViewDropped(v.clone())));
+    }
+    for v in added {
+        changes.push(change(&v.name, ChangeKind::ViewAdded(v.clone())));
+    }
+    for (o, n) in both {
+        if !same_definition(&o.definition, &n.definition) || o.comment != n.comment {
+            changes.push(change(
+                &n.name,
+                ChangeKind::ViewChanged {
+                    old: o.clone(),
+                    new: n.clone(),
+                },
+            ));
+        }
+    }
+
+    let (dropped, added, both) =
+        match_by_key(&old.materialized_views, &new.materialized_views, |v| {
+            v.name.clone()
+        });
+    for v in dropped {
+        changes.push(change(
+            &v.name,
+            ChangeKind::MaterializedViewDropped(v.clone()),
+        ));
+    }
+    for v in added {
+        changes.push(change(
+            &v.name,
+            ChangeKind::MaterializedViewAdded(v.clone()),
+        ));
+    }
+    for (o, n) in both {
+        if !same_definition(&o.definition, &n.definition) || o.comment != n.comment {
+            changes.push(change(
+                &n.name,
+                ChangeKind::MaterializedViewChanged {
+                    old: o.clone(),
+                    new: n.clone(),
+                },
+            ));
+        }
+    }
+
+    let (dropped, added, both) = match_by_key(&old.functions, &new.functions, |f| {
+        routine_key(&f.name, &f.signature)
+    });
+    for f in dropped {
+        changes.push(change("", ChangeKind::FunctionDropped(f.clone())));
+    }
+    for f in added {
+        changes.push(change("", ChangeKind::FunctionAdded(f.clone())));
+    }
+    for (o, n) in both {
+        let same = same_definition(&o.definition, &n.definition)
+            && o.return_type == n.return_type
+            && o.parameters == n.parameters
+            && o.language == n.language
+            && o.volatility == n.volatility
+            && o.security == n.security
+            && (options.ignore_owners || o.owner == n.owner)
+            && o.comment == n.comment;
+        if !same {
+            changes.push(change(
+                "",
+                ChangeKind::FunctionChanged {
+                    old: o.clone(),
+                    new: n.clone(),
+                },
+            ));
+        }
+    }
+
+    let (dropped, added, both) = match_by_key(&old.procedures, &new.procedures, |p| {
+        routine_key(&p.name, &p.signature)
+    });
+    for p in dropped {
+        changes.push(change("", ChangeKind::ProcedureDropped(p.clone())));
+    }
+    for p in added {
+        changes.push(change("", ChangeKind::ProcedureAdded(p.clone())));
+    }
+    for (o, n) in both {
+        let same = same_definition(&o.definition, &n.definition)
+            && o.parameters == n.parameters
+            && o.language == n.language
+            && o.volatility == n.volatility
+            && o.security == n.security
+            && (options.ignore_owners || o.owner == n.owner)
+            && o.comment == n.comment;
+        if !same {
+            changes.push(change(
+                "",
+                ChangeKind::ProcedureChanged {
+                    old: o.clone(),
+                    new: n.clone(),
+                },
+            ));
+        }
+    }
+
+    let (dropped, added, both) = match_by_key(&old.events, &new.events, |e| e.name.clone());
+    for e in dropped {
+        changes.push(change("", ChangeKind::EventDropped(e.clone())));
+    }
+    for e in added {
+        changes.push(change("", ChangeKind::EventAdded(e.clone())));
+    }
+    for (o, n) in both {
+        let same = same_definition(&o.definition, &n.definition)
+            && o.schedule == n.schedule
+            && o.time_zone == n.time_zone
+            && o.status == n.status
+            && o.on_completion == n.on_completion
+            && (options.ignore_owners || o.definer == n.definer)
+            && o.body == n.body
+            && o.comment == n.comment;
+        if !same {
+            changes.push(change(
+                "",
+                ChangeKind::EventChanged {
+                    old: Box::new(o.clone()),
+                    new: Box::new(n.clone()),
+                },
+            ));
+        }
+    }
+}
+
+fn diff_table(
+    changes: &mut Vec<Change>,
+    schema: &str,
+    old: &store::TableMetadata,
+    new: &store::TableMetadata,
+    options: &DiffOptions,
+) {
+    let change = |kind: ChangeKind| Change {
+        schema: schema.to_string(),
+        table: new.name.clone(),
+        kind,
+    };
+
+    let mut attributes = vec![];
+    if old.engine != new.engine {
+        attributes.push(TableAttribute::Engine);
+    }
+    if old.collation != new.collation {
+        attributes.push(TableAttribute::Collation);
+    }
+    if old.create_options != new.create_options {
+        attributes.push(TableAttribute::CreateOptions);
+    }
+    if old.comment != new.comment {
+        attributes.push(TableAttribute::Comment);
+    }
+    if !same_partitioning(
+        old.partitioning.as_ref(),
+        new.partitioning.as_ref(),
+        options,
+    ) {
+        attributes.push(TableAttribute::Partitioning);
+    }
+    if !attributes.is_empty() {
+        changes.push(change(ChangeKind::TableChanged {
+            old: Box::new(old.clone()),
+            new: Box::new(new.clone()),
+            attributes,
+        }));
+    }
+
+    let (dropped, added, both) = match_by_key(&old.columns, &new.columns, |c| c.name.clone());
+    for c in dropped {
+        changes.push(change(ChangeKind::ColumnDropped(c.clone())));
+    }
+    for c in added {
+        changes.push(change(ChangeKind::ColumnAdded(c.clone())));
+    }
+    for (o, n) in both {
+        let attributes = diff_column(o, n);
+        if !attributes.is_empty() {
+            changes.push(change(ChangeKind::ColumnChanged {
+                old: Box::new(o.clone()),
+                new: Box::new(n.clone()),
+                attributes,
+            }));
+        }
+    }
+
+    let (dropped, added, both) = match_by_key(&old.indexes, &new.indexes, |i| i.name.clone());
+    for i in dropped {
+        changes.push(change(ChangeKind::IndexDropped(i.clone())));
+    }
+    for i in added {
+        changes.push(change(ChangeKind::IndexAdded(i.clone())));
+    }
+    for (o, n) in both {
+        if !same_index(o, n, options) {
+            changes.push(change(ChangeKind::IndexChanged {
+                old: Box::new(o.clone()),
+                new: Box::new(n.clone()),
+            }));
+        }
+    }
+
+    let (dropped, added, both) =
+        match_by_key(&old.foreign_keys, &new.foreign_keys, |f| f.name.clone());
+    for f in dropped {
+        changes.push(change(ChangeKind::ForeignKeyDropped(f.clone())));
+    }
+    for f in added {
+        changes.push(change(ChangeKind::ForeignKeyAdded(f.clone())));
+    }
+    for (o, n) in both {
+        if o != n {
+            changes.push(change(ChangeKind::ForeignKeyChanged {
+                old: Box::new(o.clone()),
+                new: Box::new(n.clone()),
+            }));
+        }
+    }
+
+    let (dropped, added, both) = match_by_key(&old.triggers, &new.triggers, |t| t.name.clone());
+    for t in dropped {
+        changes.push(change(ChangeKind::TriggerDropped(t.clone())));
+    }
+    for t in added {
+        changes.push(change(ChangeKind::TriggerAdded(t.clone())));
+    }
+    for (o, n) in both {
+        let same = (options.ignore_definitions || o.definition == n.definition)
+            && o.timing == n.timing
+            && o.events == n.events
+            && o.for_each_row == n.for_each_row
+            && o.enabled == n.enabled;
+        if !same {
+            changes.push(change(ChangeKind::TriggerChanged {
+                old: Box::new(o.clone()),
+                new: Box::new(n.clone()),
+            }));
+        }
+    }
+}
+
+fn diff_column(old: &store::ColumnMetadata, new: &store::ColumnMetadata) -> Vec<ColumnAttribute> {
+    let mut attributes = vec![];
+    if old.r#type != new.r#type {
+        attributes.push(ColumnAttribute::Type);
+    }
+    if old.default != new.default {
+        attributes.push(ColumnAttribute::Default);
+    }
+    if old.on_update != new.on_update {
+        attributes.push(ColumnAttribute::OnUpdate);
+    }
+    if old.nullable != new.nullable {
+        attributes.push(ColumnAttribute::Nullable);
+    }
+    if old.character_set != new.character_set {
+        attributes.push(ColumnAttribute::CharacterSet);
+    }
+    if old.collation != new.collation {
+        attributes.push(ColumnAttribute::Collation);
+    }
+    if old.comment != new.comment {
+        attributes.push(ColumnAttribute::Comment);
+    }
+    if old.identity_generation != new.identity_generation {
+        attributes.push(ColumnAttribute::IdentityGeneration);
+    }
+    attributes
+}
+
+// same_partitioning compares two partitionings and their partitions in order, recursing into
+// subpartitions. The row counts are statistics and the key expression is a definition.
+fn same_partitioning(
+    old: Option<&store::PartitioningMetadata>,
+    new: Option<&store::PartitioningMetadata>,
+    options: &DiffOptions,
+) -> bool {
+    match (old, new) {
+        (None, None) => true,
+        (Some(o), Some(n)) => {
+            o.strategy == n.strategy
+                && (options.ignore_definitions || o.expression == n.expression)
+                && o.partitions.len() == n.partitions.len()
+                && o.partitions.iter().zip(&n.partitions).all(|(o, n)| {
+                    o.name == n.name
+                        && o.value == n.value
+                        && same_partitioning(
+                            o.subpartitioning.as_ref(),
+                            n.subpartitioning.as_ref(),
+                            options,
+                        )
+                })
+        }
+        _ => false,
+    }
+}
+
+// match_by_key splits two lists into the old items missing in new, the new items missing in old
+// and the pairs present in both, each in the order of its list.
+fn match_by_key<'a, T, K: PartialEq>(
+    old: &'a [T],
+    new: &'a [T],
+    key: impl Fn(&T) -> K,
+) -> (Vec<&'a T>, Vec<&'a T>, Vec<(&'a T, &'a T)>) {
+    let dropped = old
+        .iter()
+        .filter(|o| !new.iter().any(|n| key(n) == key(o)))
+        .collect();
+    let added = new
+        .iter()
+        .filter(|n| !old.iter().any(|o| key(o) == key(n)))
+        .collect();
+    let both = old
+        .iter()
+        .filter_map(|o| new.iter().find(|n| key(n) == key(o)).map(|n| (o, n)))
+        .collect();
+    (dropped, added, both)
+}
+
+// same_index compares two indexes. Ignoring definitions leaves out the index definitions and
+// the key expressions, plain column keys are still compared.
+fn same_index(
+    old: &store::IndexMetadata,
+    new: &store::IndexMetadata,
+    options: &DiffOptions,
+) -> bool {
+    if !options.ignore_definitions {
+        return old == new;
+    }
+    let without_definitions = |index: &store::IndexMetadata| store::IndexMetadata {
+        expressions: index
+            .expressions
+            .iter()
+            .map(|e| {
+                if e.contains('(') {
+                    String::new()
+                } else {
+                    e.clone()
+                }
+            })
+            .collect(),
+        definition: String::new(),
+        ..index.clone()
+    };
+    without_definitions(old) == without_definitions(new)
+}
+
+fn routine_key(name: &str, signature: &str) -> String {
+    if signature.is_empty() {
+        name.to_string()
+    } else {
+        signature.to_string()
+    }
+}
+
+fn same_columns(a: &[store::ColumnMetadata], b: &[store::ColumnMetadata]) -> bool {
+    !a.is_empty()
+        && a.len() == b.len()
+        && a.iter()
+            .zip(b)
+            .all(|(x, y)| x.name == y.name && x.r#type == y.r#type)
+}
+
+impl Display for Change {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let path = [self.schema.as_str(), self.table.as_str()]
+            .iter()
+            .filter(|p| !p.is_empty())
+            .copied()
+            .collect::<Vec<_>>()
+            .join(".");
+        if !path.is_empty() {
+            write!(f, "{path}: ")?;
+        }
+
+        match &self.kind {
+            ChangeKind::SchemaAdded => write!(f, "schema added"),
+            ChangeKind::SchemaDropped => write!(f, "schema dropped"),
+            ChangeKind::TableAdded(_) => write!(f, "table added"),
+            ChangeKind::TableDropped(_) => write!(f, "table dropped"),
+            ChangeKind::TableRenameCandidate { old, .. } => {
+                write!(f, "table may be renamed from {}", old.name)
+            }
+            ChangeKind::TableChanged { attributes, .. } => {
+                write!(f, "table {attributes:?} changed")
+            }
+            ChangeKind::ColumnAdded(c) => write!(f, "column {} added", c.name),
+            ChangeKind::ColumnDropped(c) => write!(f, "column {} dropped", c.name),
+            ChangeKind::ColumnChanged {
+                old,
+                new,
+                attributes,
+            } => {
+                write!(f, "column {} {attributes:?} changed", new.name)?;
+                if attributes.contains(&ColumnAttribute::Type) {
+                    write!(f, ", type {} -> {}", old.r#type, new.r#type)?;
+                }
+                Ok(())
+            }
+            ChangeKind::IndexAdded(i) => write!(f, "index {} added", i.name),
+            ChangeKind::IndexDropped(i) => write!(f, "index {} dropped", i.name),
+            ChangeKind::IndexChanged { new, .. } => write!(f, "index {} changed", new.name),
+            ChangeKind::ForeignKeyAdded(fk) => write!(f, "foreign key {} added", fk.name),
+            ChangeKind::ForeignKeyDropped(fk) => write!(f, "foreign key {} dropped", fk.name),
+            ChangeKind::ForeignKeyChanged { new, .. } => {
+                write!(f, "foreign key {} changed", new.name)
+            }
+            ChangeKind::TriggerAdded(t) => write!(f, "trigger {} added", t.name),
+            ChangeKind::TriggerDropped(t) => write!(f, "trigger {} dropped", t.name),
+            ChangeKind::TriggerChanged { new, .. } => write!(f, "trigger {} changed", new.name),
+            ChangeKind::ViewAdded(_) => write!(f, "view added"),
+            ChangeKind::ViewDropped(_) => write!(f, "view dropped"),
+            ChangeKind::ViewChanged { .. } => write!(f, "view changed"),
+            ChangeKind::MaterializedViewAdded(_) => write!(f, "materialized view added"),
+            ChangeKind::MaterializedViewDropped(_) => write!(f, "materialized view dropped"),
+            ChangeKind::MaterializedViewChanged { .. } => write!(f, "materialized view changed"),
+            ChangeKind::FunctionAdded(r) => {
+                write!(f, "function {} added", routine_key(&r.name, &r.signature))
+            }
+            ChangeKind::FunctionDropped(r) => {
+                write!(f, "function {} dropped", routine_key(&r.name, &r.signature))
+            }
+            ChangeKind::FunctionChanged { new, .. } => {
+                write!(
+                    f,
+                    "function {} changed",
+                    routine_key(&new.name, &new.signature)
+                )
+            }
+            ChangeKind::ProcedureAdded(r) => {
+                write!(f, "procedure {} added", routine_key(&r.name, &r.signature))
+            }
+            ChangeKind::ProcedureDropped(r) => {
+                write!(
+                    f,
+                    "procedure {} dropped",
+                    routine_key(&r.name, &r.signature)
+                )
+            }
+            ChangeKind::ProcedureChanged { new, .. } => {
+                write!(
+                    f,
+                    "procedure {} changed",
+                    routine_key(&new.name, &new.signature)
+                )
+            }
+            ChangeKind::EventAdded(e) => write!(f, "event {} added", e.name),
+            ChangeKind::EventDropped(e) => write!(f, "event {} dropped", e.name),
+            ChangeKind::EventChanged { new, .. } => write!(f, "event {} changed", new.name),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::db::builder::{column, database, index, schema, table};
+
+    #[test]
+    fn test_diff_identical() {
+        let db = database(vec![schema(
+            "sales",
+            vec![table("customers", vec![column("id", 0, "integer", true)])],
+        )]);
+        assert!(diff_database(&db, &db).is_empty());
+    }
+
+    #[test]
+    fn test_diff_tables() {
+        let source = database(vec![schema(
+            "sales",
+            vec![
+                table("customers", vec![column("id", 0, "integer", true)]),
+                table(
+                    "orders",
+                    vec![
+                        column("id", 0, "integer", true),
+                        column("total", 0, "numeric", true),
+                    ],
+                ),
+                table("legacy", vec![column("payload", 0, "text", true)]),
+            ],
+        )]);
+        let target = database(vec![schema(
+            "sales",
+            vec![
+                table("customers", vec![column("id", 0, "integer", true)]),
+                table(
+                    "purchase_orders",
+                    vec![
+                        column("id", 0, "integer", true),
+                        column("total", 0, "numeric", true),
+                    ],
+                ),
+                table("regions", vec![column("code", 0, "text", true)]),
+            ],
+        )]);
+
+        let changes = diff_database(&source, &target);
+        let summary: Vec<String> = changes.iter().map(|c| c.to_string()).collect();
+        assert_eq!(
+            summary,
+            vec![
+                "sales.purchase_orders: table may be renamed from orders",
+                "sales.legacy: table dropped",
+                "sales.regions: table added",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_diff_columns() {
+        let mut email = column("email", 0, "varchar(100)", true);
+        let source = database(vec![schema(
+            "",
+            vec![table(
+                "customers",
+                vec![
+                    column("id", 0, "int", true),
+                    email.clone(),
+                    column("phone", 0, "text", true),
+                ],
+            )],
+        )]);
+        email.r#type = "varchar(255)".to_string();
+        email.nullable = false;
+        let mut target_table = table(
+            "customers",
+            vec![
+                column("id", 0, "int", true),
+                email,
+                column("city", 0, "text", true),
+            ],
+        );
+        target_table.comment = "Customer information".to_string();
+        let target = database(vec![schema("", vec![target_table])]);
+
+        let changes = diff_database(&source, &target);
+        assert!(changes
+            .iter()
+            .all(|c| c.schema.is_empty() && c.table == "customers"));
+
+        let summary: Vec<String> = changes.iter().map(|c| c.to_string()).collect();
+        assert_eq!(
+            summary,
+            vec![
+                "customers: table [Comment] changed",
+                "customers: column phone dropped",
+                "customers: column city added",
+                "customers: column email [Type, Nullable] changed, type varchar(100) -> varchar(255)",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_diff_schema_and_routines() {
+        let function = |signature: &str, definition: &str| store::FunctionMetadata {
+            name: "total".to_string(),
+            signature: signature.to_string(),
+            definition: definition.to_string(),
+            parameters: vec![],
+            return_type: String::new(),
+            language: String::new(),
+            volatility: store::Volatility::UNSPECIFIED,
+            security: store::SecurityType::UNSPECIFIED,
+            owner: String::new(),
+            comment: String::new(),
+        };
+        let mut sales = schema("sales", vec![]);
+        sales.functions = vec![function("total(integer)", "v1")];
+        let source = database(vec![sales.clone()]);
+
+        sales.functions = vec![
+            function("total(integer)", "v2"),
+            function("total(integer, boolean)", "v1"),
+        ];
+        let inventory = schema(
+            "inventory",
+            vec![table("products", vec![column("id", 0, "int", true)])],
+        );
+        let target = database(vec![sales, inventory]);
+
+        let summary: Vec<String> = diff_database(&source, &target)
+            .iter()
+            .map(|c| c.to_string())
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                "sales: function total(integer, boolean) added",
+                "sales: function total(integer) changed",
+                "inventory: schema added",
+                "inventory.products: table added",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_diff_ignore_definitions() {
+        let index = |expression: &str, definition: &str| store::IndexMetadata {
+            definition: definition.to_string(),
+            ..index("idx_email", &[expression], false, false)
+        };
+        let view = |definition: &str| store::ViewMetadata {
+            name: "active_customers".to_string(),
+            definition: definition.to_string(),
+            comment: String::new(),
+            dependent_columns: vec![],
+        };
+
+        let mut customers = table("customers", vec![column("email", 0, "text", true)]);
+        customers.indexes = vec![index("lower(email)", "CREATE INDEX ... (lower(email))")];
+        let mut source = schema("sales", vec![customers.clone()]);
+        source.views = vec![view("SELECT * FROM customers")];
+        customers.indexes = vec![index("lower((email)::text)", "CREATE INDEX ...")];
+        let mut target = schema("sales", vec![customers.clone()]);
+        target.views = vec![view(" SELECT customers.email FROM sales.customers;")];
+
+        let (source, target) = (database(vec![source]), database(vec![target]));
+        let summary = |options: &DiffOptions| -> Vec<String> {
+            diff_database_with(&source, &target, options)
+                .iter()
+                .map(|c| c.to_string())
+                .collect()
+        };
+        assert_eq!(
+            summary(&DiffOptions::default()),
+            vec![
+                "sales.customers: index idx_email changed",
+                "sales.active_customers: view changed",
+            ]
+        );
+        let options = DiffOptions {
+            ignore_definitions: true,
+            ..Default::default()
+        };
+        assert!(summary(&options).is_empty());
+
+        // Plain column keys are still compared.
+        let mut target = target.clone();
+        target.schemas[0].tables[0].indexes[0].expressions = vec!["email".to_string()];
+        assert_eq!(diff_database_with(&source, &target, &options).len(), 1);
+    }
+
+    #[test]
+    fn test_diff_routine_attributes() {
+        let procedure = store::ProcedureMetadata {
+            name: "cancel_order".to_string(),
+            signature: "cancel_order(integer)".to_string(),
+            definition: "CREATE PROCEDURE ...".to_string(),
+            parameters: vec![store::RoutineParameter {
+                name: "id".to_string(),
+                mode: store::ParameterMode::In,
+                r#type: "integer".to_string(),
+            }],
+            language: "sql".to_string(),
+            volatility: store::Volatility::Volatile,
+            security: store::SecurityType::Invoker,
+            owner: "app".to_string(),
+            comment: String::new(),
+        };
+        let mut sales = schema("sales", vec![]);
+        sales.procedures = vec![procedure.clone()];
+        let source = database(vec![sales.clone()]);
+
+        let mut changed = vec![];
+        for change in [
+            |p: &mut store::ProcedureMetadata| p.owner = "admin".to_string(),
+            |p: &mut store::ProcedureMetadata| p.comment = "Cancel an order".to_string(),
+            |p: &mut store::ProcedureMetadata| p.security = store::SecurityType::Definer,
+            |p: &mut store::ProcedureMetadata| p.parameters[0].mode = store::ParameterMode::InOut,
+        ] {
+            let mut procedure = procedure.clone();
+            change(&mut procedure);
+            sales.procedures = vec![procedure];
+            changed.push(diff_database(&source, &database(vec![sales.clone()])).len());
+        }
+        assert_eq!(changed, vec![1, 1, 1, 1]);
+
+        // An owner only change is left out when owners are ignored.
+        sales.procedures = vec![store::ProcedureMetadata {
+            owner: "admin".to_string(),
+            ..procedure
+        }];
+        let options = DiffOptions {
+            ignore_owners: true,
+            ..Default::default()
+        };
+        assert!(diff_database_with(&source, &database(vec![sales]), &options).is_empty());
+    }
+
+    #[test]
+    fn test_diff_events() {
+        let event = |name: &str, status: &str| store::EventMetadata {
+            name: name.to_string(),
+            definition: format!("CREATE DEFINER=`root`@`%` EVENT `{name}` ..."),
+            schedule: "EVERY 1 DAY STARTS '2024-01-01 03:00:00'".to_string(),
+            time_zone: "SYSTEM".to_string(),
+            status: status.to_string(),
+            on_completion: "PRESERVE".to_string(),
+            definer: "root@%".to_string(),
+            body: "DELETE FROM orders WHERE status = 'cancelled'".to_string(),
+            comment: String::new(),
+        };
+        let mut source = schema("", vec![]);
+        source.events = vec![
+            event("purge_orders", "ENABLED"),
+            event("purge_logs", "ENABLED"),
+        ];
+        let mut target = schema("", vec![]);
+        target.events = vec![
+            event("purge_orders", "DISABLED"),
+            event("refresh_stats", "ENABLED"),
+        ];
+        let (source, target) = (database(vec![source]), database(vec![target]));
+
+        let summary: Vec<String> = diff_database(&source, &target)
+            .iter()
+            .map(|c| c.to_string())
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                "event purge_logs dropped",
+                "event refresh_stats added",
+                "event purge_orders changed",
+            ]
+        );
+
+        // A definer only change is left out when owners are ignored.
+        let mut target = source.clone();
+        target.schemas[0].events[0].definer = "admin@localhost".to_string();
+        assert_eq!(diff_database(&source, &target).len(), 1);
+        let options = DiffOptions {
+            ignore_owners: true,
+            ..Default::default()
+        };
+        assert!(diff_database_with(&source, &target, &options).is_empty());
+    }
+
+    #[test]
+    fn test_diff_triggers() {
+        let trigger = |name: &str, definition: &str| store::TriggerMetadata {
+            name: name.to_string(),
+            timing: "BEFORE".to_string(),
+            events: vec!["INSERT".to_string()],
+            for_each_row: true,
+            definition: definition.to_string(),
+            enabled: true,
+        };
+        let mut products = table("products", vec![column("name", 0, "text", true)]);
+        products.triggers = vec![
+            trigger("trg_trim_name", "CREATE TRIGGER trg_trim_name ..."),
+            trigger("trg_audit", "CREATE TRIGGER trg_audit ..."),
+        ];
+        let source = database(vec![schema("inventory", vec![products.clone()])]);
+
+        let mut disabled = trigger("trg_trim_name", "CREATE TRIGGER trg_trim_name ...");
+        disabled.enabled = false;
+        products.triggers = vec![
+            disabled,
+            trigger("trg_stock", "CREATE TRIGGER trg_stock ..."),
+        ];
+        let target = database(vec![schema("inventory", vec![products])]);
+
+        let summary: Vec<String> = diff_database(&source, &target)
+            .iter()
+            .map(|c| c.to_string())
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                "inventory.products: trigger trg_audit dropped",
+                "inventory.products: trigger trg_stock added",
+                "inventory.products: trigger trg_trim_name changed",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_diff_partitioning() {
+        let partition = |name: &str, value: &str, row_count: i64| store::PartitionMetadata {
+            name: name.to_string(),
+            value: value.to_string(),
+            subpartitioning: None,
+            row_count,
+        };
+        let mut events = table("order_events", vec![column("created_at", 0, "date", true)]);
+        events.partitioning = Some(store::PartitioningMetadata {
+            strategy: store::PartitionStrategy::Range,
+            expression: "created_at".to_string(),
+            partitions: vec![partition(
+                "order_events_2024",
+                "FOR VALUES FROM ('2024-01-01') TO ('2025-01-01')",
+                10,
+            )],
+        });
+        let source = database(vec![schema("sales", vec![events.clone()])]);
+
+        // Row counts are statistics.
+        let mut counted = events.clone();
+        counted.partitioning.as_mut().unwrap().partitions[0].row_count = 20;
+        let target = database(vec![schema("sales", vec![counted])]);
+        assert!(diff_database(&source, &target).is_empty());
+
+        let mut partitioned = events.clone();
+        let partitioning = partitioned.partitioning.as_mut().unwrap();
+        partitioning.partitions[0].subpartitioning = Some(store::PartitioningMetadata {
+            strategy: store::PartitionStrategy::Hash,
+            expression: "event_id".to_string(),
+            partitions: vec![partition(
+                "order_events_2024_0",
+                "FOR VALUES WITH (modulus 1, remainder 0)",
+                0,
+            )],
+        });
+        let target = database(vec![schema("sales", vec![partitioned])]);
+        let changes = diff_database(&source, &target);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(
+            changes[0].to_string(),
+            "sales.order_events: table [Partitioning] changed"
+        );
+
+        let mut unpartitioned = events.clone();
+        unpartitioned.partitioning = None;
+        let target = database(vec![schema("sales", vec![unpartitioned])]);
+        assert_eq!(diff_database(&source, &target).len(), 1);
+
+        let mut rekeyed = events;
+        rekeyed.partitioning.as_mut().unwrap().expression = "date_trunc('year', created_at)".into();
+        let target = database(vec![schema("sales", vec![rekeyed])]);
+        assert_eq!(diff_database(&source, &target).len(), 1);
+        let options = DiffOptions {
+            ignore_definitions: true,
+            ignore_owners: false,
+        };
+        assert!(diff_database_with(&source, &target, &options).is_empty());
+    }
+}
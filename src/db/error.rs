@@ -1,18 +1,208 @@
+// Most helpers are only used by the drivers.
+#![cfg_attr(
+    not(any(feature = "db-mysql", feature = "db-tidb", feature = "db-postgres")),
+    allow(dead_code)
+)]
+
+use std::error::Error;
+use std::fmt;
+
+// ErrorKind classifies a failure, so callers can tell transient failures worth a retry from
+// the ones to report.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    // InvalidArgument is a bad input such as a malformed connection URL or schema file.
+    InvalidArgument,
+    Authentication,
+    PermissionDenied,
+    ConnectionRefused,
+    // Connection is any other failure to reach or keep talking to the server, TLS included.
+    Connection,
+    Timeout,
+    UnsupportedVersion,
+    // UnexpectedCatalog is a catalog row the loaders cannot make sense of.
+    UnexpectedCatalog,
+    // Database is any other error reported by the database.
+    Database,
+}
+
 #[derive(Debug)]
-pub enum DBError {
-    Todo,
-    Args(String),
-    DB(String),
-    Unknow(String),
+pub struct DBError {
+    kind: ErrorKind,
+    message: String,
+    // The context is the loaders the error went through, innermost first.
+    context: Vec<String>,
+    source: Option<Box<dyn Error + Send + Sync>>,
 }
+
+impl DBError {
+    pub fn new(kind: ErrorKind, message: impl Into<String>) -> DBError {
+        DBError {
+            kind,
+            message: message.into(),
+            context: vec![],
+            source: None,
+        }
+    }
+
+    pub(crate) fn args(message: impl Into<String>) -> DBError {
+        DBError::new(ErrorKind::InvalidArgument, message)
+    }
+
+    pub(crate) fn unexpected_catalog(message: impl Into<String>) -> DBError {
+        DBError::new(ErrorKind::UnexpectedCatalog, message)
+    }
+
+    pub(crate) fn unsupported_version(message: impl Into<String>) -> DBError {
+        DBError::new(ErrorKind::UnsupportedVersion, message)
+    }
+
+    pub(crate) fn with_source(mut self, source: impl Error + Send + Sync + 'static) -> DBError {
+        self.source = Some(Box::new(source));
+        self
+    }
+
+    // with_context records the loader or query the error went through.
+    pub(crate) fn with_context(mut self, context: impl Into<String>) -> DBError {
+        self.context.push(context.into());
+        self
+    }
+
+    pub fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    // context returns the loaders the error went through, outermost first.
+    pub fn context(&self) -> Vec<&str> {
+        self.context.iter().rev().map(String::as_str).collect()
+    }
+
+    // is_transient reports whether retrying later may succeed.
+    pub fn is_transient(&self) -> bool {
+        matches!(
+            self.kind,
+            ErrorKind::ConnectionRefused | ErrorKind::Connection | ErrorKind::Timeout
+        )
+    }
+}
+
+impl fmt::Display for DBError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for context in self.context.iter().rev() {
+            write!(f, "{context}: ")?;
+        }
+        write!(f, "{}", self.message)
+    }
+}
+
+impl Error for DBError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.source
+            .as_deref()
+            .map(|source| source as &(dyn Error + 'static))
+    }
+}
+
+// Context adds the loader or query a failed result comes from.
+pub(crate) trait Context<T> {
+    fn context(self, context: &str) -> Result<T, DBError>;
+}
+
+impl<T, E: Into<DBError>> Context<T> for Result<T, E> {
+    fn context(self, context: &str) -> Result<T, DBError> {
+        self.map_err(|e| e.into().with_context(context))
+    }
+}
+
 #[cfg(any(feature = "db-mysql", feature = "db-tidb", feature = "db-postgres"))]
 impl From<sqlx::Error> for DBError {
     fn from(value: sqlx::Error) -> Self {
-        DBError::DB(value.to_string())
+        let kind = match &value {
+            sqlx::Error::Database(e) => database_error_kind(e.as_ref()),
+            sqlx::Error::Io(e) => match e.kind() {
+                std::io::ErrorKind::ConnectionRefused => ErrorKind::ConnectionRefused,
+                std::io::ErrorKind::TimedOut => ErrorKind::Timeout,
+                _ => ErrorKind::Connection,
+            },
+            sqlx::Error::Configuration(_) => ErrorKind::InvalidArgument,
+            sqlx::Error::Tls(_)
+            | sqlx::Error::Protocol(_)
+            | sqlx::Error::PoolClosed
+            | sqlx::Error::WorkerCrashed => ErrorKind::Connection,
+            sqlx::Error::PoolTimedOut => ErrorKind::Timeout,
+            sqlx::Error::RowNotFound
+            | sqlx::Error::TypeNotFound { .. }
+            | sqlx::Error::ColumnIndexOutOfBounds { .. }
+            | sqlx::Error::ColumnNotFound(_)
+            | sqlx::Error::ColumnDecode { .. }
+            | sqlx::Error::Decode(_) => ErrorKind::UnexpectedCatalog,
+            _ => ErrorKind::Database,
+        };
+        DBError::new(kind, value.to_string()).with_source(value)
     }
 }
+
+// database_error_kind classifies an error reported by the server by its SQLSTATE, and by the
+// error number for the MySQL errors sharing the generic 42000 state.
+#[cfg(any(feature = "db-mysql", feature = "db-tidb", feature = "db-postgres"))]
+fn database_error_kind(e: &dyn sqlx::error::DatabaseError) -> ErrorKind {
+    if let Some(e) = e.try_downcast_ref::<sqlx::mysql::MySqlDatabaseError>() {
+        match e.number() {
+            // ER_DBACCESS_DENIED_ERROR, ER_TABLEACCESS_DENIED_ERROR, ER_COLUMNACCESS_DENIED_ERROR,
+            // ER_SPECIFIC_ACCESS_DENIED_ERROR and ER_PROCACCESS_DENIED_ERROR.
+            1044 | 1142 | 1143 | 1227 | 1370 => return ErrorKind::PermissionDenied,
+            // ER_QUERY_TIMEOUT is raised once max_execution_time elapses.
+            3024 => return ErrorKind::Timeout,
+            _ => {}
+        }
+    }
+    match e.code().as_deref() {
+        Some("28000" | "28P01") => ErrorKind::Authentication,
+        Some("42501") => ErrorKind::PermissionDenied,
+        // query_canceled is raised once statement_timeout elapses.
+        Some("57014") => ErrorKind::Timeout,
+        Some("57P01" | "57P02" | "57P03" | "53300") => ErrorKind::Connection,
+        Some(code) if code.starts_with("08") => ErrorKind::Connection,
+        _ => ErrorKind::Database,
+    }
+}
+
 impl From<url::ParseError> for DBError {
     fn from(value: url::ParseError) -> Self {
-        DBError::Args(value.to_string())
+        DBError::args(value.to_string()).with_source(value)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Context, DBError, ErrorKind};
+    use std::error::Error;
+
+    #[test]
+    fn test_display() {
+        let result: Result<(), DBError> = Err(DBError::unexpected_catalog(
+            "unrecognized isNullable type X",
+        ));
+        let e = result
+            .context("load_column")
+            .context("sync_database")
+            .unwrap_err();
+        assert_eq!(e.kind(), ErrorKind::UnexpectedCatalog);
+        assert_eq!(e.context(), vec!["sync_database", "load_column"]);
+        assert_eq!(
+            e.to_string(),
+            "sync_database: load_column: unrecognized isNullable type X"
+        );
+        assert!(!e.is_transient());
+        assert!(e.source().is_none());
+
+        let e = DBError::from(url::Url::parse("no scheme").unwrap_err());
+        assert_eq!(e.kind(), ErrorKind::InvalidArgument);
+        assert!(e.source().is_some());
     }
 }
@@ -0,0 +1,1758 @@
+use super::ddl;
+use super::diff::{self, ChangeKind, ColumnAttribute, TableAttribute};
+use super::store;
+use super::Engine;
+use std::collections::HashSet;
+
+// Dialect is the SQL dialect a migration plan is rendered in.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Dialect {
+    // MySQL is used for both MySQL and TiDB.
+    MySQL,
+    PostgreSQL,
+}
+
+impl From<&Engine> for Dialect {
+    fn from(engine: &Engine) -> Self {
+        match *engine {
+            #[cfg(feature = "db-mysql")]
+            Engine::MYSQL => Dialect::MySQL,
+            #[cfg(feature = "db-tidb")]
+            Engine::TIDB => Dialect::MySQL,
+            #[cfg(feature = "db-postgres")]
+            Engine::POSTGRES => Dialect::PostgreSQL,
+        }
+    }
+}
+
+// MigrationStep is a single statement of a migration plan.
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MigrationStep {
+    // The statement is terminated by a semicolon.
+    pub statement: String,
+    // The destructive lists why the statement may lose data or fail on existing rows.
+    // It is empty for statements that are safe to apply.
+    pub destructive: Vec<Destructive>,
+}
+
+impl MigrationStep {
+    pub fn is_destructive(&self) -> bool {
+        !self.destructive.is_empty()
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Destructive {
+    DropSchema,
+    DropTable,
+    DropColumn,
+    // The new column type cannot hold every value of the old one.
+    TypeNarrowing,
+    // An existing column becomes NOT NULL, which fails when it holds NULL values.
+    SetNotNull,
+}
+
+// plan_migration returns the statements that turn the source database into the target database,
+// both synced from an engine of the given dialect.
+// Statements are ordered so that no foreign key, trigger or view references a missing object:
+// foreign keys, triggers, views, routines, events and tables are dropped first, then schemas and
+// tables are created and altered, and routines, events, triggers, views and foreign keys are
+// created last. Views depending on a dropped
+// column or a column whose type changes are recreated. Tables reported as rename candidates by
+// the diff are renamed instead of being dropped and created.
+// MySQL tables are repartitioned in place. PostgreSQL partitions are attached and dropped as
+// tables, and a table whose partition strategy or key changes is dropped and created again.
+pub fn plan_migration(
+    dialect: Dialect,
+    source: &store::DatabaseSchemaMetadata,
+    target: &store::DatabaseSchemaMetadata,
+) -> Vec<MigrationStep> {
+    let mut changes = diff::diff_database(source, target);
+    if dialect == Dialect::PostgreSQL {
+        changes = recreate_repartitioned_tables(changes);
+    }
+    let mut plan = Plan::new(dialect, target);
+
+    for (schema, name) in views_to_recreate(source, target, &changes) {
+        if let Some(relation) = find_relation(source, &schema, &name) {
+            plan.drop_relations.push((schema.clone(), relation));
+        }
+        if let Some(relation) = find_relation(target, &schema, &name) {
+            plan.create_relations.push((schema, relation));
+        }
+    }
+
+    for change in &changes {
+        plan.add_change(&change.schema, &change.table, &change.kind);
+    }
+
+    plan.finish()
+}
+
+// Relation is a view or a materialized view.
+#[derive(Clone)]
+enum Relation {
+    View(store::ViewMetadata),
+    MaterializedView(store::MaterializedViewMetadata),
+}
+
+impl Relation {
+    fn name(&self) -> &str {
+        match self {
+            Relation::View(v) => &v.name,
+            Relation::MaterializedView(v) => &v.name,
+        }
+    }
+
+    fn dependent_columns(&self) -> &[store::DependentColumn] {
+        match self {
+            Relation::View(v) => &v.dependent_columns,
+            Relation::MaterializedView(v) => &v.dependent_columns,
+        }
+    }
+}
+
+// Routine is a function or a procedure.
+enum Routine<'a> {
+    Function(&'a store::FunctionMetadata),
+    Procedure(&'a store::ProcedureMetadata),
+}
+
+impl Routine<'_> {
+    fn kind(&self) -> &'static str {
+        match self {
+            Routine::Function(_) => "FUNCTION",
+            Routine::Procedure(_) => "PROCEDURE",
+        }
+    }
+
+    fn name(&self) -> &str {
+        match self {
+            Routine::Function(f) => &f.name,
+            Routine::Procedure(p) => &p.name,
+        }
+    }
+
+    fn signature(&self) -> &str {
+        match self {
+            Routine::Function(f) => &f.signature,
+            Routine::Procedure(p) => &p.signature,
+        }
+    }
+
+    fn definition(&self) -> &str {
+        match self {
+            Routine::Function(f) => &f.definition,
+            Routine::Procedure(p) => &p.definition,
+        }
+    }
+
+    fn owner(&self) -> &str {
+        match self {
+            Routine::Function(f) => &f.owner,
+            Routine::Procedure(p) => &p.owner,
+        }
+    }
+
+    fn comment(&self) -> &str {
+        match self {
+            Routine::Function(f) => &f.comment,
+            Routine::Procedure(p) => &p.comment,
+        }
+    }
+}
+
+// Plan collects the statements of each phase of a migration,
+// the phases are applied in the order of the fields.
+struct Plan<'a> {
+    dialect: Dialect,
+    target: &'a store::DatabaseSchemaMetadata,
+    drop_foreign_keys: Vec<MigrationStep>,
+    drop_triggers: Vec<MigrationStep>,
+    drop_relations: Vec<(String, Relation)>,
+    drop_routines: Vec<MigrationStep>,
+    drop_tables: Vec<MigrationStep>,
+    create_schemas: Vec<MigrationStep>,
+    create_tables: Vec<MigrationStep>,
+    drop_indexes: Vec<MigrationStep>,
+    alter_tables: Vec<MigrationStep>,
+    create_indexes: Vec<MigrationStep>,
+    create_routines: Vec<MigrationStep>,
+    create_triggers: Vec<MigrationStep>,
+    create_relations: Vec<(String, Relation)>,
+    add_foreign_keys: Vec<MigrationStep>,
+    drop_schemas: Vec<MigrationStep>,
+}
+
+impl<'a> Plan<'a> {
+    fn new(dialect: Dialect, target: &'a store::DatabaseSchemaMetadata) -> Self {
+        Plan {
+            dialect,
+            target,
+            drop_foreign_keys: vec![],
+            drop_triggers: vec![],
+            drop_relations: vec![],
+            drop_routines: vec![],
+            drop_tables: vec![],
+            create_schemas: vec![],
+            create_tables: vec![],
+            drop_indexes: vec![],
+            alter_tables: vec![],
+            create_indexes: vec![],
+            create_routines: vec![],
+            create_triggers: vec![],
+            create_relations: vec![],
+            add_foreign_keys: vec![],
+            drop_schemas: vec![],
+        }
+    }
+
+    fn add_change(&mut self, schema: &str, table: &str, kind: &ChangeKind) {
+        let table_name = self.qualified_name(schema, table);
+        match kind {
+            ChangeKind::SchemaAdded => {
+                self.create_schemas.push(step(format!(
+                    "CREATE SCHEMA {};",
+                    self.quote_identifier(schema)
+                )));
+            }
+            ChangeKind::SchemaDropped => {
+                self.drop_schemas.push(destructive_step(
+                    format!("DROP SCHEMA {};", self.quote_identifier(schema)),
+                    vec![Destructive::DropSchema],
+                ));
+            }
+            ChangeKind::TableAdded(t) => {
+                let stmts = self.create_table(schema, t);
+                self.create_tables.extend(stmts);
+                for fk in &t.foreign_keys {
+                    let stmt = self.add_foreign_key(schema, table, fk);
+                    self.add_foreign_keys.push(stmt);
+                }
+                for trigger in &t.triggers {
+                    let stmts = self.create_trigger(schema, table, trigger);
+                    self.create_triggers.extend(stmts);
+                }
+            }
+            ChangeKind::TableDropped(t) => {
+                // Foreign keys between dropped tables are dropped first, so the tables can be
+                // dropped in any order.
+                for fk in &t.foreign_keys {
+                    let referenced_schema = if fk.referenced_schema.is_empty() {
+                        schema
+                    } else {
+                        &fk.referenced_schema
+                    };
+                    if find_table(self.target, referenced_schema, &fk.referenced_table).is_none() {
+                        let stmt = self.drop_foreign_key(&table_name, fk);
+                        self.drop_foreign_keys.push(stmt);
+                    }
+                }
+                self.drop_tables.push(destructive_step(
+                    format!("DROP TABLE {table_name};"),
+                    vec![Destructive::DropTable],
+                ));
+            }
+            ChangeKind::TableRenameCandidate { old, new } => {
+                self.create_tables.push(step(format!(
+                    "ALTER TABLE {} RENAME TO {};",
+                    self.qualified_name(schema, &old.name),
+                    self.quote_identifier(&new.name)
+                )));
+            }
+            ChangeKind::TableChanged {
+                old,
+                new,
+                attributes,
+            } => {
+                let stmts = self.alter_table(&table_name, new, attributes);
+                self.alter_tables.extend(stmts);
+                // The tables left with a partitioning change share the strategy and key.
+                if let (Dialect::PostgreSQL, Some(old), Some(new)) =
+                    (self.dialect, &old.partitioning, &new.partitioning)
+                {
+                    self.alter_partitions(schema, table, old, new);
+                }
+            }
+            ChangeKind::ColumnAdded(c) => {
+                let stmts = self.add_column(schema, table, c);
+                self.alter_tables.extend(stmts);
+            }
+            ChangeKind::ColumnDropped(c) => {
+                self.alter_tables.push(destructive_step(
+                    format!(
+                        "ALTER TABLE {table_name} DROP COLUMN {};",
+                        self.quote_identifier(&c.name)
+                    ),
+                    vec![Destructive::DropColumn],
+                ));
+            }
+            ChangeKind::ColumnChanged {
+                old,
+                new,
+                attributes,
+            } => {
+                let stmts = self.alter_column(schema, table, old, new, attributes);
+                self.alter_tables.extend(stmts);
+            }
+            ChangeKind::IndexAdded(idx) => {
+                let stmts = self.create_index(schema, table, idx);
+                self.create_indexes.extend(stmts);
+            }
+            ChangeKind::IndexDropped(idx) => {
+                let stmts = self.drop_index(schema, table, idx);
+                self.drop_indexes.extend(stmts);
+            }
+            ChangeKind::IndexChanged { old, new } => {
+                let stmts = self.drop_index(schema, table, old);
+                self.drop_indexes.extend(stmts);
+                let stmts = self.create_index(schema, table, new);
+                self.create_indexes.extend(stmts);
+            }
+            ChangeKind::ForeignKeyAdded(fk) => {
+                let stmt = self.add_foreign_key(schema, table, fk);
+                self.add_foreign_keys.push(stmt);
+            }
+            ChangeKind::ForeignKeyDropped(fk) => {
+                let stmt = self.drop_foreign_key(&table_name, fk);
+                self.drop_foreign_keys.push(stmt);
+            }
+            ChangeKind::ForeignKeyChanged { old, new } => {
+                let stmt = self.drop_foreign_key(&table_name, old);
+                self.drop_foreign_keys.push(stmt);
+                let stmt = self.add_foreign_key(schema, table, new);
+                self.add_foreign_keys.push(stmt);
+            }
+            ChangeKind::TriggerAdded(trigger) => {
+                let stmts = self.create_trigger(schema, table, trigger);
+                self.create_triggers.extend(stmts);
+            }
+            ChangeKind::TriggerDropped(trigger) => {
+                let stmt = self.drop_trigger(&table_name, trigger);
+                self.drop_triggers.push(stmt);
+            }
+            ChangeKind::TriggerChanged { old, new } => {
+                if self.dialect == Dialect::PostgreSQL && old.definition == new.definition {
+                    let action = if new.enabled { "ENABLE" } else { "DISABLE" };
+                    self.create_triggers.push(step(format!(
+                        "ALTER TABLE {table_name} {action} TRIGGER {};",
+                        self.quote_identifier(&new.name)
+                    )));
+                } else {
+                    let stmt = self.drop_trigger(&table_name, old);
+                    self.drop_triggers.push(stmt);
+                    let stmts = self.create_trigger(schema, table, new);
+                    self.create_triggers.extend(stmts);
+                }
+            }
+            ChangeKind::ViewAdded(v) => {
+                self.create_relations
+                    .push((schema.to_string(), Relation::View(v.clone())));
+            }
+            ChangeKind::ViewDropped(v) => {
+                self.drop_relations
+                    .push((schema.to_string(), Relation::View(v.clone())));
+            }
+            ChangeKind::ViewChanged { old, new } => {
+                if old.definition == new.definition {
+                    let stmt = self.comment_on("VIEW", &table_name, &new.comment);
+                    self.alter_tables.extend(stmt.into_iter().map(step));
+                } else {
+                    self.drop_relations
+                        .push((schema.to_string(), Relation::View(old.clone())));
+                    self.create_relations
+                        .push((schema.to_string(), Relation::View(new.clone())));
+                }
+            }
+            ChangeKind::MaterializedViewAdded(v) => {
+                self.create_relations
+                    .push((schema.to_string(), Relation::MaterializedView(v.clone())));
+            }
+            ChangeKind::MaterializedViewDropped(v) => {
+                self.drop_relations
+                    .push((schema.to_string(), Relation::MaterializedView(v.clone())));
+            }
+            ChangeKind::MaterializedViewChanged { old, new } => {
+                if old.definition == new.definition {
+                    let stmt = self.comment_on("MATERIALIZED VIEW", &table_name, &new.comment);
+                    self.alter_tables.extend(stmt.into_iter().map(step));
+                } else {
+                    self.drop_relations
+                        .push((schema.to_string(), Relation::MaterializedView(old.clone())));
+                    self.create_relations
+                        .push((schema.to_string(), Relation::MaterializedView(new.clone())));
+                }
+            }
+            ChangeKind::FunctionAdded(f) => self.create_routine(schema, &Routine::Function(f)),
+            ChangeKind::FunctionDropped(f) => {
+                let stmt = self.drop_routine(schema, &Routine::Function(f));
+                self.drop_routines.push(stmt);
+            }
+            ChangeKind::FunctionChanged { old, new } => {
+                self.alter_routine(schema, &Routine::Function(old), &Routine::Function(new));
+            }
+            ChangeKind::ProcedureAdded(p) => self.create_routine(schema, &Routine::Procedure(p)),
+            ChangeKind::ProcedureDropped(p) => {
+                let stmt = self.drop_routine(schema, &Routine::Procedure(p));
+                self.drop_routines.push(stmt);
+            }
+            ChangeKind::ProcedureChanged { old, new } => {
+                self.alter_routine(schema, &Routine::Procedure(old), &Routine::Procedure(new));
+            }
+            // Events are MySQL stored programs like routines and share their phases.
+            ChangeKind::EventAdded(e) => {
+                self.create_routines.push(routine_definition(&e.definition));
+            }
+            ChangeKind::EventDropped(e) => {
+                let stmt = self.drop_event(e);
+                self.drop_routines.push(stmt);
+            }
+            ChangeKind::EventChanged { old, new } => {
+                let stmt = self.drop_event(old);
+                self.drop_routines.push(stmt);
+                self.create_routines
+                    .push(routine_definition(&new.definition));
+            }
+        }
+    }
+
+    fn finish(mut self) -> Vec<MigrationStep> {
+        let mut drop_relations: Vec<MigrationStep> = ordered_relations(&self.drop_relations)
+            .into_iter()
+            .map(|(schema, relation)| self.drop_relation(schema, relation))
+            .collect();
+        // Dependent relations are dropped before the relations they depend on.
+        drop_relations.reverse();
+        let create_relations: Vec<MigrationStep> = ordered_relations(&self.create_relations)
+            .into_iter()
+            .flat_map(|(schema, relation)| self.create_relation(schema, relation))
+            .collect();
+
+        let mut steps = vec![];
+        steps.append(&mut self.drop_foreign_keys);
+        steps.append(&mut self.drop_triggers);
+        steps.extend(drop_relations);
+        steps.append(&mut self.drop_routines);
+        steps.append(&mut self.drop_tables);
+        steps.append(&mut self.create_schemas);
+        steps.append(&mut self.create_tables);
+        steps.append(&mut self.drop_indexes);
+        steps.append(&mut self.alter_tables);
+        steps.append(&mut self.create_indexes);
+        steps.append(&mut self.create_routines);
+        steps.append(&mut self.create_triggers);
+        steps.extend(create_relations);
+        steps.append(&mut self.add_foreign_keys);
+        steps.append(&mut self.drop_schemas);
+        steps
+    }
+
+    fn create_table(&self, schema: &str, table: &store::TableMetadata) -> Vec<MigrationStep> {
+        match self.dialect {
+            Dialect::MySQL => {
+                // Foreign keys are added once every table exists.
+                let table = store::TableMetadata {
+                    foreign_keys: vec![],
+                    ..table.clone()
+                };
+                vec![step(format!("{};", ddl::mysql::create_table(&table)))]
+            }
+            Dialect::PostgreSQL => ddl::postgres::create_table(schema, table)
+                .into_iter()
+                .map(step)
+                .collect(),
+        }
+    }
+
+    fn alter_table(
+        &self,
+        table_name: &str,
+        table: &store::TableMetadata,
+        attributes: &[TableAttribute],
+    ) -> Vec<MigrationStep> {
+        let mut stmts = vec![];
+        for attribute in attributes {
+            match (self.dialect, attribute) {
+                (Dialect::MySQL, TableAttribute::Engine) => {
+                    stmts.push(format!("ALTER TABLE {table_name} ENGINE={};", table.engine));
+                }
+                (Dialect::MySQL, TableAttribute::Collation) => {
+                    if let Some(collation) = &table.collation {
+                        stmts.push(format!(
+                            "ALTER TABLE {table_name} DEFAULT CHARSET={} COLLATE={collation};",
+                            ddl::mysql::charset_of(collation)
+                        ));
+                    }
+                }
+                (Dialect::MySQL, TableAttribute::CreateOptions) => {
+                    let options = ddl::mysql::table_options(&table.create_options);
+                    if !options.is_empty() {
+                        stmts.push(format!("ALTER TABLE {table_name}{options};"));
+                    }
+                }
+                (Dialect::MySQL, TableAttribute::Comment) => {
+                    stmts.push(format!(
+                        "ALTER TABLE {table_name} COMMENT={};",
+                        ddl::mysql::quote_string(&table.comment)
+                    ));
+                }
+                (Dialect::MySQL, TableAttribute::Partitioning) => match &table.partitioning {
+                    Some(partitioning) => stmts.push(format!(
+                        "ALTER TABLE {table_name} {};",
+                        ddl::mysql::partition_by(partitioning, &table.engine)
+                    )),
+                    None => stmts.push(format!("ALTER TABLE {table_name} REMOVE PARTITIONING;")),
+                },
+                (Dialect::PostgreSQL, TableAttribute::Comment) => {
+                    stmts.extend(self.comment_on("TABLE", table_name, &table.comment));
+                }
+                // PostgreSQL tables have no engine, collation or create options, and their
+                // partitions are altered by alter_partitions.
+                (Dialect::PostgreSQL, _) => {}
+            }
+        }
+        stmts.into_iter().map(step).collect()
+    }
+
+    // alter_partitions drops the partitions missing from the new partitioning and creates the
+    // added ones, a partition whose bound changes is dropped and created again. The partitions of
+    // a partition are altered the same way when its own strategy and key are kept.
+    fn alter_partitions(
+        &mut self,
+        schema: &str,
+        table: &str,
+        old: &store::PartitioningMetadata,
+        new: &store::PartitioningMetadata,
+    ) {
+        for o in &old.partitions {
+            let n = new.partitions.iter().find(|n| n.name == o.name);
+            let recreated = n.is_some_and(|n| {
+                n.value != o.value
+                    || !same_partition_key(o.subpartitioning.as_ref(), n.subpartitioning.as_ref())
+            });
+            if n.is_none() || recreated {
+                let (partition_schema, partition_name) = ddl::postgres::partition_name(schema, o);
+                self.drop_tables.push(destructive_step(
+                    format!(
+                        "DROP TABLE {};",
+                        self.qualified_name(partition_schema, partition_name)
+                    ),
+                    vec![Destructive::DropTable],
+                ));
+            }
+        }
+        for n in &new.partitions {
+            let o = old.partitions.iter().find(|o| o.name == n.name);
+            let kept = o.filter(|o| {
+                o.value == n.value
+                    && same_partition_key(o.subpartitioning.as_ref(), n.subpartitioning.as_ref())
+            });
+            match kept {
+                Some(o) => {
+                    if let (Some(old_sub), Some(new_sub)) = (&o.subpartitioning, &n.subpartitioning)
+                    {
+                        let (partition_schema, partition_name) =
+                            ddl::postgres::partition_name(schema, n);
+                        self.alter_partitions(partition_schema, partition_name, old_sub, new_sub);
+                    }
+                }
+                None => {
+                    let stmts = ddl::postgres::create_partition(schema, table, n);
+                    self.create_tables.extend(stmts.into_iter().map(step));
+                }
+            }
+        }
+    }
+
+    fn add_column(
+        &self,
+        schema: &str,
+        table: &str,
+        column: &store::ColumnMetadata,
+    ) -> Vec<MigrationStep> {
+        let table_name = self.qualified_name(schema, table);
+        let mut stmts = vec![];
+        match self.dialect {
+            Dialect::MySQL => {
+                let target_table = find_table(self.target, schema, table);
+                let collation = target_table
+                    .and_then(|t| t.collation.as_deref())
+                    .unwrap_or_default();
+                // The column is placed after the column preceding it in the target table.
+                let previous = target_table
+                    .iter()
+                    .flat_map(|t| t.columns.iter())
+                    .filter(|c| c.position < column.position)
+                    .max_by_key(|c| c.position);
+                let position = match previous {
+                    Some(c) => format!(" AFTER {}", ddl::mysql::quote_identifier(&c.name)),
+                    None => " FIRST".to_string(),
+                };
+                stmts.push(format!(
+                    "ALTER TABLE {table_name} ADD COLUMN {}{position};",
+                    ddl::mysql::column_definition(
+                        column,
+                        ddl::mysql::charset_of(collation),
+                        collation
+                    )
+                ));
+            }
+            Dialect::PostgreSQL => {
+                let sequence = ddl::postgres::sequence_of(&column.default);
+                if let Some(seq) = sequence {
+                    stmts.push(format!("CREATE SEQUENCE IF NOT EXISTS {seq};"));
+                }
+                stmts.push(format!(
+                    "ALTER TABLE {table_name} ADD COLUMN {};",
+                    ddl::postgres::column_definition(column)
+                ));
+                if let Some(seq) = sequence {
+                    stmts.push(format!(
+                        "ALTER SEQUENCE {seq} OWNED BY {table_name}.{};",
+                        self.quote_identifier(&column.name)
+                    ));
+                }
+                if !column.comment.is_empty() {
+                    stmts.extend(self.comment_on(
+                        "COLUMN",
+                        &format!("{table_name}.{}", self.quote_identifier(&column.name)),
+                        &column.comment,
+                    ));
+                }
+            }
+        }
+        stmts.into_iter().map(step).collect()
+    }
+
+    fn alter_column(
+        &self,
+        schema: &str,
+        table: &str,
+        old: &store::ColumnMetadata,
+        new: &store::ColumnMetadata,
+        attributes: &[ColumnAttribute],
+    ) -> Vec<MigrationStep> {
+        let table_name = self.qualified_name(schema, table);
+        let narrowing = attributes.contains(&ColumnAttribute::Type)
+            && !is_type_widening(&old.r#type, &new.r#type);
+        let set_not_null = attributes.contains(&ColumnAttribute::Nullable) && !new.nullable;
+
+        if self.dialect == Dialect::MySQL {
+            let collation = find_table(self.target, schema, table)
+                .and_then(|t| t.collation.as_deref())
+                .unwrap_or_default();
+            let mut destructive = vec![];
+            if narrowing {
+                destructive.push(Destructive::TypeNarrowing);
+            }
+            if set_not_null {
+                destructive.push(Destructive::SetNotNull);
+            }
+            return vec![destructive_step(
+                format!(
+                    "ALTER TABLE {table_name} MODIFY COLUMN {};",
+                    ddl::mysql::column_definition(
+                        new,
+                        ddl::mysql::charset_of(collation),
+                        collation
+                    )
+                ),
+                destructive,
+            )];
+        }
+
+        let column = self.quote_identifier(&new.name);
+        let alter =
+            |action: String| format!("ALTER TABLE {table_name} ALTER COLUMN {column} {action};");
+        let mut steps = vec![];
+
+        let identity_changed = attributes.contains(&ColumnAttribute::IdentityGeneration);
+        // An identity is dropped before the column gets a default and added once the default is gone.
+        if identity_changed && new.identity_generation == store::IdentityGeneration::UNSPECIFIED {
+            steps.push(step(alter("DROP IDENTITY".to_string())));
+        }
+        if attributes.contains(&ColumnAttribute::Type)
+            || attributes.contains(&ColumnAttribute::Collation)
+        {
+            let mut action = format!("TYPE {}", new.r#type);
+            if !new.collation.is_empty() {
+                action.push_str(&format!(
+                    " COLLATE {}",
+                    self.quote_identifier(&new.collation)
+                ));
+            }
+            let destructive = if narrowing {
+                vec![Destructive::TypeNarrowing]
+            } else {
+                vec![]
+            };
+            steps.push(destructive_step(alter(action), destructive));
+        }
+        if attributes.contains(&ColumnAttribute::Default) {
+            if new.default.is_empty() {
+                steps.push(step(alter("DROP DEFAULT".to_string())));
+            } else {
+                if let Some(seq) = ddl::postgres::sequence_of(&new.default) {
+                    steps.push(step(format!("CREATE SEQUENCE IF NOT EXISTS {seq};")));
+                }
+                steps.push(step(alter(format!("SET DEFAULT {}", new.default))));
+            }
+        }
+        if attributes.contains(&ColumnAttribute::Nullable) {
+            if new.nullable {
+                steps.push(step(alter("DROP NOT NULL".to_string())));
+            } else {
+                steps.push(destructive_step(
+                    alter("SET NOT NULL".to_string()),
+                    vec![Destructive::SetNotNull],
+                ));
+            }
+        }
+        if identity_changed {
+            let generation = match new.identity_generation {
+                store::IdentityGeneration::Always => "ALWAYS",
+                store::IdentityGeneration::ByDefault => "BY DEFAULT",
+                store::IdentityGeneration::UNSPECIFIED => "",
+            };
+            if old.identity_generation == store::IdentityGeneration::UNSPECIFIED {
+                steps.push(step(alter(format!(
+                    "ADD GENERATED {generation} AS IDENTITY"
+                ))));
+            } else if !generation.is_empty() {
+                steps.push(step(alter(format!("SET GENERATED {generation}"))));
+            }
+        }
+        if attributes.contains(&ColumnAttribute::Comment) {
+            steps.extend(
+                self.comment_on("COLUMN", &format!("{table_name}.{column}"), &new.comment)
+                    .into_iter()
+                    .map(step),
+            );
+        }
+        steps
+    }
+
+    fn create_index(
+        &self,
+        schema: &str,
+        table: &str,
+        index: &store::IndexMetadata,
+    ) -> Vec<MigrationStep> {
+        let table_name = self.qualified_name(schema, table);
+        let mut stmts = vec![];
+        match self.dialect {
+            Dialect::MySQL => {
+                stmts.push(format!(
+                    "ALTER TABLE {table_name} ADD {};",
+                    ddl::mysql::index_definition(index)
+                ));
+            }
+            Dialect::PostgreSQL => {
+                if index.primary {
+                    // The key columns are stored as returned by pg_get_indexdef, so they are already quoted.
+                    stmts.push(format!(
+                        "ALTER TABLE {table_name} ADD CONSTRAINT {} PRIMARY KEY ({});",
+                        self.quote_identifier(&index.name),
+                        index.expressions.join(", ")
+                    ));
+                } else {
+                    let partitioned = find_table(self.target, schema, table)
+                        .is_some_and(|t| t.partitioning.is_some());
+                    stmts.push(ddl::postgres::create_index(index, partitioned));
+                }
+                stmts.extend(self.comment_on(
+                    "INDEX",
+                    &self.qualified_name(schema, &index.name),
+                    &index.comment,
+                ));
+            }
+        }
+        stmts.into_iter().map(step).collect()
+    }
+
+    fn drop_index(
+        &self,
+        schema: &str,
+        table: &str,
+        index: &store::IndexMetadata,
+    ) -> Vec<MigrationStep> {
+        let table_name = self.qualified_name(schema, table);
+        let index_name = self.quote_identifier(&index.name);
+        let stmts = match self.dialect {
+            Dialect::MySQL if index.primary => {
+                vec![format!("ALTER TABLE {table_name} DROP PRIMARY KEY;")]
+            }
+            Dialect::MySQL => vec![format!("ALTER TABLE {table_name} DROP INDEX {index_name};")],
+            Dialect::PostgreSQL if index.primary => {
+                vec![format!(
+                    "ALTER TABLE {table_name} DROP CONSTRAINT {index_name};"
+                )]
+            }
+            // A unique index may back a unique constraint, which cannot be dropped with DROP INDEX.
+            // Only one of the statements has an effect.
+            Dialect::PostgreSQL if index.unique => vec![
+                format!("ALTER TABLE {table_name} DROP CONSTRAINT IF EXISTS {index_name};"),
+                format!(
+                    "DROP INDEX IF EXISTS {};",
+                    self.qualified_name(schema, &index.name)
+                ),
+            ],
+            Dialect::PostgreSQL => vec![format!(
+                "DROP INDEX {};",
+                self.qualified_name(schema, &index.name)
+            )],
+        };
+        stmts.into_iter().map(step).collect()
+    }
+
+    fn add_foreign_key(
+        &self,
+        schema: &str,
+        table: &str,
+        fk: &store::ForeignKeyMetadata,
+    ) -> MigrationStep {
+        match self.dialect {
+            Dialect::MySQL => step(format!(
+                "ALTER TABLE {} ADD {};",
+                self.qualified_name(schema, table),
+                ddl::mysql::foreign_key_definition(fk)
+            )),
+            Dialect::PostgreSQL => step(ddl::postgres::add_foreign_key(schema, table, fk)),
+        }
+    }
+
+    fn drop_foreign_key(&self, table_name: &str, fk: &store::ForeignKeyMetadata) -> MigrationStep {
+        let kind = match self.dialect {
+            Dialect::MySQL => "FOREIGN KEY",
+            Dialect::PostgreSQL => "CONSTRAINT",
+        };
+        step(format!(
+            "ALTER TABLE {table_name} DROP {kind} {};",
+            self.quote_identifier(&fk.name)
+        ))
+    }
+
+    fn create_trigger(
+        &self,
+        schema: &str,
+        table: &str,
+        trigger: &store::TriggerMetadata,
+    ) -> Vec<MigrationStep> {
+        match self.dialect {
+            Dialect::MySQL => vec![routine_definition(&trigger.definition)],
+            Dialect::PostgreSQL => ddl::postgres::create_trigger(schema, table, trigger)
+                .into_iter()
+                .map(step)
+                .collect(),
+        }
+    }
+
+    // MySQL trigger names are unique in a database, PG ones in a table.
+    fn drop_trigger(&self, table_name: &str, trigger: &store::TriggerMetadata) -> MigrationStep {
+        let name = self.quote_identifier(&trigger.name);
+        match self.dialect {
+            Dialect::MySQL => step(format!("DROP TRIGGER {name};")),
+            Dialect::PostgreSQL => step(format!("DROP TRIGGER {name} ON {table_name};")),
+        }
+    }
+
+    fn create_relation(&self, schema: &str, relation: &Relation) -> Vec<MigrationStep> {
+        let stmts = match (self.dialect, relation) {
+            (Dialect::MySQL, Relation::View(v)) => {
+                vec![format!("{};", ddl::mysql::create_view(v))]
+            }
+            (Dialect::PostgreSQL, Relation::View(v)) => ddl::postgres::create_view(schema, v),
+            (Dialect::PostgreSQL, Relation::MaterializedView(v)) => {
+                ddl::postgres::create_materialized_view(schema, v)
+            }
+            // MySQL has no materialized views.
+            (Dialect::MySQL, Relation::MaterializedView(_)) => vec![],
+        };
+        stmts.into_iter().map(step).collect()
+    }
+
+    fn drop_relation(&self, schema: &str, relation: &Relation) -> MigrationStep {
+        let kind = match relation {
+            Relation::View(_) => "VIEW",
+            Relation::MaterializedView(_) => "MATERIALIZED VIEW",
+        };
+        step(format!(
+            "DROP {kind} {};",
+            self.qualified_name(schema, relation.name())
+        ))
+    }
+
+    fn create_routine(&mut self, schema: &str, routine: &Routine) {
+        self.create_routines
+            .push(routine_definition(routine.definition()));
+        if !routine.comment().is_empty() {
+            let stmts = self.comment_on(
+                routine.kind(),
+                &self.routine_name(schema, routine),
+                routine.comment(),
+            );
+            self.create_routines.extend(stmts.into_iter().map(step));
+        }
+    }
+
+    // A changed definition recreates a routine. Otherwise only the comment or the owner of a PG
+    // routine changed, MySQL definitions carry both.
+    fn alter_routine(&mut self, schema: &str, old: &Routine, new: &Routine) {
+        if old.definition() != new.definition() {
+            let stmt = self.drop_routine(schema, old);
+            self.drop_routines.push(stmt);
+            self.create_routine(schema, new);
+            return;
+        }
+        let name = self.routine_name(schema, new);
+        if old.comment() != new.comment() {
+            let stmts = self.comment_on(new.kind(), &name, new.comment());
+            self.create_routines.extend(stmts.into_iter().map(step));
+        }
+        if self.dialect == Dialect::PostgreSQL
+            && old.owner() != new.owner()
+            && !new.owner().is_empty()
+        {
+            self.create_routines.push(step(format!(
+                "ALTER {} {name} OWNER TO {};",
+                new.kind(),
+                self.quote_identifier(new.owner())
+            )));
+        }
+    }
+
+    fn drop_event(&self, event: &store::EventMetadata) -> MigrationStep {
+        step(format!(
+            "DROP EVENT {};",
+            self.quote_identifier(&event.name)
+        ))
+    }
+
+    fn drop_routine(&self, schema: &str, routine: &Routine) -> MigrationStep {
+        step(format!(
+            "DROP {} {};",
+            routine.kind(),
+            self.routine_name(schema, routine)
+        ))
+    }
+
+    // The PG name carries the argument types of the signature, which pick an overload.
+    fn routine_name(&self, schema: &str, routine: &Routine) -> String {
+        match self.dialect {
+            Dialect::MySQL => self.quote_identifier(routine.name()),
+            Dialect::PostgreSQL => {
+                ddl::postgres::routine_name(schema, routine.name(), routine.signature())
+            }
+        }
+    }
+
+    // Renders a COMMENT ON statement, MySQL comments are part of the object definition instead.
+    fn comment_on(&self, kind: &str, object: &str, comment: &str) -> Vec<String> {
+        if self.dialect == Dialect::MySQL {
+            return vec![];
+        }
+        let comment = if comment.is_empty() {
+            "NULL".to_string()
+        } else {
+            ddl::postgres::quote_string(comment)
+        };
+        vec![format!("COMMENT ON {kind} {object} IS {comment};")]
+    }
+
+    fn qualified_name(&self, schema: &str, name: &str) -> String {
+        match self.dialect {
+            Dialect::MySQL => ddl::mysql::quote_identifier(name),
+            Dialect::PostgreSQL => ddl::postgres::qualified_name(schema, name),
+        }
+    }
+
+    fn quote_identifier(&self, name: &str) -> String {
+        match self.dialect {
+            Dialect::MySQL => ddl::mysql::quote_identifier(name),
+            Dialect::PostgreSQL => ddl::postgres::quote_identifier(name),
+        }
+    }
+}
+
+fn step(statement: String) -> MigrationStep {
+    destructive_step(statement, vec![])
+}
+
+fn destructive_step(statement: String, destructive: Vec<Destructive>) -> MigrationStep {
+    MigrationStep {
+        statement,
+        destructive,
+    }
+}
+
+// The definition is a complete CREATE statement without a terminating semicolon.
+fn routine_definition(definition: &str) -> MigrationStep {
+    step(format!("{};", definition.trim_end()))
+}
+
+// RelationKey is the schema and the name of a relation.
+type RelationKey<'a> = (&'a str, &'a str);
+
+// Returns the relations in an order where every relation follows the relations it depends on.
+fn ordered_relations(relations: &[(String, Relation)]) -> Vec<(&str, &Relation)> {
+    let keys: Vec<(RelationKey, Vec<RelationKey>)> = relations
+        .iter()
+        .map(|(schema, relation)| {
+            let dependencies = relation
+                .dependent_columns()
+                .iter()
+                .map(|c| (c.schema.as_str(), c.table.as_str()))
+                .collect();
+            ((schema.as_str(), relation.name()), dependencies)
+        })
+        .collect();
+
+    ddl::dependency_order(&keys)
+        .into_iter()
+        .filter_map(|(schema, name)| {
+            relations
+                .iter()
+                .find(|(s, r)| s == schema && r.name() == name)
+                .map(|(s, r)| (s.as_str(), r))
+        })
+        .collect()
+}
+
+// Returns the unchanged views and materialized views which have to be recreated because
+// an object they depend on is dropped, recreated or has its type changed.
+fn views_to_recreate(
+    source: &store::DatabaseSchemaMetadata,
+    target: &store::DatabaseSchemaMetadata,
+    changes: &[diff::Change],
+) -> Vec<(String, String)> {
+    let mut columns = HashSet::<(&str, &str, &str)>::new();
+    let mut relations = HashSet::<(&str, &str)>::new();
+    for change in changes {
+        let schema = change.schema.as_str();
+        match &change.kind {
+            ChangeKind::ColumnDropped(c) => {
+                columns.insert((schema, &change.table, &c.name));
+            }
+            ChangeKind::ColumnChanged {
+                new, attributes, ..
+            } if attributes.iter().any(|a| {
+                matches!(
+                    a,
+                    ColumnAttribute::Type
+                        | ColumnAttribute::CharacterSet
+                        | ColumnAttribute::Collation
+                )
+            }) =>
+            {
+                columns.insert((schema, &change.table, &new.name));
+            }
+            ChangeKind::TableDropped(t) => {
+                relations.insert((schema, &t.name));
+            }
+            ChangeKind::TableRenameCandidate { old, .. } => {
+                relations.insert((schema, &old.name));
+            }
+            ChangeKind::ViewDropped(_)
+            | ChangeKind::ViewChanged { .. }
+            | ChangeKind::MaterializedViewDropped(_)
+            | ChangeKind::MaterializedViewChanged { .. } => {
+                relations.insert((schema, &change.table));
+            }
+            _ => {}
+        }
+    }
+
+    let source_relations: Vec<(&str, Relation)> = source
+        .schemas
+        .iter()
+        .flat_map(|s| {
+            s.views
+                .iter()
+                .map(|v| (s.name.as_str(), Relation::View(v.clone())))
+                .chain(
+                    s.materialized_views
+                        .iter()
+                        .map(|v| (s.name.as_str(), Relation::MaterializedView(v.clone()))),
+                )
+        })
+        .collect();
+
+    let mut recreated = vec![];
+    loop {
+        let found: Vec<(&str, &str)> = source_relations
+            .iter()
+            .filter(|(schema, relation)| {
+                !relations.contains(&(schema, relation.name()))
+                    && find_relation(target, schema, relation.name()).is_some()
+                    && relation.dependent_columns().iter().any(|c| {
+                        relations.contains(&(c.schema.as_str(), c.table.as_str()))
+                            || columns.contains(&(
+                                c.schema.as_str(),
+                                c.table.as_str(),
+                                c.column.as_str(),
+                            ))
+                    })
+            })
+            .map(|(schema, relation)| (*schema, relation.name()))
+            .collect();
+        if found.is_empty() {
+            break;
+        }
+        for key in found {
+            relations.insert(key);
+            recreated.push((key.0.to_string(), key.1.to_string()));
+        }
+    }
+
+    recreated
+}
+
+// recreate_repartitioned_tables replaces the changes of each table whose partition strategy or
+// key changes with the drop and the creation of the table, PostgreSQL only attaches and detaches
+// partitions of an existing table.
+fn recreate_repartitioned_tables(changes: Vec<diff::Change>) -> Vec<diff::Change> {
+    let repartitioned: HashSet<(String, String)> = changes
+        .iter()
+        .filter(|change| match &change.kind {
+            ChangeKind::TableChanged { old, new, .. } => {
+                !same_partition_key(old.partitioning.as_ref(), new.partitioning.as_ref())
+            }
+            _ => false,
+        })
+        .map(|change| (change.schema.clone(), change.table.clone()))
+        .collect();
+
+    let mut rewritten = vec![];
+    for change in changes {
+        if !repartitioned.contains(&(change.schema.clone(), change.table.clone())) {
+            rewritten.push(change);
+            continue;
+        }
+        if let ChangeKind::TableChanged { old, new, .. } = change.kind {
+            rewritten.push(diff::Change {
+                schema: change.schema.clone(),
+                table: old.name.clone(),
+                kind: ChangeKind::TableDropped(old),
+            });
+            rewritten.push(diff::Change {
+                schema: change.schema,
+                table: change.table,
+                kind: ChangeKind::TableAdded(new),
+            });
+        }
+    }
+    rewritten
+}
+
+// same_partition_key reports whether two partitionings share the strategy and key, or are both
+// missing.
+fn same_partition_key(
+    old: Option<&store::PartitioningMetadata>,
+    new: Option<&store::PartitioningMetadata>,
+) -> bool {
+    match (old, new) {
+        (None, None) => true,
+        (Some(o), Some(n)) => o.strategy == n.strategy && o.expression == n.expression,
+        _ => false,
+    }
+}
+
+fn find_table<'a>(
+    database: &'a store::DatabaseSchemaMetadata,
+    schema: &str,
+    table: &str,
+) -> Option<&'a store::TableMetadata> {
+    database
+        .schemas
+        .iter()
+        .find(|s| s.name == schema)
+        .and_then(|s| s.tables.iter().find(|t| t.name == table))
+}
+
+fn find_relation(
+    database: &store::DatabaseSchemaMetadata,
+    schema: &str,
+    name: &str,
+) -> Option<Relation> {
+    let schema = database.schemas.iter().find(|s| s.name == schema)?;
+    if let Some(v) = schema.views.iter().find(|v| v.name == name) {
+        return Some(Relation::View(v.clone()));
+    }
+    schema
+        .materialized_views
+        .iter()
+        .find(|v| v.name == name)
+        .map(|v| Relation::MaterializedView(v.clone()))
+}
+
+// ColumnType is a column type split into its name, its length, precision or scale
+// arguments and whether it is unsigned.
+struct ColumnType {
+    name: String,
+    args: Vec<i64>,
+    unsigned: bool,
+}
+
+impl ColumnType {
+    fn parse(s: &str) -> ColumnType {
+        let lower = s.trim().to_lowercase();
+        let unsigned = lower.contains(" unsigned");
+        let lower = lower.replace(" unsigned", "").replace(" zerofill", "");
+        match lower.split_once('(') {
+            Some((name, rest)) => ColumnType {
+                name: name.trim().to_string(),
+                args: rest
+                    .trim_end_matches(')')
+                    .split(',')
+                    .filter_map(|a| a.trim().parse().ok())
+                    .collect(),
+                unsigned,
+            },
+            None => ColumnType {
+                name: lower.trim().to_string(),
+                args: vec![],
+                unsigned,
+            },
+        }
+    }
+}
+
+// is_type_widening returns whether every value of the old column type fits the new one.
+// Unknown type changes are treated as narrowing.
+fn is_type_widening(old: &str, new: &str) -> bool {
+    let old = ColumnType::parse(old);
+    let new = ColumnType::parse(new);
+    if old.unsigned != new.unsigned {
+        return false;
+    }
+
+    let integer_rank = |name: &str| match name {
+        "tinyint" => Some(1),
+        "smallint" | "int2" => Some(2),
+        "mediumint" => Some(3),
+        "int" | "integer" | "int4" => Some(4),
+        "bigint" | "int8" => Some(5),
+        _ => None,
+    };
+    let float_rank = |name: &str| match name {
+        "float" | "real" | "float4" => Some(1),
+        "double" | "double precision" | "float8" => Some(2),
+        _ => None,
+    };
+    let text_rank = |name: &str| match name {
+        "tinytext" => Some(1),
+        "text" => Some(2),
+        "mediumtext" => Some(3),
+        "longtext" => Some(4),
+        _ => None,
+    };
+    let is_character =
+        |name: &str| matches!(name, "char" | "varchar" | "character" | "character varying");
+    // A missing length is unbounded for PostgreSQL character varying, a longer length widens.
+    let length_widens =
+        |old: &ColumnType, new: &ColumnType| match (old.args.first(), new.args.first()) {
+            (_, None) => true,
+            (None, Some(_)) => false,
+            (Some(o), Some(n)) => n >= o,
+        };
+
+    if let (Some(o), Some(n)) = (integer_rank(&old.name), integer_rank(&new.name)) {
+        // MySQL display widths such as int(11) do not limit the range.
+        return n >= o;
+    }
+    if let (Some(o), Some(n)) = (float_rank(&old.name), float_rank(&new.name)) {
+        return n >= o;
+    }
+    if let (Some(o), Some(n)) = (text_rank(&old.name), text_rank(&new.name)) {
+        return n >= o;
+    }
+    if is_character(&old.name) {
+        if is_character(&new.name) {
+            return length_widens(&old, &new);
+        }
+        return match text_rank(&new.name) {
+            // TINYTEXT holds at most 255 bytes.
+            Some(1) => old.args.first().is_some_and(|l| *l <= 255),
+            Some(_) => true,
+            None => false,
+        };
+    }
+    if matches!(old.name.as_str(), "decimal" | "numeric")
+        && matches!(new.name.as_str(), "decimal" | "numeric")
+    {
+        return match (old.args.as_slice(), new.args.as_slice()) {
+            (_, []) => true,
+            ([], _) => false,
+            (o, n) => {
+                let (op, os) = (o[0], o.get(1).copied().unwrap_or(0));
+                let (np, ns) = (n[0], n.get(1).copied().unwrap_or(0));
+                ns >= os && np - ns >= op - os
+            }
+        };
+    }
+    if old.name == new.name {
+        return length_widens(&old, &new);
+    }
+
+    false
+}
+
+#[cfg(test)]
+mod test {
+    use super::{is_type_widening, plan_migration, Destructive, Dialect, MigrationStep};
+    use crate::db::builder::{column, database, index, schema, table};
+    use crate::db::store::{
+        ColumnMetadata, DependentColumn, EventMetadata, ForeignKeyMetadata, FunctionMetadata,
+        IdentityGeneration, MaterializedViewMetadata, PartitionMetadata, PartitionStrategy,
+        PartitioningMetadata, ProcedureMetadata, SecurityType, TableMetadata, TriggerMetadata,
+        ViewMetadata, Volatility,
+    };
+
+    fn dependent_columns(schema: &str, table: &str, column: &str) -> Vec<DependentColumn> {
+        vec![DependentColumn {
+            schema: schema.to_string(),
+            table: table.to_string(),
+            column: column.to_string(),
+        }]
+    }
+
+    fn statements(steps: &[MigrationStep]) -> Vec<&str> {
+        steps.iter().map(|s| s.statement.as_str()).collect()
+    }
+
+    fn destructive(steps: &[MigrationStep]) -> Vec<(&str, Vec<Destructive>)> {
+        steps
+            .iter()
+            .filter(|s| s.is_destructive())
+            .map(|s| (s.statement.as_str(), s.destructive.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn test_plan_mysql() {
+        let mysql_table = |name: &str, columns: Vec<ColumnMetadata>| TableMetadata {
+            engine: "InnoDB".to_string(),
+            collation: Some("utf8mb4_general_ci".to_string()),
+            ..table(name, columns)
+        };
+        let mut id = column("id", 1, "int", false);
+        id.default = "AUTO_INCREMENT".to_string();
+        let mut email = column("email", 2, "varchar(255)", true);
+        email.default = "NULL".to_string();
+
+        let mut customers = mysql_table(
+            "customers",
+            vec![
+                id.clone(),
+                email.clone(),
+                column("name", 3, "varchar(100)", false),
+            ],
+        );
+        customers.indexes = vec![
+            index("PRIMARY", &["id"], true, true),
+            index("idx_name", &["name"], false, false),
+        ];
+        let trigger = |name: &str, table: &str, statement: &str| {
+            TriggerMetadata {
+            name: name.to_string(),
+            timing: "BEFORE".to_string(),
+            events: vec!["INSERT".to_string()],
+            for_each_row: true,
+            definition: format!(
+                "CREATE DEFINER=`root`@`%` TRIGGER `{name}` BEFORE INSERT ON `{table}` FOR EACH ROW {statement}"
+            ),
+            enabled: true,
+        }
+        };
+        customers.triggers = vec![trigger(
+            "trg_trim_name",
+            "customers",
+            "SET NEW.name = TRIM(NEW.name)",
+        )];
+        let view = ViewMetadata {
+            name: "v_names".to_string(),
+            definition: "select `test_db`.`customers`.`name` AS `name` from `test_db`.`customers`"
+                .to_string(),
+            comment: String::new(),
+            dependent_columns: dependent_columns("", "customers", "name"),
+        };
+        let mut source_schema = schema(
+            "",
+            vec![
+                customers.clone(),
+                mysql_table("legacy", vec![column("id", 1, "int", false)]),
+            ],
+        );
+        source_schema.views = vec![view.clone()];
+
+        let mut phone = column("phone", 3, "varchar(20)", true);
+        phone.default = "NULL".to_string();
+        customers.columns = vec![
+            id,
+            ColumnMetadata {
+                nullable: false,
+                default: String::new(),
+                ..email
+            },
+            phone,
+            column("name", 4, "varchar(50)", false),
+        ];
+        customers.indexes.truncate(1);
+        customers.triggers = vec![trigger(
+            "trg_trim_name",
+            "customers",
+            "SET NEW.name = UPPER(TRIM(NEW.name))",
+        )];
+        let mut orders = mysql_table(
+            "orders",
+            vec![
+                column("id", 1, "int", false),
+                column("customer_id", 2, "int", false),
+            ],
+        );
+        orders.indexes = vec![index("PRIMARY", &["id"], true, true)];
+        orders.foreign_keys = vec![ForeignKeyMetadata {
+            name: "fk_orders_customer".to_string(),
+            columns: vec!["customer_id".to_string()],
+            referenced_schema: String::new(),
+            referenced_table: "customers".to_string(),
+            referenced_columns: vec!["id".to_string()],
+            on_delete: "CASCADE".to_string(),
+            on_update: "NO ACTION".to_string(),
+            match_type: "NONE".to_string(),
+        }];
+        orders.triggers = vec![trigger(
+            "trg_order_id",
+            "orders",
+            "SET NEW.id = IFNULL(NEW.id, 0)",
+        )];
+        let mut target_schema = schema("", vec![customers, orders]);
+        target_schema.views = vec![view];
+        let event = |status: &str, status_clause: &str| {
+            EventMetadata {
+            name: "purge_orders".to_string(),
+            definition: format!(
+                "CREATE DEFINER=`root`@`%` EVENT `purge_orders` ON SCHEDULE EVERY 1 DAY ON COMPLETION NOT PRESERVE {status_clause} DO DELETE FROM `orders`"
+            ),
+            schedule: "EVERY 1 DAY".to_string(),
+            time_zone: "SYSTEM".to_string(),
+            status: status.to_string(),
+            on_completion: "NOT PRESERVE".to_string(),
+            definer: "root@%".to_string(),
+            body: "DELETE FROM `orders`".to_string(),
+            comment: String::new(),
+        }
+        };
+        source_schema.events = vec![event("ENABLED", "ENABLE")];
+        target_schema.events = vec![event("DISABLED", "DISABLE")];
+
+        let steps = plan_migration(
+            Dialect::MySQL,
+            &database(vec![source_schema]),
+            &database(vec![target_schema]),
+        );
+        assert_eq!(
+            statements(&steps),
+            vec![
+                "DROP TRIGGER `trg_trim_name`;",
+                "DROP VIEW `v_names`;",
+                "DROP EVENT `purge_orders`;",
+                "DROP TABLE `legacy`;",
+                "CREATE TABLE `orders` (
+  `id` int NOT NULL,
+  `customer_id` int NOT NULL,
+  PRIMARY KEY (`id`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_general_ci;",
+                "ALTER TABLE `customers` DROP INDEX `idx_name`;",
+                "ALTER TABLE `customers` ADD COLUMN `phone` varchar(20) DEFAULT NULL AFTER `email`;",
+                "ALTER TABLE `customers` MODIFY COLUMN `email` varchar(255) NOT NULL;",
+                "ALTER TABLE `customers` MODIFY COLUMN `name` varchar(50) NOT NULL;",
+                "CREATE DEFINER=`root`@`%` EVENT `purge_orders` ON SCHEDULE EVERY 1 DAY ON COMPLETION NOT PRESERVE DISABLE DO DELETE FROM `orders`;",
+                "CREATE DEFINER=`root`@`%` TRIGGER `trg_order_id` BEFORE INSERT ON `orders` FOR EACH ROW SET NEW.id = IFNULL(NEW.id, 0);",
+                "CREATE DEFINER=`root`@`%` TRIGGER `trg_trim_name` BEFORE INSERT ON `customers` FOR EACH ROW SET NEW.name = UPPER(TRIM(NEW.name));",
+                "CREATE VIEW `v_names` AS select `test_db`.`customers`.`name` AS `name` from `test_db`.`customers`;",
+                "ALTER TABLE `orders` ADD CONSTRAINT `fk_orders_customer` FOREIGN KEY (`customer_id`) REFERENCES `customers` (`id`) ON DELETE CASCADE;",
+            ]
+        );
+        assert_eq!(
+            destructive(&steps),
+            vec![
+                ("DROP TABLE `legacy`;", vec![Destructive::DropTable]),
+                (
+                    "ALTER TABLE `customers` MODIFY COLUMN `email` varchar(255) NOT NULL;",
+                    vec![Destructive::SetNotNull]
+                ),
+                (
+                    "ALTER TABLE `customers` MODIFY COLUMN `name` varchar(50) NOT NULL;",
+                    vec![Destructive::TypeNarrowing]
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_plan_postgres() {
+        let mut id = column("id", 1, "integer", false);
+        id.default = "nextval('app.items_id_seq'::regclass)".to_string();
+        let mut items = table(
+            "items",
+            vec![
+                id,
+                column("price", 2, "numeric(10,2)", true),
+                column("label", 3, "text", true),
+            ],
+        );
+        items.indexes = vec![
+            index("items_pkey", &["id"], true, true),
+            index("items_label_key", &["label"], false, true),
+        ];
+        let trigger = |name: &str| {
+            TriggerMetadata {
+            name: name.to_string(),
+            timing: "BEFORE".to_string(),
+            events: vec!["UPDATE".to_string()],
+            for_each_row: true,
+            definition: format!(
+                "CREATE TRIGGER {name} BEFORE UPDATE ON app.items FOR EACH ROW EXECUTE FUNCTION app.touch()"
+            ),
+            enabled: true,
+        }
+        };
+        items.triggers = vec![trigger("trg_old"), trigger("trg_touch")];
+        let function = |definition: &str| FunctionMetadata {
+            name: "f".to_string(),
+            signature: "f(a integer)".to_string(),
+            definition: definition.to_string(),
+            parameters: vec![],
+            return_type: String::new(),
+            language: String::new(),
+            volatility: Volatility::UNSPECIFIED,
+            security: SecurityType::UNSPECIFIED,
+            owner: String::new(),
+            comment: String::new(),
+        };
+        let mut source_app = schema("app", vec![items.clone()]);
+        source_app.views = vec![ViewMetadata {
+            name: "item_labels".to_string(),
+            definition: " SELECT label\n   FROM app.items;".to_string(),
+            comment: String::new(),
+            dependent_columns: dependent_columns("app", "items", "label"),
+        }];
+        source_app.materialized_views = vec![MaterializedViewMetadata {
+            name: "item_prices".to_string(),
+            definition: " SELECT price\n   FROM app.items;".to_string(),
+            comment: String::new(),
+            dependent_columns: dependent_columns("app", "items", "price"),
+        }];
+        source_app.functions = vec![function(
+            "CREATE OR REPLACE FUNCTION app.f(a integer)\n RETURNS integer\n LANGUAGE sql\nAS $function$ SELECT a $function$\n",
+        )];
+
+        source_app.procedures = vec![ProcedureMetadata {
+            name: "cleanup".to_string(),
+            signature: "cleanup()".to_string(),
+            definition: "CREATE OR REPLACE PROCEDURE app.cleanup()\n LANGUAGE sql\nAS $procedure$ SELECT 1 $procedure$\n".to_string(),
+            parameters: vec![],
+            language: "sql".to_string(),
+            volatility: Volatility::Volatile,
+            security: SecurityType::Invoker,
+            owner: "app".to_string(),
+            comment: String::new(),
+        }];
+
+        let mut target_app = source_app.clone();
+        target_app.procedures[0].owner = "admin".to_string();
+        target_app.procedures[0].comment = "Removes stale rows".to_string();
+        items.columns[0].default = String::new();
+        items.columns[0].identity_generation = IdentityGeneration::Always;
+        items.columns[1].r#type = "numeric(12,2)".to_string();
+        items.columns[2].comment = "Display label".to_string();
+        items.indexes.truncate(1);
+        items.triggers = vec![TriggerMetadata {
+            enabled: false,
+            ..trigger("trg_touch")
+        }];
+        target_app.tables = vec![items];
+        target_app.functions = vec![FunctionMetadata {
+            comment: "Adds one".to_string(),
+            ..function(
+                "CREATE OR REPLACE FUNCTION app.f(a integer)\n RETURNS integer\n LANGUAGE sql\nAS $function$ SELECT a + 1 $function$\n",
+            )
+        }];
+
+        let steps = plan_migration(
+            Dialect::PostgreSQL,
+            &database(vec![source_app, schema("old", vec![])]),
+            &database(vec![
+                target_app,
+                schema(
+                    "audit",
+                    vec![table("log", vec![column("id", 1, "bigint", false)])],
+                ),
+            ]),
+        );
+        assert_eq!(
+            statements(&steps),
+            vec![
+                r#"DROP TRIGGER "trg_old" ON "app"."items";"#,
+                r#"DROP MATERIALIZED VIEW "app"."item_prices";"#,
+                r#"DROP FUNCTION "app"."f"(a integer);"#,
+                r#"CREATE SCHEMA "audit";"#,
+                "CREATE TABLE \"audit\".\"log\" (\n    \"id\" bigint NOT NULL\n);",
+                r#"ALTER TABLE "app"."items" DROP CONSTRAINT IF EXISTS "items_label_key";"#,
+                r#"DROP INDEX IF EXISTS "app"."items_label_key";"#,
+                r#"ALTER TABLE "app"."items" ALTER COLUMN "id" DROP DEFAULT;"#,
+                r#"ALTER TABLE "app"."items" ALTER COLUMN "id" ADD GENERATED ALWAYS AS IDENTITY;"#,
+                r#"ALTER TABLE "app"."items" ALTER COLUMN "price" TYPE numeric(12,2);"#,
+                r#"COMMENT ON COLUMN "app"."items"."label" IS 'Display label';"#,
+                "CREATE OR REPLACE FUNCTION app.f(a integer)\n RETURNS integer\n LANGUAGE sql\nAS $function$ SELECT a + 1 $function$;",
+                r#"COMMENT ON FUNCTION "app"."f"(a integer) IS 'Adds one';"#,
+                r#"COMMENT ON PROCEDURE "app"."cleanup"() IS 'Removes stale rows';"#,
+                r#"ALTER PROCEDURE "app"."cleanup"() OWNER TO "admin";"#,
+                r#"ALTER TABLE "app"."items" DISABLE TRIGGER "trg_touch";"#,
+                "CREATE MATERIALIZED VIEW \"app\".\"item_prices\" AS\nSELECT price\n   FROM app.items;",
+                r#"DROP SCHEMA "old";"#,
+            ]
+        );
+        assert_eq!(
+            destructive(&steps),
+            vec![(r#"DROP SCHEMA "old";"#, vec![Destructive::DropSchema])]
+        );
+    }
+
+    #[test]
+    fn test_plan_partitions() {
+        let partition = |name: &str, value: &str| PartitionMetadata {
+            name: name.to_string(),
+            value: value.to_string(),
+            subpartitioning: None,
+            row_count: 0,
+        };
+        let partitioning = |strategy: PartitionStrategy, expression: &str, partitions| {
+            Some(PartitioningMetadata {
+                strategy,
+                expression: expression.to_string(),
+                partitions,
+            })
+        };
+
+        let mut archive = TableMetadata {
+            engine: "InnoDB".to_string(),
+            ..table("archive", vec![column("year", 1, "int", false)])
+        };
+        archive.partitioning = partitioning(
+            PartitionStrategy::Range,
+            "`year`",
+            vec![partition("p2023", "2024"), partition("pmax", "MAXVALUE")],
+        );
+        let source = database(vec![schema(
+            "",
+            vec![
+                archive.clone(),
+                table("log", vec![column("id", 1, "int", false)]),
+            ],
+        )]);
+        let mut log = table("log", vec![column("id", 1, "int", false)]);
+        log.partitioning = partitioning(PartitionStrategy::Key, "`id`", vec![partition("p0", "")]);
+        archive.partitioning = None;
+        let target = database(vec![schema("", vec![archive, log])]);
+        assert_eq!(
+            statements(&plan_migration(Dialect::MySQL, &source, &target)),
+            vec![
+                "ALTER TABLE `archive` REMOVE PARTITIONING;",
+                "ALTER TABLE `log` PARTITION BY KEY (`id`)\nPARTITIONS 1;",
+            ]
+        );
+
+        let mut events = table("events", vec![column("created_at", 1, "date", false)]);
+        events.partitioning = partitioning(
+            PartitionStrategy::Range,
+            "created_at",
+            vec![
+                partition(
+                    "events_2024",
+                    "FOR VALUES FROM ('2024-01-01') TO ('2025-01-01')",
+                ),
+                PartitionMetadata {
+                    subpartitioning: partitioning(
+                        PartitionStrategy::List,
+                        "created_at",
+                        vec![partition("events_2025_q1", "FOR VALUES IN ('2025-01-01')")],
+                    ),
+                    ..partition(
+                        "events_2025",
+                        "FOR VALUES FROM ('2025-01-01') TO ('2026-01-01')",
+                    )
+                },
+                partition("events_default", "DEFAULT"),
+            ],
+        );
+        let mut logs = table("logs", vec![column("id", 1, "bigint", false)]);
+        logs.partitioning = partitioning(
+            PartitionStrategy::Hash,
+            "id",
+            vec![partition(
+                "logs_0",
+                "FOR VALUES WITH (modulus 1, remainder 0)",
+            )],
+        );
+        let source = database(vec![schema("app", vec![events.clone(), logs.clone()])]);
+
+        let partitions = &mut events.partitioning.as_mut().unwrap().partitions;
+        partitions[0].value = "FOR VALUES FROM ('2023-01-01') TO ('2025-01-01')".to_string();
+        partitions[1].subpartitioning.as_mut().unwrap().partitions =
+            vec![partition("events_2025_q2", "FOR VALUES IN ('2025-04-01')")];
+        partitions.pop();
+        logs.partitioning = partitioning(
+            PartitionStrategy::Range,
+            "id",
+            vec![partition(
+                "logs_low",
+                "FOR VALUES FROM (MINVALUE) TO (1000)",
+            )],
+        );
+        let target = database(vec![schema("app", vec![events, logs])]);
+        let steps = plan_migration(Dialect::PostgreSQL, &source, &target);
+        assert_eq!(
+            statements(&steps),
+            vec![
+                r#"DROP TABLE "app"."events_2024";"#,
+                r#"DROP TABLE "app"."events_default";"#,
+                r#"DROP TABLE "app"."events_2025_q1";"#,
+                r#"DROP TABLE "app"."logs";"#,
+                r#"CREATE TABLE "app"."events_2024" PARTITION OF "app"."events" FOR VALUES FROM ('2023-01-01') TO ('2025-01-01');"#,
+                r#"CREATE TABLE "app"."events_2025_q2" PARTITION OF "app"."events_2025" FOR VALUES IN ('2025-04-01');"#,
+                "CREATE TABLE \"app\".\"logs\" (\n    \"id\" bigint NOT NULL\n) PARTITION BY RANGE (id);",
+                r#"CREATE TABLE "app"."logs_low" PARTITION OF "app"."logs" FOR VALUES FROM (MINVALUE) TO (1000);"#,
+            ]
+        );
+        assert_eq!(destructive(&steps).len(), 4);
+    }
+
+    #[test]
+    fn test_is_type_widening() {
+        let cases = [
+            ("int", "bigint", true),
+            ("bigint", "int", false),
+            ("int(11)", "int(10)", true),
+            ("int", "int unsigned", false),
+            ("varchar(100)", "varchar(255)", true),
+            ("varchar(255)", "varchar(100)", false),
+            ("character varying(50)", "character varying", true),
+            ("character varying(50)", "text", true),
+            ("varchar(300)", "tinytext", false),
+            ("text", "varchar(100)", false),
+            ("mediumtext", "longtext", true),
+            ("decimal(10,2)", "decimal(12,2)", true),
+            ("decimal(10,2)", "decimal(10,4)", false),
+            ("numeric(10,2)", "numeric", true),
+            ("float", "double", true),
+            ("integer", "text", false),
+        ];
+        for (old, new, widening) in cases {
+            assert_eq!(is_type_widening(old, new), widening, "{old} -> {new}");
+        }
+    }
+}
@@ -1,13 +1,22 @@
-use self::error::DBError;
+pub use self::error::{DBError, ErrorKind};
 use async_trait::async_trait;
 use std::fmt::Debug;
+use std::time::Duration;
 
+#[cfg(test)]
+mod builder;
+mod config;
+pub mod ddl;
+pub mod diff;
 mod error;
+pub mod migration;
 #[cfg(any(feature = "db-mysql", feature = "db-tidb"))]
 mod mysql;
+pub mod parser;
 #[cfg(feature = "db-postgres")]
 mod postgres;
 pub mod store;
+#[cfg(any(feature = "db-mysql", feature = "db-tidb", feature = "db-postgres"))]
 mod util;
 
 #[derive(Clone, PartialEq, Debug)]
@@ -25,9 +34,55 @@ pub struct ConnectionConfig {
     pub engine: Engine,
     pub host: String,
     pub port: u16,
+    // The socket is the Unix domain socket to connect through instead of host and port.
+    // It is the socket file for MySQL and the directory holding the socket for PostgreSQL,
+    // which still appends the port to the socket name.
+    pub socket: Option<String>,
     pub username: String,
     pub password: String,
     pub database: String,
+    pub tls: TlsConfig,
+    pub pool: PoolConfig,
+    // The params are the query parameters of a connection URL, in order.
+    pub params: Vec<(String, String)>,
+}
+
+// SslMode follows the libpq sslmode values, MySQL maps VerifyFull to VERIFY_IDENTITY.
+// It defaults to Prefer like libpq and the MySQL client, TLS is turned off by Disable only.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum SslMode {
+    Disable,
+    #[default]
+    Prefer,
+    Require,
+    VerifyCa,
+    VerifyFull,
+}
+
+// TlsConfig is the TLS setup of a connection, the certificates are paths to PEM files.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct TlsConfig {
+    pub mode: SslMode,
+    // The ca is the CA bundle the server certificate is verified against.
+    pub ca: Option<String>,
+    // The cert and the key are the client certificate and its private key.
+    pub cert: Option<String>,
+    pub key: Option<String>,
+}
+
+// PoolConfig bounds the connections a driver opens and the time it waits on the server.
+// Unset values keep the sqlx defaults and no statement timeout.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct PoolConfig {
+    pub max_connections: Option<u32>,
+    // The connect_timeout bounds opening the pool when the driver is created.
+    pub connect_timeout: Option<Duration>,
+    // The acquire_timeout bounds waiting for a pooled connection, opening it included.
+    pub acquire_timeout: Option<Duration>,
+    // The statement_timeout bounds every catalog query, it sets statement_timeout on
+    // PostgreSQL and max_execution_time on MySQL. The MySQL SHOW statements, which
+    // max_execution_time does not limit, are killed once it elapses.
+    pub statement_timeout: Option<Duration>,
 }
 
 #[async_trait]
@@ -35,6 +90,11 @@ pub trait DB: Send + Sync + Debug + Unpin + 'static {
     fn get_engine(&self) -> Engine;
     async fn sync_instance(&self) -> Result<store::InstanceMetadata, DBError>;
     async fn sync_database(&self) -> Result<store::DatabaseSchemaMetadata, DBError>;
+    // sync_database_lenient syncs like sync_database, but an object that cannot be loaded is
+    // skipped or loaded partially and reported as a warning instead of failing the sync.
+    async fn sync_database_lenient(
+        &self,
+    ) -> Result<(store::DatabaseSchemaMetadata, Vec<store::SyncWarning>), DBError>;
 }
 
 pub async fn create_driver(cfg: &ConnectionConfig) -> Result<Box<dyn DB>, DBError> {
@@ -1,10 +1,14 @@
+use crate::db::ddl::mysql::{quote_identifier, quote_string, AUTO_INCREMENT_SYMBOL};
+use crate::db::error::{Context, DBError, ErrorKind};
+use crate::db::util;
 use crate::db::{self, Engine};
-use crate::db::{error::DBError, util};
 use async_trait::async_trait;
-use sqlx::{mysql::MySqlPool, Column, Pool, Row};
+use sqlx::mysql::MySqlRow;
+use sqlx::{Column, ConnectOptions, Connection, Executor, FromRow, MySqlConnection, Pool, Row};
 use std::collections::HashMap;
 use std::fmt::Debug;
 use std::fmt::Formatter;
+use std::time::Duration;
 
 use regex::Regex;
 use version_compare::Version;
@@ -16,6 +20,8 @@ pub struct Driver {
     engine: Engine,
     database_name: String,
     pool: Pool<sqlx::MySql>,
+    // The statement_timeout bounds the statements max_execution_time leaves unlimited.
+    statement_timeout: Option<Duration>,
 }
 
 impl Debug for Driver {
@@ -33,13 +39,22 @@ impl db::DB for Driver {
         self.engine.clone()
     }
     async fn sync_instance(&self) -> Result<db::store::InstanceMetadata, DBError> {
-        let (version, _) = self.get_version().await?;
+        let mut conn = self.pool.acquire().await.context("acquire")?;
 
-        let databases = self.load_database().await?;
+        let (version, _) = self.get_version(&mut conn).await.context("get_version")?;
+
+        let databases = self
+            .load_database(&mut conn)
+            .await
+            .context("load_database")?;
+        let instance_roles = self
+            .load_instance_role(&mut conn)
+            .await
+            .context("load_instance_role")?;
 
         let instance = db::store::InstanceMetadata {
             version,
-            instance_roles: vec![],
+            instance_roles,
             databases,
             last_sync: 0,
         };
@@ -48,12 +63,222 @@ impl db::DB for Driver {
     }
 
     async fn sync_database(&self) -> Result<db::store::DatabaseSchemaMetadata, DBError> {
+        self.sync_database_with(&mut util::Warnings::new(false))
+            .await
+    }
+
+    async fn sync_database_lenient(
+        &self,
+    ) -> Result<
+        (
+            db::store::DatabaseSchemaMetadata,
+            Vec<db::store::SyncWarning>,
+        ),
+        DBError,
+    > {
+        let mut warnings = util::Warnings::new(true);
+        let database = self.sync_database_with(&mut warnings).await?;
+        Ok((database, warnings.into_inner()))
+    }
+}
+
+macro_rules! create_get_function_procedure_stmt {
+    ($func_name:ident, $column_name:expr) => {
+        async fn $func_name(
+            &self,
+            conn: &mut MySqlConnection,
+            database_name: &str,
+            function_name: &str,
+        ) -> Result<String, DBError> {
+            let query = format!(
+                "SHOW {} `{}`.`{}`",
+                $column_name, database_name, function_name
+            );
+            let row = self
+                .fetch_bounded(conn, &query)
+                .await?
+                .into_iter()
+                .next()
+                .ok_or(sqlx::Error::RowNotFound)?;
+
+            let idx = if let Some(idx) = row
+                .columns()
+                .iter()
+                .position(|column| column.name().eq_ignore_ascii_case($column_name))
+            {
+                Ok(idx)
+            } else {
+                Err(DBError::unexpected_catalog(format!(
+                    "Not Find {} Failed",
+                    $column_name
+                )))
+            }?;
+
+            // The definition is NULL without the privilege to see the routine body.
+            let define: Option<String> = row.try_get(idx)?;
+            define.ok_or_else(|| {
+                DBError::new(
+                    ErrorKind::PermissionDenied,
+                    format!("the definition of {function_name} is not visible to the user"),
+                )
+            })
+        }
+    };
+}
+
+impl Driver {
+    pub async fn create(cfg: &db::ConnectionConfig) -> Result<impl db::DB, DBError> {
+        return Self::create_driver(cfg).await;
+    }
+
+    pub async fn create_driver(cfg: &db::ConnectionConfig) -> Result<Driver, DBError> {
+        let mut opt = sqlx::mysql::MySqlConnectOptions::default()
+            .host(&cfg.host)
+            .port(cfg.port)
+            .username(&cfg.username)
+            .password(&cfg.password)
+            .database(&cfg.database)
+            .ssl_mode(match cfg.tls.mode {
+                db::SslMode::Disable => sqlx::mysql::MySqlSslMode::Disabled,
+                db::SslMode::Prefer => sqlx::mysql::MySqlSslMode::Preferred,
+                db::SslMode::Require => sqlx::mysql::MySqlSslMode::Required,
+                db::SslMode::VerifyCa => sqlx::mysql::MySqlSslMode::VerifyCa,
+                db::SslMode::VerifyFull => sqlx::mysql::MySqlSslMode::VerifyIdentity,
+            });
+        if let Some(socket) = &cfg.socket {
+            opt = opt.socket(socket);
+        }
+        if let Some(ca) = &cfg.tls.ca {
+            opt = opt.ssl_ca(ca);
+        }
+        if let Some(cert) = &cfg.tls.cert {
+            opt = opt.ssl_client_cert(cert);
+        }
+        if let Some(key) = &cfg.tls.key {
+            opt = opt.ssl_client_key(key);
+        }
+
+        let mut pool_options = util::pool_options(&cfg.pool);
+        if let Some(timeout) = cfg.pool.statement_timeout {
+            let statement = format!("SET SESSION max_execution_time = {}", timeout.as_millis());
+            pool_options = pool_options.after_connect(move |conn: &mut MySqlConnection, _| {
+                let statement = statement.clone();
+                Box::pin(async move { conn.execute(statement.as_str()).await.map(|_| ()) })
+            });
+        }
+        let pool = util::connect_pool(pool_options, opt, cfg.pool.connect_timeout).await?;
+
+        Ok(Driver {
+            engine: cfg.engine.clone(),
+            database_name: cfg.database.clone(),
+            pool,
+            statement_timeout: cfg.pool.statement_timeout,
+        })
+    }
+
+    // fetch_bounded runs a statement max_execution_time does not limit, such as SHOW, within the
+    // statement timeout. Past the timeout the statement is killed from a separate connection, so
+    // its own connection and transaction stay usable.
+    async fn fetch_bounded(
+        &self,
+        conn: &mut MySqlConnection,
+        statement: &str,
+    ) -> Result<Vec<MySqlRow>, DBError> {
+        let Some(timeout) = self.statement_timeout else {
+            return Ok(sqlx::query(statement).fetch_all(&mut *conn).await?);
+        };
+        let id: u64 = sqlx::query_scalar("SELECT CONNECTION_ID()")
+            .fetch_one(&mut *conn)
+            .await?;
+        let mut rows = std::pin::pin!(sqlx::query(statement).fetch_all(&mut *conn));
+        if let Ok(rows) = tokio::time::timeout(timeout, &mut rows).await {
+            return Ok(rows?);
+        }
+
+        let mut killer = self.pool.connect_options().connect().await?;
+        sqlx::query(&format!("KILL QUERY {id}"))
+            .execute(&mut killer)
+            .await?;
+        killer.close().await?;
+        // The killed statement fails as interrupted.
+        let _ = rows.await;
+        Err(DBError::new(
+            ErrorKind::Timeout,
+            format!("{statement} timed out after {timeout:?}"),
+        ))
+    }
+
+    async fn sync_database_with(
+        &self,
+        warnings: &mut util::Warnings,
+    ) -> Result<db::store::DatabaseSchemaMetadata, DBError> {
+        // All catalog queries run on one connection inside a read only consistent snapshot, so
+        // concurrent DDL cannot produce metadata mixed from different points in time. The
+        // connection is detached from the pool: a sync cancelled inside the transaction drops the
+        // connection, and the server rolls the transaction back, instead of returning it to the
+        // pool with the transaction still open.
+        let mut conn = self.pool.acquire().await.context("acquire")?.detach();
+        sqlx::query("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ")
+            .execute(&mut conn)
+            .await?;
+        sqlx::query("START TRANSACTION WITH CONSISTENT SNAPSHOT, READ ONLY")
+            .execute(&mut conn)
+            .await?;
+
+        let database = self.load_database_schema(&mut conn, warnings).await;
+
+        // A load failure is reported before a failure to commit.
+        let commit = sqlx::query("COMMIT").execute(&mut conn).await;
+        let database = database?;
+        commit?;
+        conn.close().await?;
+
+        Ok(database)
+    }
+
+    async fn load_database_schema(
+        &self,
+        conn: &mut MySqlConnection,
+        warnings: &mut util::Warnings,
+    ) -> Result<db::store::DatabaseSchemaMetadata, DBError> {
         let database_name = &self.database_name;
-        let (character_set, collation) = self.get_database_info(database_name).await?;
-        let mut index = self.load_index(database_name).await?;
-        let mut columns = self.load_column(database_name).await?;
-        let mut foreign_keys = self.get_foreign_key_list(database_name).await?;
-        let (tables, views) = self.load_table_and_view(database_name).await?;
+        let (character_set, collation) = self
+            .get_database_info(conn, database_name)
+            .await
+            .context("get_database_info")?;
+        let mut index = self
+            .load_index(conn, database_name)
+            .await
+            .context("load_index")?;
+        let mut columns = self
+            .load_column(conn, database_name, warnings)
+            .await
+            .context("load_column")?;
+        let mut foreign_keys = self
+            .get_foreign_key_list(conn, database_name)
+            .await
+            .context("get_foreign_key_list")?;
+        let mut triggers = self
+            .load_trigger(conn, database_name)
+            .await
+            .context("load_trigger")?;
+        let mut partitions = self
+            .load_partition(conn, database_name, warnings)
+            .await
+            .context("load_partition")?;
+        let (tables, mut views) = self
+            .load_table_and_view(conn, database_name, warnings)
+            .await
+            .context("load_table_and_view")?;
+        let mut view_dependencies = self
+            .load_view_dependency(conn, database_name, &views, &columns)
+            .await
+            .context("load_view_dependency")?;
+        for view in views.iter_mut() {
+            if let Some(dependent_columns) = view_dependencies.remove(&view.name) {
+                view.dependent_columns = dependent_columns;
+            }
+        }
 
         let tables = tables
             .into_iter()
@@ -76,11 +301,23 @@ impl db::DB for Driver {
                     table.foreign_keys = fk_list;
                 }
 
+                if let Some(trigger_list) = triggers.remove(&table.name) {
+                    table.triggers = trigger_list;
+                }
+                table.partitioning = partitions.remove(&table.name);
+
                 table
             })
             .collect();
 
-        let (functions, procedures) = self.load_routines(database_name).await?;
+        let (functions, procedures) = self
+            .load_routines(conn, database_name, warnings)
+            .await
+            .context("load_routines")?;
+        let events = self
+            .load_event(conn, database_name)
+            .await
+            .context("load_event")?;
         let schema = db::store::SchemaMetadata {
             name: String::new(),
             tables,
@@ -89,6 +326,7 @@ impl db::DB for Driver {
             functions,
             procedures,
             materialized_views: vec![],
+            events,
             owner: String::new(),
             comment: String::new(),
         };
@@ -106,75 +344,25 @@ impl db::DB for Driver {
 
         Ok(dbmeta)
     }
-}
-
-macro_rules! create_get_function_procedure_stmt {
-    ($func_name:ident, $column_name:expr) => {
-        async fn $func_name(
-            &self,
-            database_name: &str,
-            function_name: &str,
-        ) -> Result<String, DBError> {
-            let query = format!(
-                "SHOW {} `{}`.`{}`",
-                $column_name, database_name, function_name
-            );
-            let row = sqlx::query(&query).fetch_one(&self.pool).await?;
-
-            let idx = if let Some(idx) = row
-                .columns()
-                .iter()
-                .position(|column| column.name().eq_ignore_ascii_case($column_name))
-            {
-                Ok(idx)
-            } else {
-                Err(DBError::Unknow(format!("Not Find {} Failed", $column_name)))
-            }?;
-
-            let define: String = row.get(idx);
-
-            Ok(define)
-        }
-    };
-}
-
-impl Driver {
-    pub async fn create(cfg: &db::ConnectionConfig) -> Result<impl db::DB, DBError> {
-        return Self::create_driver(cfg).await;
-    }
 
-    pub async fn create_driver(cfg: &db::ConnectionConfig) -> Result<Driver, DBError> {
-        let opt = sqlx::mysql::MySqlConnectOptions::default()
-            .host(&cfg.host)
-            .port(cfg.port)
-            .username(&cfg.username)
-            .password(&cfg.password)
-            .database(&cfg.database)
-            .ssl_mode(sqlx::mysql::MySqlSslMode::Disabled);
-
-        let pool = MySqlPool::connect_with(opt).await?;
-
-        Ok(Driver {
-            engine: cfg.engine.clone(),
-            database_name: cfg.database.clone(),
-            pool,
-        })
-    }
-
-    async fn get_version(&self) -> Result<(String, String), DBError> {
+    async fn get_version(&self, conn: &mut MySqlConnection) -> Result<(String, String), DBError> {
         #[derive(sqlx::FromRow)]
         struct Version {
             version: String,
         }
         let version = sqlx::query_as::<_, Version>("SELECT VERSION() as version")
-            .fetch_one(&self.pool)
+            .fetch_one(&mut *conn)
             .await?;
         let pversion = parse_version(&version.version)?;
         Ok(pversion)
     }
 
     #[allow(dead_code)]
-    async fn get_variable(&self, var_name: String) -> Result<String, DBError> {
+    async fn get_variable(
+        &self,
+        conn: &mut MySqlConnection,
+        var_name: String,
+    ) -> Result<String, DBError> {
         #[derive(sqlx::FromRow)]
         struct Variable {
             #[sqlx(rename = "Variable_name")]
@@ -183,14 +371,22 @@ impl Driver {
             value: String,
         }
 
-        let variable = sqlx::query_as::<_, Variable>(&format!("SHOW VARIABLES LIKE '{var_name}'"))
-            .fetch_one(&self.pool)
-            .await?;
+        let row = self
+            .fetch_bounded(conn, &format!("SHOW VARIABLES LIKE '{var_name}'"))
+            .await?
+            .into_iter()
+            .next()
+            .ok_or(sqlx::Error::RowNotFound)?;
+        let variable = Variable::from_row(&row)?;
 
         Ok(variable.value)
     }
 
-    async fn get_database_info(&self, database_name: &str) -> Result<(String, String), DBError> {
+    async fn get_database_info(
+        &self,
+        conn: &mut MySqlConnection,
+        database_name: &str,
+    ) -> Result<(String, String), DBError> {
         let query = "
         SELECT
 			DEFAULT_CHARACTER_SET_NAME,
@@ -201,7 +397,7 @@ impl Driver {
 
         let row = sqlx::query(query)
             .bind(database_name)
-            .fetch_one(&self.pool)
+            .fetch_one(&mut *conn)
             .await?;
 
         let character_name: String = row.get("DEFAULT_CHARACTER_SET_NAME");
@@ -210,7 +406,10 @@ impl Driver {
         Ok((character_name, collation))
     }
 
-    async fn load_database(&self) -> Result<Vec<db::store::DatabaseSchemaMetadata>, DBError> {
+    async fn load_database(
+        &self,
+        conn: &mut MySqlConnection,
+    ) -> Result<Vec<db::store::DatabaseSchemaMetadata>, DBError> {
         let query = format!(
             "SELECT
         SCHEMA_NAME,
@@ -221,7 +420,7 @@ impl Driver {
         "
         );
 
-        let databases = sqlx::query(&query).fetch_all(&self.pool).await?;
+        let databases = sqlx::query(&query).fetch_all(&mut *conn).await?;
 
         let db_metadatas = databases
             .iter()
@@ -246,9 +445,47 @@ impl Driver {
         Ok(db_metadatas)
     }
 
+    async fn load_instance_role(
+        &self,
+        conn: &mut MySqlConnection,
+    ) -> Result<Vec<db::store::InstanceRoleMetadata>, DBError> {
+        let query = "
+        SELECT
+            User,
+            Host
+        FROM mysql.user
+        ORDER BY User, Host
+        ";
+
+        let users = sqlx::query(query).fetch_all(&mut *conn).await?;
+
+        let mut roles = Vec::<db::store::InstanceRoleMetadata>::new();
+        for row in users {
+            let user: String = row.get("User");
+            let host: String = row.get("Host");
+
+            // SHOW GRANTS renders every grant of the account in the server's own syntax, including
+            // routine, proxy and role grants.
+            let name = format!("{}@{}", quote_string(&user), quote_string(&host));
+            let grant_list = self
+                .fetch_bounded(conn, &format!("SHOW GRANTS FOR {name}"))
+                .await?;
+            let grants: Vec<String> = grant_list.iter().map(|row| row.get(0)).collect();
+
+            roles.push(db::store::InstanceRoleMetadata {
+                name,
+                grant: grants.join("\n"),
+            });
+        }
+
+        Ok(roles)
+    }
+
     async fn load_column(
         &self,
+        conn: &mut MySqlConnection,
         database_name: &str,
+        warnings: &mut util::Warnings,
     ) -> Result<HashMap<String, Vec<db::store::ColumnMetadata>>, DBError> {
         let query = r"
         SELECT
@@ -269,7 +506,7 @@ impl Driver {
 
         let list = sqlx::query(query)
             .bind(database_name)
-            .fetch_all(&self.pool)
+            .fetch_all(&mut *conn)
             .await?;
 
         let mut column_map = HashMap::<String, Vec<db::store::ColumnMetadata>>::new();
@@ -286,7 +523,13 @@ impl Driver {
             let comment: String = row.get("COLUMN_COMMENT");
             let extra: String = row.get("EXTRA");
 
-            let nullable = util::convert_yes_no(&nullable_str)?;
+            // An unrecognized IS_NULLABLE keeps the column, assumed nullable.
+            let nullable = warnings
+                .check(
+                    &[&table_name, &column_name],
+                    util::convert_yes_no(&nullable_str),
+                )?
+                .unwrap_or(true);
             let mut col = db::store::ColumnMetadata {
                 name: column_name,
                 position: position as i32,
@@ -309,11 +552,12 @@ impl Driver {
 
     async fn load_index(
         &self,
+        conn: &mut MySqlConnection,
         database_name: &str,
     ) -> Result<HashMap<String, HashMap<String, db::store::IndexMetadata>>, DBError> {
-        let (version_str, rest) = self.get_version().await?;
+        let (version_str, rest) = self.get_version(conn).await?;
 
-        let version = Version::from(&version_str).ok_or(DBError::Unknow(format!(
+        let version = Version::from(&version_str).ok_or(DBError::unsupported_version(format!(
             "db version {version_str} cannot be parsed"
         )))?;
 
@@ -354,7 +598,7 @@ impl Driver {
 
         let list = sqlx::query(query)
             .bind(database_name)
-            .fetch_all(&self.pool)
+            .fetch_all(&mut *conn)
             .await?;
 
         let mut index_map = HashMap::<String, HashMap<String, db::store::IndexMetadata>>::new();
@@ -383,6 +627,8 @@ impl Driver {
                 visible: is_visible == 1,
                 comment,
                 definition: String::new(),
+                include_columns: vec![],
+                predicate: String::new(),
             };
 
             let table_map = index_map.entry(table_name).or_default();
@@ -405,6 +651,7 @@ impl Driver {
 
     async fn get_foreign_key_list(
         &self,
+        conn: &mut MySqlConnection,
         database_name: &str,
     ) -> Result<HashMap<String, Vec<db::store::ForeignKeyMetadata>>, DBError> {
         let query = "
@@ -430,7 +677,7 @@ impl Driver {
 
         let list = sqlx::query(query)
             .bind(database_name)
-            .fetch_all(&self.pool)
+            .fetch_all(&mut *conn)
             .await?;
 
         let mut build_table = String::new();
@@ -486,7 +733,9 @@ impl Driver {
 
     async fn load_table_and_view(
         &self,
+        conn: &mut MySqlConnection,
         database_name: &str,
+        warnings: &mut util::Warnings,
     ) -> Result<(Vec<db::store::TableMetadata>, Vec<db::store::ViewMetadata>), DBError> {
         let mut view_map = HashMap::<String, db::store::ViewMetadata>::new();
 
@@ -502,7 +751,7 @@ impl Driver {
 
         let view_list = sqlx::query(view_query)
             .bind(database_name)
-            .fetch_all(&self.pool)
+            .fetch_all(&mut *conn)
             .await?;
         for row in view_list {
             let view_name: String = row.get("TABLE_NAME");
@@ -537,7 +786,7 @@ impl Driver {
 
         let list = sqlx::query(query)
             .bind(database_name)
-            .fetch_all(&self.pool)
+            .fetch_all(&mut *conn)
             .await?;
 
         for row in list {
@@ -545,7 +794,7 @@ impl Driver {
             let table_type: String = row.get("TABLE_TYPE");
             let comment: String = row.get("TABLE_COMMENT");
 
-            match table_type.as_str() {
+            let result = match table_type.as_str() {
                 VIEW_TABLE_TYPE => {
                     if let Some(view) = view_map.get_mut(&table_name) {
                         view.comment = comment;
@@ -575,14 +824,17 @@ impl Driver {
                         comment: comment.clone(),
                         foreign_keys: vec![],
                         owner: String::new(),
+                        triggers: vec![],
+                        partitioning: None,
                     };
                     table_vec.push(table);
                     Ok(())
                 }
-                _ => Err(DBError::Unknow(format!(
+                _ => Err(DBError::unexpected_catalog(format!(
                     "Unexpected table_type {table_type}"
                 ))),
-            }?;
+            };
+            warnings.check(&[&table_name], result)?;
         }
 
         let view_vec: Vec<db::store::ViewMetadata> = view_map.into_values().collect();
@@ -590,9 +842,315 @@ impl Driver {
         Ok((table_vec, view_vec))
     }
 
+    // load_trigger loads the triggers of each table, in the order they fire for an event.
+    async fn load_trigger(
+        &self,
+        conn: &mut MySqlConnection,
+        database_name: &str,
+    ) -> Result<HashMap<String, Vec<db::store::TriggerMetadata>>, DBError> {
+        let query = "
+        SELECT
+            TRIGGER_NAME,
+            EVENT_OBJECT_TABLE,
+            EVENT_MANIPULATION,
+            ACTION_TIMING,
+            ACTION_ORIENTATION,
+            ACTION_STATEMENT,
+            DEFINER
+        FROM INFORMATION_SCHEMA.TRIGGERS
+        WHERE TRIGGER_SCHEMA = ?
+        ORDER BY EVENT_OBJECT_TABLE, ACTION_TIMING, EVENT_MANIPULATION, ACTION_ORDER
+        ";
+
+        let list = sqlx::query(query)
+            .bind(database_name)
+            .fetch_all(&mut *conn)
+            .await?;
+
+        let mut trigger_map = HashMap::<String, Vec<db::store::TriggerMetadata>>::new();
+        for row in list {
+            let name: String = row.get("TRIGGER_NAME");
+            let table_name: String = row.get("EVENT_OBJECT_TABLE");
+            let event: String = row.get("EVENT_MANIPULATION");
+            let timing: String = row.get("ACTION_TIMING");
+            let orientation: String = row.get("ACTION_ORIENTATION");
+            let statement: String = row.get("ACTION_STATEMENT");
+            let definer: String = row.get("DEFINER");
+
+            // The definition is built the way SHOW CREATE TRIGGER reports it.
+            let definition = format!(
+                "CREATE DEFINER={} TRIGGER {} {timing} {event} ON {} FOR EACH {orientation} {statement}",
+                quote_definer(&definer),
+                quote_identifier(&name),
+                quote_identifier(&table_name),
+            );
+            trigger_map
+                .entry(table_name)
+                .or_default()
+                .push(db::store::TriggerMetadata {
+                    name,
+                    timing,
+                    events: vec![event],
+                    for_each_row: orientation == "ROW",
+                    definition,
+                    enabled: true,
+                });
+        }
+
+        Ok(trigger_map)
+    }
+
+    // load_partition loads the partitioning of partitioned tables, keyed by table name.
+    async fn load_partition(
+        &self,
+        conn: &mut MySqlConnection,
+        database_name: &str,
+        warnings: &mut util::Warnings,
+    ) -> Result<HashMap<String, db::store::PartitioningMetadata>, DBError> {
+        // A table that is not partitioned has a single row without a partition name.
+        let query = "
+        SELECT
+            TABLE_NAME,
+            PARTITION_NAME,
+            SUBPARTITION_NAME,
+            PARTITION_METHOD,
+            SUBPARTITION_METHOD,
+            PARTITION_EXPRESSION,
+            SUBPARTITION_EXPRESSION,
+            PARTITION_DESCRIPTION,
+            CAST(IFNULL(TABLE_ROWS, 0) as SIGNED) as TABLE_ROWS
+        FROM INFORMATION_SCHEMA.PARTITIONS
+        WHERE TABLE_SCHEMA = ? AND PARTITION_NAME IS NOT NULL
+        ORDER BY TABLE_NAME, PARTITION_ORDINAL_POSITION, SUBPARTITION_ORDINAL_POSITION
+        ";
+
+        let list = sqlx::query(query)
+            .bind(database_name)
+            .fetch_all(&mut *conn)
+            .await?;
+
+        let mut row_map = HashMap::<String, Vec<PartitionRow>>::new();
+        for row in list {
+            let table_name: String = row.get("TABLE_NAME");
+            row_map.entry(table_name).or_default().push(PartitionRow {
+                partition_name: row.get("PARTITION_NAME"),
+                subpartition_name: row.get("SUBPARTITION_NAME"),
+                method: row.get("PARTITION_METHOD"),
+                subpartition_method: row.get("SUBPARTITION_METHOD"),
+                expression: row.get("PARTITION_EXPRESSION"),
+                subpartition_expression: row.get("SUBPARTITION_EXPRESSION"),
+                description: row.get("PARTITION_DESCRIPTION"),
+                row_count: row.get("TABLE_ROWS"),
+            });
+        }
+
+        let mut partition_map = HashMap::<String, db::store::PartitioningMetadata>::new();
+        for (table_name, rows) in row_map {
+            if let Some(partitioning) = warnings.check(&[&table_name], build_partitioning(&rows))? {
+                partition_map.insert(table_name, partitioning);
+            }
+        }
+
+        Ok(partition_map)
+    }
+
+    async fn load_event(
+        &self,
+        conn: &mut MySqlConnection,
+        database_name: &str,
+    ) -> Result<Vec<db::store::EventMetadata>, DBError> {
+        let query = "
+        SELECT
+            EVENT_NAME,
+            DEFINER,
+            TIME_ZONE,
+            EVENT_DEFINITION,
+            CAST(EXECUTE_AT AS CHAR) AS EXECUTE_AT,
+            INTERVAL_VALUE,
+            INTERVAL_FIELD,
+            CAST(STARTS AS CHAR) AS STARTS,
+            CAST(ENDS AS CHAR) AS ENDS,
+            STATUS,
+            ON_COMPLETION,
+            EVENT_COMMENT
+        FROM INFORMATION_SCHEMA.EVENTS
+        WHERE EVENT_SCHEMA = ?
+        ORDER BY EVENT_NAME
+        ";
+
+        let list = sqlx::query(query)
+            .bind(database_name)
+            .fetch_all(&mut *conn)
+            .await?;
+
+        let mut events = vec![];
+        for row in list {
+            let name: String = row.get("EVENT_NAME");
+            let definer: String = row.get("DEFINER");
+            let time_zone: String = row.get("TIME_ZONE");
+            let body: String = row.get("EVENT_DEFINITION");
+            let execute_at: Option<String> = row.get("EXECUTE_AT");
+            let interval_value: Option<String> = row.get("INTERVAL_VALUE");
+            let interval_field: Option<String> = row.get("INTERVAL_FIELD");
+            let starts: Option<String> = row.get("STARTS");
+            let ends: Option<String> = row.get("ENDS");
+            let status: String = row.get("STATUS");
+            let on_completion: String = row.get("ON_COMPLETION");
+            let comment: String = row.get("EVENT_COMMENT");
+
+            let schedule = format_event_schedule(
+                execute_at.as_deref(),
+                interval_value.as_deref().zip(interval_field.as_deref()),
+                starts.as_deref(),
+                ends.as_deref(),
+            );
+            // The definition is built the way SHOW CREATE EVENT reports it.
+            let status_clause = match status.as_str() {
+                "ENABLED" => "ENABLE",
+                "DISABLED" => "DISABLE",
+                _ => "DISABLE ON SLAVE",
+            };
+            let comment_clause = if comment.is_empty() {
+                String::new()
+            } else {
+                format!(" COMMENT {}", quote_string(&comment))
+            };
+            let definition = format!(
+                "CREATE DEFINER={} EVENT {} ON SCHEDULE {schedule} ON COMPLETION {on_completion} {status_clause}{comment_clause} DO {body}",
+                quote_definer(&definer),
+                quote_identifier(&name),
+            );
+            events.push(db::store::EventMetadata {
+                name,
+                definition,
+                schedule,
+                time_zone,
+                status,
+                on_completion,
+                definer,
+                body,
+                comment,
+            });
+        }
+
+        Ok(events)
+    }
+
+    // load_view_dependency resolves the columns each view reads. VIEW_TABLE_USAGE gives the
+    // referenced tables, the columns are parsed from the normalized VIEW_DEFINITION.
+    // Only tables in the synced database are resolved.
+    async fn load_view_dependency(
+        &self,
+        conn: &mut MySqlConnection,
+        database_name: &str,
+        views: &[db::store::ViewMetadata],
+        column_map: &HashMap<String, Vec<db::store::ColumnMetadata>>,
+    ) -> Result<HashMap<String, Vec<db::store::DependentColumn>>, DBError> {
+        let mut dependency_map = HashMap::<String, Vec<db::store::DependentColumn>>::new();
+
+        let (version_str, rest) = self.get_version(conn).await?;
+        let version = Version::from(&version_str).ok_or(DBError::unsupported_version(format!(
+            "db version {version_str} cannot be parsed"
+        )))?;
+        let version8_0_13 = Version::from("8.0.13").unwrap();
+        // VIEW_TABLE_USAGE is introduced in MySQL 8.0.13.
+        if version.lt(&version8_0_13) || rest.contains("MariaDB") {
+            return Ok(dependency_map);
+        }
+
+        let query = "
+        SELECT
+            VIEW_NAME,
+            TABLE_NAME
+        FROM INFORMATION_SCHEMA.VIEW_TABLE_USAGE
+        WHERE VIEW_SCHEMA = ? AND TABLE_SCHEMA = ?
+        ORDER BY VIEW_NAME, TABLE_NAME
+        ";
+
+        let list = sqlx::query(query)
+            .bind(database_name)
+            .bind(database_name)
+            .fetch_all(&mut *conn)
+            .await?;
+
+        let mut table_usage = HashMap::<String, Vec<String>>::new();
+        for row in list {
+            let view_name: String = row.get("VIEW_NAME");
+            let table_name: String = row.get("TABLE_NAME");
+            table_usage.entry(view_name).or_default().push(table_name);
+        }
+
+        for view in views {
+            if let Some(tables) = table_usage.get(&view.name) {
+                let dependent_columns = parse_view_dependent_columns(
+                    &view.definition,
+                    database_name,
+                    tables,
+                    column_map,
+                );
+                dependency_map.insert(view.name.clone(), dependent_columns);
+            }
+        }
+
+        Ok(dependency_map)
+    }
+
+    // load_routine_parameters loads the parameters of all routines, keyed by routine type and
+    // name since a function and a procedure may share a name.
+    async fn load_routine_parameters(
+        &self,
+        conn: &mut MySqlConnection,
+        database_name: &str,
+    ) -> Result<HashMap<(String, String), Vec<db::store::RoutineParameter>>, DBError> {
+        let query = "
+        SELECT
+            ROUTINE_TYPE,
+            SPECIFIC_NAME,
+            PARAMETER_MODE,
+            PARAMETER_NAME,
+            DTD_IDENTIFIER
+        FROM INFORMATION_SCHEMA.PARAMETERS
+        WHERE SPECIFIC_SCHEMA = ? AND ORDINAL_POSITION > 0
+        ORDER BY ROUTINE_TYPE, SPECIFIC_NAME, ORDINAL_POSITION
+        ";
+
+        let list = sqlx::query(query)
+            .bind(database_name)
+            .fetch_all(&mut *conn)
+            .await?;
+
+        let mut parameter_map =
+            HashMap::<(String, String), Vec<db::store::RoutineParameter>>::new();
+        for row in list {
+            let routine_type: String = row.get("ROUTINE_TYPE");
+            let routine_name: String = row.get("SPECIFIC_NAME");
+            // The mode is NULL for function parameters, which are all IN.
+            let mode: Option<String> = row.get("PARAMETER_MODE");
+            parameter_map
+                .entry((routine_type, routine_name))
+                .or_default()
+                .push(db::store::RoutineParameter {
+                    name: row.get("PARAMETER_NAME"),
+                    mode: match mode.as_deref() {
+                        Some("OUT") => db::store::ParameterMode::Out,
+                        Some("INOUT") => db::store::ParameterMode::InOut,
+                        _ => db::store::ParameterMode::In,
+                    },
+                    r#type: row.get("DTD_IDENTIFIER"),
+                });
+        }
+
+        Ok(parameter_map)
+    }
+
+    // load_routines loads the metadata of all routines with two queries. The definitions are
+    // read with SHOW CREATE, the one statement keeping the parameter list and the character set
+    // and collation details as the server reports them.
     async fn load_routines(
         &self,
+        conn: &mut MySqlConnection,
         database_name: &str,
+        warnings: &mut util::Warnings,
     ) -> Result<
         (
             Vec<db::store::FunctionMetadata>,
@@ -600,10 +1158,19 @@ impl Driver {
         ),
         DBError,
     > {
+        let mut parameter_map = self.load_routine_parameters(conn, database_name).await?;
+
         let routines_query = "
         SELECT
             ROUTINE_NAME,
-            ROUTINE_TYPE
+            ROUTINE_TYPE,
+            IFNULL(DTD_IDENTIFIER, '') AS DTD_IDENTIFIER,
+            ROUTINE_BODY,
+            ROUTINE_DEFINITION,
+            IS_DETERMINISTIC,
+            SECURITY_TYPE,
+            DEFINER,
+            ROUTINE_COMMENT
         FROM
             INFORMATION_SCHEMA.ROUTINES
         WHERE ROUTINE_SCHEMA = ? AND ROUTINE_TYPE IN ('FUNCTION', 'PROCEDURE')
@@ -615,24 +1182,74 @@ impl Driver {
 
         let routines_list = sqlx::query(routines_query)
             .bind(database_name)
-            .fetch_all(&self.pool)
+            .fetch_all(&mut *conn)
             .await?;
 
         for row in routines_list {
             let name: String = row.get("ROUTINE_NAME");
             let routine_type: String = row.get("ROUTINE_TYPE");
+            let language: String = row.get("ROUTINE_BODY");
+            let body: Option<String> = row.get("ROUTINE_DEFINITION");
+            let return_type: String = row.get("DTD_IDENTIFIER");
+            let deterministic = row.get::<String, _>("IS_DETERMINISTIC") == "YES";
+            let security = match row.get::<String, _>("SECURITY_TYPE").as_str() {
+                "INVOKER" => db::store::SecurityType::Invoker,
+                _ => db::store::SecurityType::Definer,
+            };
+            let owner: String = row.get("DEFINER");
+            let comment: String = row.get("ROUTINE_COMMENT");
+            let is_procedure = routine_type.eq_ignore_ascii_case("PROCEDURE");
+            let parameters = parameter_map
+                .remove(&(routine_type, name.clone()))
+                .unwrap_or_default();
+
+            let definition = match body {
+                Some(_) if is_procedure => {
+                    self.get_create_procedure_stmt(conn, database_name, &name)
+                        .await
+                }
+                Some(_) => {
+                    self.get_create_function_stmt(conn, database_name, &name)
+                        .await
+                }
+                // The body is NULL without the privilege to see it.
+                None => Err(DBError::new(
+                    ErrorKind::PermissionDenied,
+                    format!("the definition of {name} is not visible to the user"),
+                )),
+            };
+            let definition = warnings.check(&[&name], definition)?.unwrap_or_default();
 
-            if routine_type.eq_ignore_ascii_case("PROCEDURE") {
-                let define = self.get_create_procedure_stmt(database_name, &name).await?;
+            // DETERMINISTIC declares the same result for the same arguments, i.e. IMMUTABLE.
+            let volatility = if deterministic {
+                db::store::Volatility::Immutable
+            } else {
+                db::store::Volatility::Volatile
+            };
+            if is_procedure {
                 procedures.push(db::store::ProcedureMetadata {
                     name,
-                    definition: define,
+                    signature: String::new(),
+                    definition,
+                    parameters,
+                    language,
+                    volatility,
+                    security,
+                    owner,
+                    comment,
                 })
             } else {
-                let define = self.get_create_function_stmt(database_name, &name).await?;
                 functions.push(db::store::FunctionMetadata {
                     name,
-                    definition: define,
+                    signature: String::new(),
+                    definition,
+                    parameters,
+                    return_type,
+                    language,
+                    volatility,
+                    security,
+                    owner,
+                    comment,
                 })
             }
         }
@@ -647,7 +1264,8 @@ impl Driver {
 }
 
 fn parse_version(version: &str) -> Result<(String, String), DBError> {
-    let regex = Regex::new(r#"^\d+\.\d+\.\d+"#).map_err(|e| DBError::Unknow(e.to_string()))?;
+    let regex =
+        Regex::new(r#"^\d+\.\d+\.\d+"#).map_err(|e| DBError::unexpected_catalog(e.to_string()))?;
     if let Some(loc) = regex.find(version) {
         let start_index = loc.start();
         let end_index = loc.end();
@@ -656,12 +1274,183 @@ fn parse_version(version: &str) -> Result<(String, String), DBError> {
             version[end_index..].to_string(),
         ))
     } else {
-        Err(DBError::Unknow(format!(
+        Err(DBError::unsupported_version(format!(
             "failed to parse version {version}",
         )))
     }
 }
 
+// parse_view_dependent_columns collects the `db`.`table_or_alias`.`column` references of a
+// normalized view definition, keeping the columns that exist in the referenced tables.
+fn parse_view_dependent_columns(
+    definition: &str,
+    database_name: &str,
+    tables: &[String],
+    column_map: &HashMap<String, Vec<db::store::ColumnMetadata>>,
+) -> Vec<db::store::DependentColumn> {
+    // MySQL writes table references as `db`.`table` `alias`.
+    let table_re = Regex::new(r"`([^`]+)`\.`([^`]+)`\s+`([^`]+)`").unwrap();
+    let column_re = Regex::new(r"`([^`]+)`\.`([^`]+)`\.`([^`]+)`").unwrap();
+
+    let mut alias_map = HashMap::<&str, &str>::new();
+    for caps in table_re.captures_iter(definition) {
+        let (db_name, table, alias) = (&caps[1], caps.get(2).unwrap(), caps.get(3).unwrap());
+        if db_name == database_name && tables.iter().any(|t| t == table.as_str()) {
+            alias_map.insert(alias.as_str(), table.as_str());
+        }
+    }
+
+    let mut found = Vec::<(String, i32, String)>::new();
+    for caps in column_re.captures_iter(definition) {
+        if &caps[1] != database_name {
+            continue;
+        }
+        let qualifier = &caps[2];
+        let table = alias_map.get(qualifier).copied().unwrap_or(qualifier);
+        if !tables.iter().any(|t| t == table) {
+            continue;
+        }
+        let column = &caps[3];
+        let position = column_map
+            .get(table)
+            .and_then(|cols| cols.iter().find(|c| c.name == column))
+            .map(|c| c.position);
+        if let Some(position) = position {
+            let dependency = (table.to_string(), position, column.to_string());
+            if !found.contains(&dependency) {
+                found.push(dependency);
+            }
+        }
+    }
+    found.sort();
+
+    found
+        .into_iter()
+        .map(|(table, _, column)| db::store::DependentColumn {
+            schema: String::new(),
+            table,
+            column,
+        })
+        .collect()
+}
+
+// PartitionRow is a row of INFORMATION_SCHEMA.PARTITIONS.
+struct PartitionRow {
+    partition_name: String,
+    subpartition_name: Option<String>,
+    method: String,
+    subpartition_method: Option<String>,
+    expression: Option<String>,
+    subpartition_expression: Option<String>,
+    // The description is the VALUES LESS THAN or VALUES IN bound, it is NULL for HASH and KEY.
+    description: Option<String>,
+    row_count: i64,
+}
+
+// build_partitioning returns the partitioning of a table from its partition rows, ordered by
+// partition and subpartition. A subpartitioned partition has one row per subpartition.
+fn build_partitioning(rows: &[PartitionRow]) -> Result<db::store::PartitioningMetadata, DBError> {
+    let first = rows
+        .first()
+        .ok_or_else(|| DBError::unexpected_catalog("partitioned table without partitions"))?;
+    let mut partitions = Vec::<db::store::PartitionMetadata>::new();
+    for row in rows {
+        if partitions.last().map(|p| &p.name) != Some(&row.partition_name) {
+            partitions.push(db::store::PartitionMetadata {
+                name: row.partition_name.clone(),
+                value: row.description.clone().unwrap_or_default(),
+                subpartitioning: None,
+                row_count: 0,
+            });
+        }
+        let partition = partitions.last_mut().unwrap();
+        // The rows of a subpartitioned partition add up over its subpartitions.
+        partition.row_count += row.row_count;
+        let Some(subpartition_name) = &row.subpartition_name else {
+            continue;
+        };
+        if partition.subpartitioning.is_none() {
+            partition.subpartitioning = Some(db::store::PartitioningMetadata {
+                strategy: convert_partition_method(
+                    row.subpartition_method.as_deref().unwrap_or_default(),
+                )?,
+                expression: row.subpartition_expression.clone().unwrap_or_default(),
+                partitions: vec![],
+            });
+        }
+        if let Some(subpartitioning) = partition.subpartitioning.as_mut() {
+            subpartitioning
+                .partitions
+                .push(db::store::PartitionMetadata {
+                    name: subpartition_name.clone(),
+                    value: String::new(),
+                    subpartitioning: None,
+                    row_count: row.row_count,
+                });
+        }
+    }
+
+    Ok(db::store::PartitioningMetadata {
+        strategy: convert_partition_method(&first.method)?,
+        expression: first.expression.clone().unwrap_or_default(),
+        partitions,
+    })
+}
+
+// convert_partition_method converts INFORMATION_SCHEMA.PARTITIONS.PARTITION_METHOD to the
+// partition strategy.
+fn convert_partition_method(method: &str) -> Result<db::store::PartitionStrategy, DBError> {
+    match method {
+        "RANGE" => Ok(db::store::PartitionStrategy::Range),
+        "RANGE COLUMNS" => Ok(db::store::PartitionStrategy::RangeColumns),
+        "LIST" => Ok(db::store::PartitionStrategy::List),
+        "LIST COLUMNS" => Ok(db::store::PartitionStrategy::ListColumns),
+        "HASH" => Ok(db::store::PartitionStrategy::Hash),
+        "LINEAR HASH" => Ok(db::store::PartitionStrategy::LinearHash),
+        "KEY" => Ok(db::store::PartitionStrategy::Key),
+        "LINEAR KEY" => Ok(db::store::PartitionStrategy::LinearKey),
+        _ => Err(DBError::unexpected_catalog(format!(
+            "unrecognized partition method {method}"
+        ))),
+    }
+}
+
+// format_event_schedule returns the ON SCHEDULE clause of an event, AT for a one-time event and
+// EVERY for a recurring one given its interval value and field.
+fn format_event_schedule(
+    execute_at: Option<&str>,
+    interval: Option<(&str, &str)>,
+    starts: Option<&str>,
+    ends: Option<&str>,
+) -> String {
+    if let Some(execute_at) = execute_at {
+        return format!("AT {}", quote_string(execute_at));
+    }
+    let mut schedule = match interval {
+        // A compound interval such as HOUR_MINUTE has a value like 1:30, which must be quoted.
+        Some((value, field)) if value.chars().all(|c| c.is_ascii_digit()) => {
+            format!("EVERY {value} {field}")
+        }
+        Some((value, field)) => format!("EVERY {} {field}", quote_string(value)),
+        None => String::new(),
+    };
+    if let Some(starts) = starts {
+        schedule += &format!(" STARTS {}", quote_string(starts));
+    }
+    if let Some(ends) = ends {
+        schedule += &format!(" ENDS {}", quote_string(ends));
+    }
+    schedule
+}
+
+// quote_definer quotes the user and host of a user@host definer.
+fn quote_definer(definer: &str) -> String {
+    match definer.rsplit_once('@') {
+        Some((user, host)) => format!("{}@{}", quote_identifier(user), quote_identifier(host)),
+        None => quote_identifier(definer),
+    }
+}
+
 fn set_column_metadata_default(
     column: &mut db::store::ColumnMetadata,
     default_str: Option<String>,
@@ -720,17 +1509,21 @@ fn extract_digits_from_current_timestamp(extra: &str) -> Option<&str> {
     }
 }
 
-const AUTO_INCREMENT_SYMBOL: &str = "AUTO_INCREMENT";
 const BASE_TABLE_TYPE: &str = "BASE TABLE";
 const VIEW_TABLE_TYPE: &str = "VIEW";
 
 #[cfg(test)]
 mod test {
 
-    use crate::db::DB;
+    use crate::db::{self, DB};
     use crate::tests::{init_mysql_test_schema, init_mysql_test_service};
+    use std::collections::HashMap;
+    use std::time::Duration;
 
-    use super::Driver;
+    use super::{
+        build_partitioning, format_event_schedule, parse_view_dependent_columns, Driver,
+        PartitionRow,
+    };
 
     #[tokio::test]
     async fn test_mysql_schema_validation() {
@@ -747,7 +1540,8 @@ mod test {
         let driver = Driver::create_driver(&test_config).await.unwrap();
 
         // Test 1: Verify database version
-        let version = driver.get_version().await.unwrap();
+        let mut conn = driver.pool.acquire().await.unwrap();
+        let version = driver.get_version(&mut conn).await.unwrap();
         println!("VERSION:{:?}\n", version);
         assert!(!version.0.is_empty(), "Version should not be empty");
 
@@ -765,8 +1559,8 @@ mod test {
         // Test 5: Verify tables count
         assert_eq!(
             schema.tables.len(),
-            4,
-            "Should have 4 tables: customers, products, orders, order_items"
+            5,
+            "Should have 5 tables: customers, products, orders, order_items, order_archive"
         );
 
         // Test 6: Validate customers table
@@ -833,6 +1627,15 @@ mod test {
             7,
             "products table should have 7 columns"
         );
+        assert_eq!(products_table.triggers.len(), 1);
+        let trigger = &products_table.triggers[0];
+        assert_eq!(trigger.name, "trg_products_trim_name");
+        assert_eq!(trigger.timing, "BEFORE");
+        assert_eq!(trigger.events, vec!["INSERT"]);
+        assert!(trigger.for_each_row);
+        assert!(trigger.definition.ends_with(
+            "TRIGGER `trg_products_trim_name` BEFORE INSERT ON `products` FOR EACH ROW SET NEW.product_name = TRIM(NEW.product_name)"
+        ));
 
         // Test 8: Validate orders table and foreign keys
         let orders_table = schema
@@ -888,6 +1691,39 @@ mod test {
         assert_eq!(fk_to_products.referenced_table, "products");
         assert_eq!(fk_to_products.on_delete, "RESTRICT");
 
+        // Test 9.1: Validate order_archive partitions
+        let order_archive_table = schema
+            .tables
+            .iter()
+            .find(|t| t.name == "order_archive")
+            .expect("order_archive table should exist");
+        assert_eq!(order_archive_table.create_options, "partitioned");
+        assert!(order_items_table.partitioning.is_none());
+        let partitioning = order_archive_table
+            .partitioning
+            .as_ref()
+            .expect("order_archive should be partitioned");
+        assert_eq!(partitioning.strategy, db::store::PartitionStrategy::Range);
+        assert_eq!(partitioning.expression, "year(`order_date`)");
+        let partitions: Vec<(&str, &str)> = partitioning
+            .partitions
+            .iter()
+            .map(|p| (p.name.as_str(), p.value.as_str()))
+            .collect();
+        assert_eq!(partitions, vec![("p2023", "2024"), ("pmax", "MAXVALUE")]);
+        let subpartitioning = partitioning.partitions[0]
+            .subpartitioning
+            .as_ref()
+            .expect("p2023 should be subpartitioned");
+        assert_eq!(subpartitioning.strategy, db::store::PartitionStrategy::Hash);
+        assert_eq!(subpartitioning.expression, "`order_id`");
+        let subpartitions: Vec<&str> = subpartitioning
+            .partitions
+            .iter()
+            .map(|p| p.name.as_str())
+            .collect();
+        assert_eq!(subpartitions, vec!["p2023sp0", "p2023sp1"]);
+
         // Test 10: Verify views
         assert_eq!(schema.views.len(), 1, "Should have 1 view");
         let customer_orders_view = &schema.views[0];
@@ -896,6 +1732,25 @@ mod test {
             !customer_orders_view.definition.is_empty(),
             "View definition should not be empty"
         );
+        let dependent_columns: Vec<(&str, &str)> = customer_orders_view
+            .dependent_columns
+            .iter()
+            .map(|c| (c.table.as_str(), c.column.as_str()))
+            .collect();
+        assert_eq!(
+            dependent_columns,
+            vec![
+                ("customers", "customer_id"),
+                ("customers", "email"),
+                ("customers", "first_name"),
+                ("customers", "last_name"),
+                ("orders", "order_id"),
+                ("orders", "customer_id"),
+                ("orders", "order_date"),
+                ("orders", "total_amount"),
+                ("orders", "status"),
+            ]
+        );
 
         // Test 11: Verify procedures are loaded
         assert_eq!(schema.procedures.len(), 1, "Should have 1 stored procedure");
@@ -914,10 +1769,366 @@ mod test {
             !calculate_order_total_func.definition.is_empty(),
             "Function definition should not be empty"
         );
+        assert!(calculate_order_total_func.definition.contains(
+            "FUNCTION `calculate_order_total`(order_id_param INT) RETURNS decimal(10,2)\n    READS SQL DATA\n    DETERMINISTIC\nBEGIN"
+        ));
+        assert_eq!(
+            calculate_order_total_func.parameters,
+            vec![db::store::RoutineParameter {
+                name: "order_id_param".to_string(),
+                mode: db::store::ParameterMode::In,
+                r#type: "int".to_string(),
+            }]
+        );
+        assert_eq!(calculate_order_total_func.return_type, "decimal(10,2)");
+        assert_eq!(calculate_order_total_func.language, "SQL");
+        assert_eq!(
+            calculate_order_total_func.volatility,
+            db::store::Volatility::Immutable
+        );
+        assert_eq!(
+            calculate_order_total_func.security,
+            db::store::SecurityType::Definer
+        );
+        assert_eq!(
+            get_customer_orders_proc.parameters[0].r#type,
+            "varchar(255)"
+        );
+        assert!(get_customer_orders_proc
+            .definition
+            .contains("PROCEDURE `get_customer_orders`(IN customer_email VARCHAR(255))\nBEGIN"));
+
+        // Test 13: Verify events are loaded
+        assert_eq!(schema.events.len(), 1, "Should have 1 event");
+        let purge_event = &schema.events[0];
+        assert_eq!(purge_event.name, "purge_cancelled_orders");
+        assert_eq!(
+            purge_event.schedule,
+            "EVERY 1 DAY STARTS '2024-01-01 03:00:00'"
+        );
+        assert_eq!(purge_event.status, "DISABLED");
+        assert_eq!(purge_event.on_completion, "PRESERVE");
+        assert_eq!(purge_event.comment, "Purge cancelled orders");
+        assert_eq!(
+            purge_event.body,
+            "DELETE FROM orders WHERE status = 'cancelled'"
+        );
+        assert!(purge_event.definition.ends_with(
+            "EVENT `purge_cancelled_orders` ON SCHEDULE EVERY 1 DAY STARTS '2024-01-01 03:00:00' ON COMPLETION PRESERVE DISABLE COMMENT 'Purge cancelled orders' DO DELETE FROM orders WHERE status = 'cancelled'"
+        ));
 
         println!("✓ All MySQL schema validation tests passed!");
     }
 
+    #[tokio::test]
+    async fn test_instance_role() {
+        let test_config = init_mysql_test_service().unwrap();
+        init_mysql_test_schema()
+            .await
+            .expect("Failed to initialize test schema");
+        let d = Driver::create_driver(&test_config).await.unwrap();
+
+        let database = &test_config.database;
+        for statement in [
+            "DROP USER IF EXISTS 'dbmeta_auditor'@'%'".to_string(),
+            "CREATE USER 'dbmeta_auditor'@'%' IDENTIFIED BY 'dbmeta_auditor'".to_string(),
+            format!("GRANT SELECT ON `{database}`.`customers` TO 'dbmeta_auditor'@'%'"),
+            format!(
+                "GRANT EXECUTE ON PROCEDURE `{database}`.`get_customer_orders` TO 'dbmeta_auditor'@'%'"
+            ),
+        ] {
+            sqlx::query(&statement).execute(&d.pool).await.unwrap();
+        }
+
+        let instance = d.sync_instance().await.unwrap();
+        println!("Instance roles: {:?}\n", instance.instance_roles);
+
+        let current_user = instance
+            .instance_roles
+            .iter()
+            .find(|r| r.name.starts_with(&format!("'{}'@", test_config.username)))
+            .expect("current user should be listed as an instance role");
+        assert!(
+            current_user.grant.starts_with("GRANT "),
+            "Role grant should be rendered as GRANT statements"
+        );
+
+        let auditor = instance
+            .instance_roles
+            .iter()
+            .find(|r| r.name == "'dbmeta_auditor'@'%'")
+            .expect("the fixture account should be listed as an instance role");
+        assert_eq!(
+            auditor.grant,
+            [
+                "GRANT USAGE ON *.* TO `dbmeta_auditor`@`%`".to_string(),
+                format!("GRANT SELECT ON `{database}`.`customers` TO `dbmeta_auditor`@`%`"),
+                format!(
+                    "GRANT EXECUTE ON PROCEDURE `{database}`.`get_customer_orders` TO `dbmeta_auditor`@`%`"
+                ),
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn test_build_partitioning() {
+        let row = |partition: &str, subpartition: Option<&str>, row_count: i64| PartitionRow {
+            partition_name: partition.to_string(),
+            subpartition_name: subpartition.map(str::to_string),
+            method: "RANGE COLUMNS".to_string(),
+            subpartition_method: subpartition.map(|_| "KEY".to_string()),
+            expression: Some("`order_date`".to_string()),
+            subpartition_expression: subpartition.map(|_| "`order_id`".to_string()),
+            description: Some(format!("'{partition}-01-01'")),
+            row_count,
+        };
+        let partitioning = build_partitioning(&[
+            row("2023", None, 10),
+            row("2024", Some("s0"), 3),
+            row("2024", Some("s1"), 4),
+        ])
+        .unwrap();
+        assert_eq!(
+            partitioning.strategy,
+            db::store::PartitionStrategy::RangeColumns
+        );
+        assert_eq!(partitioning.expression, "`order_date`");
+        assert_eq!(partitioning.partitions.len(), 2);
+        assert_eq!(partitioning.partitions[0].value, "'2023-01-01'");
+        assert_eq!(partitioning.partitions[0].row_count, 10);
+        assert!(partitioning.partitions[0].subpartitioning.is_none());
+        assert_eq!(partitioning.partitions[1].row_count, 7);
+        let subpartitioning = partitioning.partitions[1].subpartitioning.as_ref().unwrap();
+        assert_eq!(subpartitioning.strategy, db::store::PartitionStrategy::Key);
+        assert_eq!(subpartitioning.expression, "`order_id`");
+        let subpartitions: Vec<(&str, i64)> = subpartitioning
+            .partitions
+            .iter()
+            .map(|p| (p.name.as_str(), p.row_count))
+            .collect();
+        assert_eq!(subpartitions, vec![("s0", 3), ("s1", 4)]);
+
+        let mut unknown = row("2023", None, 0);
+        unknown.method = "SYSTEM_TIME".to_string();
+        assert_eq!(
+            build_partitioning(&[unknown]).unwrap_err().to_string(),
+            "unrecognized partition method SYSTEM_TIME"
+        );
+    }
+
+    #[test]
+    fn test_format_event_schedule() {
+        assert_eq!(
+            format_event_schedule(Some("2024-06-01 00:00:00"), None, None, None),
+            "AT '2024-06-01 00:00:00'"
+        );
+        assert_eq!(
+            format_event_schedule(None, Some(("1", "DAY")), Some("2024-01-01 03:00:00"), None),
+            "EVERY 1 DAY STARTS '2024-01-01 03:00:00'"
+        );
+        assert_eq!(
+            format_event_schedule(
+                None,
+                Some(("1:30", "HOUR_MINUTE")),
+                Some("2024-01-01 00:00:00"),
+                Some("2024-12-31 00:00:00")
+            ),
+            "EVERY '1:30' HOUR_MINUTE STARTS '2024-01-01 00:00:00' ENDS '2024-12-31 00:00:00'"
+        );
+    }
+
+    #[test]
+    fn test_parse_view_dependent_columns() {
+        let column = |name: &str, position: i32| db::store::ColumnMetadata {
+            name: name.to_string(),
+            position,
+            default: String::new(),
+            on_update: None,
+            nullable: true,
+            r#type: "int".to_string(),
+            character_set: String::new(),
+            collation: String::new(),
+            comment: String::new(),
+            identity_generation: db::store::IdentityGeneration::UNSPECIFIED,
+        };
+        let column_map = HashMap::from([
+            (
+                "customers".to_string(),
+                vec![column("customer_id", 1), column("email", 2)],
+            ),
+            (
+                "orders".to_string(),
+                vec![column("order_id", 1), column("customer_id", 2)],
+            ),
+        ]);
+        let tables = vec!["customers".to_string(), "orders".to_string()];
+
+        let definition = "select `test_db`.`c`.`email` AS `email`,`test_db`.`o`.`order_id` AS `order_id`,`other_db`.`t`.`id` AS `id` \
+            from (`test_db`.`customers` `c` join `test_db`.`orders` `o` \
+            on((`test_db`.`c`.`customer_id` = `test_db`.`o`.`customer_id`)))";
+
+        let dependent_columns: Vec<(String, String)> =
+            parse_view_dependent_columns(definition, "test_db", &tables, &column_map)
+                .into_iter()
+                .map(|c| (c.table, c.column))
+                .collect();
+
+        assert_eq!(
+            dependent_columns,
+            vec![
+                ("customers".to_string(), "customer_id".to_string()),
+                ("customers".to_string(), "email".to_string()),
+                ("orders".to_string(), "order_id".to_string()),
+                ("orders".to_string(), "customer_id".to_string()),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sync_database_lenient() {
+        let test_config = init_mysql_test_service().unwrap();
+        init_mysql_test_schema()
+            .await
+            .expect("Failed to initialize test schema");
+        let driver = Driver::create_driver(&test_config).await.unwrap();
+        let (_, warnings) = driver.sync_database_lenient().await.unwrap();
+        assert!(warnings.is_empty(), "{warnings:?}");
+
+        // With EXECUTE only, the routines are listed but their definitions are hidden.
+        for statement in [
+            "DROP USER IF EXISTS 'dbmeta_reader'@'%'".to_string(),
+            "CREATE USER 'dbmeta_reader'@'%' IDENTIFIED BY 'dbmeta_reader'".to_string(),
+            format!(
+                "GRANT SELECT, EXECUTE ON `{}`.* TO 'dbmeta_reader'@'%'",
+                test_config.database
+            ),
+        ] {
+            sqlx::query(&statement).execute(&driver.pool).await.unwrap();
+        }
+        let mut reader_config = test_config.clone();
+        reader_config.username = "dbmeta_reader".to_string();
+        reader_config.password = "dbmeta_reader".to_string();
+        let reader = Driver::create_driver(&reader_config).await.unwrap();
+
+        let err = reader.sync_database().await.unwrap_err();
+        assert_eq!(err.kind(), db::ErrorKind::PermissionDenied);
+        assert_eq!(
+            err.context(),
+            vec!["load_routines", "calculate_order_total"]
+        );
+
+        let (database, warnings) = reader.sync_database_lenient().await.unwrap();
+        let paths: Vec<_> = warnings.iter().map(|w| w.path.join(".")).collect();
+        assert_eq!(paths, vec!["calculate_order_total", "get_customer_orders"]);
+        let schema = &database.schemas[0];
+        assert!(!schema.tables.is_empty());
+        assert_eq!(schema.functions[0].name, "calculate_order_total");
+        assert!(schema.functions[0].definition.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_routine_definitions() {
+        let test_config = init_mysql_test_service().unwrap();
+        init_mysql_test_schema()
+            .await
+            .expect("Failed to initialize test schema");
+        let driver = Driver::create_driver(&test_config).await.unwrap();
+        let schema = driver.sync_database().await.unwrap().schemas.remove(0);
+        let mut conn = driver.pool.acquire().await.unwrap();
+
+        let mut routines = vec![];
+        for function in &schema.functions {
+            let show_create = driver
+                .get_create_function_stmt(&mut conn, &test_config.database, &function.name)
+                .await
+                .unwrap();
+            routines.push((function.definition.clone(), show_create));
+        }
+        for procedure in &schema.procedures {
+            let show_create = driver
+                .get_create_procedure_stmt(&mut conn, &test_config.database, &procedure.name)
+                .await
+                .unwrap();
+            routines.push((procedure.definition.clone(), show_create));
+        }
+        assert_eq!(routines.len(), 2);
+        for (definition, show_create) in routines {
+            assert_eq!(definition, show_create);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_statement_timeout() {
+        let mut test_config = init_mysql_test_service().unwrap();
+        test_config.pool.statement_timeout = Some(Duration::from_millis(200));
+        let driver = Driver::create_driver(&test_config).await.unwrap();
+        let mut conn = driver.pool.acquire().await.unwrap();
+
+        // max_execution_time does not limit DO, the statement is killed instead.
+        let start = std::time::Instant::now();
+        let err = driver
+            .fetch_bounded(&mut conn, "DO SLEEP(5)")
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), db::ErrorKind::Timeout);
+        assert!(start.elapsed() < Duration::from_secs(5));
+        let one: i64 = sqlx::query_scalar("SELECT 1")
+            .fetch_one(&mut *conn)
+            .await
+            .unwrap();
+        assert_eq!(one, 1);
+    }
+
+    #[tokio::test]
+    async fn test_parse_fixture() {
+        let test_config = init_mysql_test_service().unwrap();
+        init_mysql_test_schema()
+            .await
+            .expect("Failed to initialize test schema");
+        let driver = Driver::create_driver(&test_config).await.unwrap();
+        let synced = driver.sync_database().await.unwrap();
+
+        let sql = std::fs::read_to_string("tests/fixtures/mysql_schema.sql").unwrap()
+            + &std::fs::read_to_string("tests/fixtures/mysql_routines.sql").unwrap();
+        let parsed = db::parser::mysql::parse_database(&synced.name, &sql).unwrap();
+
+        // Definitions are kept as written by the parser and normalized by the server, and the file
+        // sets no definers.
+        let options = db::diff::DiffOptions {
+            ignore_definitions: true,
+            ignore_owners: true,
+        };
+        let changes = db::diff::diff_database_with(&synced, &parsed, &options);
+        assert!(
+            changes.is_empty(),
+            "Parsed schemas should match the synced ones, got {changes:#?}"
+        );
+
+        // Compared with their definitions, the parsed schema only differs by the definitions and
+        // the expression partition key parse_database keeps as written.
+        let options = db::diff::DiffOptions {
+            ignore_definitions: false,
+            ignore_owners: true,
+        };
+        let mut changes: Vec<String> = db::diff::diff_database_with(&synced, &parsed, &options)
+            .iter()
+            .map(|c| c.to_string())
+            .collect();
+        changes.sort();
+        assert_eq!(
+            changes,
+            vec![
+                "customer_orders: view changed",
+                "event purge_cancelled_orders changed",
+                "function calculate_order_total changed",
+                "order_archive: table [Partitioning] changed",
+                "procedure get_customer_orders changed",
+                "products: trigger trg_products_trim_name changed",
+            ]
+        );
+    }
+
     #[tokio::test]
     async fn test_get_version() {
         let test_config = init_mysql_test_service().unwrap();
@@ -925,11 +2136,12 @@ mod test {
 
         let d = Driver::create_driver(&test_config).await.unwrap();
 
-        let v = d.get_version().await.unwrap();
+        let mut conn = d.pool.acquire().await.unwrap();
+        let v = d.get_version(&mut conn).await.unwrap();
 
         println!("VERSION:{:?}\n", v);
 
-        let db_metadatas = d.load_database().await.unwrap();
+        let db_metadatas = d.load_database(&mut conn).await.unwrap();
 
         println!("db:{:?}\n", db_metadatas);
 
@@ -0,0 +1,843 @@
+// Builds store metadata from SQL schema files without connecting to a database.
+// Each dialect replays the DDL statements of a file in order and shapes the result
+// like the metadata synced by the driver of the same engine.
+use super::error::DBError;
+
+pub mod mysql;
+pub mod postgres;
+
+#[derive(Clone, PartialEq, Debug)]
+pub(crate) enum Token {
+    // Word is an unquoted keyword or identifier.
+    Word(String),
+    // Ident is a quoted identifier.
+    Ident(String),
+    // Str is a string literal with its escapes resolved.
+    Str(String),
+    Number(String),
+    Punct(char),
+    // Dollar is the body of a PostgreSQL dollar quoted string.
+    Dollar(String),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) enum Dialect {
+    MySQL,
+    PostgreSQL,
+}
+
+#[derive(Clone, Debug)]
+struct Spanned {
+    token: Token,
+    start: usize,
+    end: usize,
+}
+
+// Splits a SQL file into statements. Statements end with a semicolon outside of quotes and
+// comments, MySQL files may switch the terminator with the client DELIMITER command.
+pub(crate) fn split_statements(sql: &str, dialect: Dialect) -> Vec<&str> {
+    let bytes = sql.as_bytes();
+    let mut statements = vec![];
+    let mut delimiter = ";".to_string();
+    let mut start = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        let c = bytes[i];
+        if dialect == Dialect::MySQL
+            && (c == b'D' || c == b'd')
+            && sql[..i].rsplit('\n').next().unwrap_or("").trim().is_empty()
+        {
+            let line_end = sql[i..].find('\n').map(|n| i + n).unwrap_or(sql.len());
+            let line = sql[i..line_end].trim();
+            if line.len() > 10
+                && line
+                    .get(..10)
+                    .is_some_and(|l| l.eq_ignore_ascii_case("DELIMITER "))
+            {
+                push_statement(&mut statements, &sql[start..i], dialect);
+                delimiter = line[10..].trim().to_string();
+                i = line_end;
+                start = i;
+                continue;
+            }
+        }
+
+        if sql[i..].starts_with(delimiter.as_str()) {
+            push_statement(&mut statements, &sql[start..i], dialect);
+            i += delimiter.len();
+            start = i;
+            continue;
+        }
+
+        i = match c {
+            // An unterminated literal runs to the end, the tokenizer reports it.
+            b'\'' | b'"' | b'`' => skip_quoted(bytes, i, c, dialect).unwrap_or(bytes.len()),
+            b'-' if sql[i..].starts_with("--") => skip_line(sql, i),
+            b'#' if dialect == Dialect::MySQL => skip_line(sql, i),
+            b'/' if sql[i..].starts_with("/*") => sql[i + 2..]
+                .find("*/")
+                .map(|n| i + n + 4)
+                .unwrap_or(sql.len()),
+            b'$' if dialect == Dialect::PostgreSQL => match dollar_tag(&sql[i..]) {
+                Some(tag) => {
+                    let body = i + tag.len();
+                    sql[body..]
+                        .find(tag)
+                        .map(|n| body + n + tag.len())
+                        .unwrap_or(sql.len())
+                }
+                None => i + 1,
+            },
+            _ => i + sql[i..].chars().next().map_or(1, char::len_utf8),
+        };
+    }
+    push_statement(&mut statements, &sql[start..], dialect);
+
+    statements
+}
+
+// Pushes a statement without its leading comments, MySQL conditional comments are kept as
+// they hold SQL.
+fn push_statement<'a>(statements: &mut Vec<&'a str>, mut statement: &'a str, dialect: Dialect) {
+    loop {
+        statement = statement.trim_start();
+        if statement.starts_with("--") || (dialect == Dialect::MySQL && statement.starts_with('#'))
+        {
+            statement = &statement[skip_line(statement, 0)..];
+        } else if statement.starts_with("/*") && !statement.starts_with("/*!") {
+            statement = statement[2..]
+                .find("*/")
+                .map_or("", |n| &statement[n + 4..]);
+        } else {
+            break;
+        }
+    }
+    if !statement.trim().is_empty() {
+        statements.push(statement.trim());
+    }
+}
+
+// Returns the end of the quoted literal starting at start, or None when it is not terminated.
+fn skip_quoted(bytes: &[u8], start: usize, quote: u8, dialect: Dialect) -> Option<usize> {
+    let mut i = start + 1;
+    while i < bytes.len() {
+        if bytes[i] == b'\\' && quote != b'`' && dialect == Dialect::MySQL {
+            i += 2;
+        } else if bytes[i] == quote {
+            if bytes.get(i + 1) == Some(&quote) {
+                i += 2;
+            } else {
+                return Some(i + 1);
+            }
+        } else {
+            i += 1;
+        }
+    }
+    None
+}
+
+fn skip_line(sql: &str, start: usize) -> usize {
+    sql[start..]
+        .find('\n')
+        .map(|n| start + n + 1)
+        .unwrap_or(sql.len())
+}
+
+// Returns the opening `$tag$` of a dollar quoted string.
+fn dollar_tag(s: &str) -> Option<&str> {
+    let end = s[1..].find('$')? + 1;
+    let tag = &s[1..end];
+    if tag.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && !tag.starts_with(|c: char| c.is_ascii_digit())
+    {
+        Some(&s[..=end])
+    } else {
+        None
+    }
+}
+
+fn tokenize(sql: &str, dialect: Dialect) -> Result<Vec<Spanned>, DBError> {
+    let bytes = sql.as_bytes();
+    let mut tokens = vec![];
+    let mut i = 0;
+    // MySQL executable comments such as /*!80000 INVISIBLE */ are parsed as plain SQL.
+    let mut in_executable_comment = false;
+    let quoted = |start: usize, quote: u8| {
+        skip_quoted(bytes, start, quote, dialect).ok_or_else(|| {
+            DBError::args(format!(
+                "unterminated quoted literal {}",
+                &sql[start..].chars().take(20).collect::<String>()
+            ))
+        })
+    };
+
+    while i < bytes.len() {
+        let c = bytes[i] as char;
+        let start = i;
+        if c.is_ascii_whitespace() {
+            i += 1;
+            continue;
+        }
+        if sql[i..].starts_with("--") || (c == '#' && dialect == Dialect::MySQL) {
+            i = skip_line(sql, i);
+            continue;
+        }
+        if sql[i..].starts_with("/*!") && dialect == Dialect::MySQL {
+            i += 3;
+            while i < bytes.len() && bytes[i].is_ascii_digit() {
+                i += 1;
+            }
+            in_executable_comment = true;
+            continue;
+        }
+        if sql[i..].starts_with("*/") && in_executable_comment {
+            in_executable_comment = false;
+            i += 2;
+            continue;
+        }
+        if sql[i..].starts_with("/*") {
+            i = sql[i + 2..]
+                .find("*/")
+                .map(|n| i + n + 4)
+                .unwrap_or(sql.len());
+            continue;
+        }
+
+        let token = match c {
+            '\'' => {
+                i = quoted(i, b'\'')?;
+                Token::Str(unescape(&sql[start + 1..i - 1], '\'', dialect))
+            }
+            '"' if dialect == Dialect::MySQL => {
+                i = quoted(i, b'"')?;
+                Token::Str(unescape(&sql[start + 1..i - 1], '"', dialect))
+            }
+            '"' | '`' => {
+                i = quoted(i, c as u8)?;
+                Token::Ident(sql[start + 1..i - 1].replace(&format!("{c}{c}"), &c.to_string()))
+            }
+            '$' if dialect == Dialect::PostgreSQL && dollar_tag(&sql[i..]).is_some() => {
+                let tag = dollar_tag(&sql[i..]).unwrap_or_default();
+                let body = i + tag.len();
+                let end = sql[body..].find(tag).map(|n| body + n).ok_or_else(|| {
+                    DBError::args(format!("unterminated dollar quoted string {tag}"))
+                })?;
+                i = end + tag.len();
+                Token::Dollar(sql[body..end].to_string())
+            }
+            c if c.is_ascii_digit()
+                || (c == '.' && sql[i + 1..].starts_with(|n: char| n.is_ascii_digit())) =>
+            {
+                while i < bytes.len() && (bytes[i].is_ascii_digit() || bytes[i] == b'.') {
+                    i += 1;
+                }
+                if i < bytes.len() && (bytes[i] == b'e' || bytes[i] == b'E') {
+                    i += 1;
+                    if i < bytes.len() && (bytes[i] == b'+' || bytes[i] == b'-') {
+                        i += 1;
+                    }
+                    while i < bytes.len() && bytes[i].is_ascii_digit() {
+                        i += 1;
+                    }
+                }
+                Token::Number(sql[start..i].to_string())
+            }
+            c if c.is_alphabetic() || c == '_' || !c.is_ascii() => {
+                while i < bytes.len() {
+                    let n = sql[i..].chars().next().unwrap_or(' ');
+                    if n.is_alphanumeric() || n == '_' || n == '$' || !n.is_ascii() {
+                        i += n.len_utf8();
+                    } else {
+                        break;
+                    }
+                }
+                let word = &sql[start..i];
+                let prefix = word.len() == 1 && "bBxXnN".contains(word) || word.starts_with('_');
+                if bytes.get(i) == Some(&b'\'') && dialect == Dialect::MySQL && prefix {
+                    // b'01' and x'ff' are bit and hex literals, N'..' and _utf8mb4'..' are strings.
+                    let literal_start = i;
+                    i = quoted(i, b'\'')?;
+                    let literal = &sql[literal_start + 1..i - 1];
+                    if word.eq_ignore_ascii_case("b") || word.eq_ignore_ascii_case("x") {
+                        Token::Word(format!("{}'{literal}'", word.to_lowercase()))
+                    } else {
+                        Token::Str(unescape(literal, '\'', dialect))
+                    }
+                } else {
+                    Token::Word(word.to_string())
+                }
+            }
+            c => {
+                i += 1;
+                Token::Punct(c)
+            }
+        };
+        tokens.push(Spanned {
+            token,
+            start,
+            end: i,
+        });
+    }
+
+    Ok(tokens)
+}
+
+fn unescape(s: &str, quote: char, dialect: Dialect) -> String {
+    let doubled = format!("{quote}{quote}");
+    if dialect != Dialect::MySQL {
+        return s.replace(&doubled, &quote.to_string());
+    }
+    let mut out = String::new();
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some('n') => out.push('\n'),
+                Some('r') => out.push('\r'),
+                Some('t') => out.push('\t'),
+                Some('0') => out.push('\0'),
+                Some(other) => out.push(other),
+                None => {}
+            },
+            c if c == quote => {
+                // The quote is doubled inside the literal.
+                chars.next();
+                out.push(c);
+            }
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+// Cursor walks the tokens of a single statement.
+pub(crate) struct Cursor<'a> {
+    sql: &'a str,
+    tokens: Vec<Spanned>,
+    pos: usize,
+    dialect: Dialect,
+}
+
+impl<'a> Cursor<'a> {
+    pub(crate) fn new(sql: &'a str, dialect: Dialect) -> Result<Self, DBError> {
+        Ok(Cursor {
+            sql,
+            tokens: tokenize(sql, dialect)?,
+            pos: 0,
+            dialect,
+        })
+    }
+
+    pub(crate) fn at_end(&self) -> bool {
+        self.pos >= self.tokens.len()
+    }
+
+    pub(crate) fn peek(&self) -> Option<&Token> {
+        self.peek_at(0)
+    }
+
+    pub(crate) fn peek_at(&self, n: usize) -> Option<&Token> {
+        self.tokens.get(self.pos + n).map(|t| &t.token)
+    }
+
+    pub(crate) fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).map(|t| t.token.clone());
+        if token.is_some() {
+            self.pos += 1;
+        }
+        token
+    }
+
+    pub(crate) fn is_keyword_at(&self, n: usize, keyword: &str) -> bool {
+        matches!(self.peek_at(n), Some(Token::Word(w)) if w.eq_ignore_ascii_case(keyword))
+    }
+
+    pub(crate) fn is_keyword(&self, keyword: &str) -> bool {
+        self.is_keyword_at(0, keyword)
+    }
+
+    pub(crate) fn is_punct(&self, c: char) -> bool {
+        self.peek() == Some(&Token::Punct(c))
+    }
+
+    pub(crate) fn eat_keyword(&mut self, keyword: &str) -> bool {
+        if self.is_keyword(keyword) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    // Consumes the keywords only when all of them follow in order.
+    pub(crate) fn eat_keywords(&mut self, keywords: &[&str]) -> bool {
+        if keywords
+            .iter()
+            .enumerate()
+            .all(|(n, k)| self.is_keyword_at(n, k))
+        {
+            self.pos += keywords.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    pub(crate) fn expect_keyword(&mut self, keyword: &str) -> Result<(), DBError> {
+        if self.eat_keyword(keyword) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected {keyword}")))
+        }
+    }
+
+    pub(crate) fn eat_punct(&mut self, c: char) -> bool {
+        if self.is_punct(c) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    pub(crate) fn expect_punct(&mut self, c: char) -> Result<(), DBError> {
+        if self.eat_punct(c) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected '{c}'")))
+        }
+    }
+
+    // Consumes an identifier, unquoted PostgreSQL identifiers are folded to lower case.
+    pub(crate) fn identifier(&mut self) -> Result<String, DBError> {
+        match self.peek().cloned() {
+            Some(Token::Word(w)) => {
+                self.pos += 1;
+                Ok(match self.dialect {
+                    Dialect::MySQL => w,
+                    Dialect::PostgreSQL => w.to_lowercase(),
+                })
+            }
+            Some(Token::Ident(w)) => {
+                self.pos += 1;
+                Ok(w)
+            }
+            _ => Err(self.error("expected an identifier")),
+        }
+    }
+
+    // Consumes a possibly qualified name and returns its parts.
+    pub(crate) fn object_name(&mut self) -> Result<Vec<String>, DBError> {
+        let mut parts = vec![self.identifier()?];
+        while self.is_punct('.') {
+            self.pos += 1;
+            parts.push(self.identifier()?);
+        }
+        Ok(parts)
+    }
+
+    // Consumes a parenthesized list of identifiers.
+    pub(crate) fn identifier_list(&mut self) -> Result<Vec<String>, DBError> {
+        self.expect_punct('(')?;
+        let mut names = vec![self.identifier()?];
+        while self.eat_punct(',') {
+            names.push(self.identifier()?);
+        }
+        self.expect_punct(')')?;
+        Ok(names)
+    }
+
+    pub(crate) fn string(&mut self) -> Result<String, DBError> {
+        match self.next() {
+            Some(Token::Str(s)) | Some(Token::Dollar(s)) => Ok(s),
+            _ => Err(self.error("expected a string literal")),
+        }
+    }
+
+    // Consumes a balanced group starting at an opening parenthesis and returns its inner text.
+    pub(crate) fn parenthesized(&mut self) -> Result<&'a str, DBError> {
+        let open = self
+            .tokens
+            .get(self.pos)
+            .filter(|t| t.token == Token::Punct('('))
+            .map(|t| t.end)
+            .ok_or_else(|| self.error("expected '('"))?;
+        let mut depth = 0;
+        while let Some(t) = self.tokens.get(self.pos) {
+            self.pos += 1;
+            match t.token {
+                Token::Punct('(') => depth += 1,
+                Token::Punct(')') => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Ok(self.sql[open..t.start].trim());
+                    }
+                }
+                _ => {}
+            }
+        }
+        Err(self.error("unbalanced parentheses"))
+    }
+
+    // Skips tokens up to the next comma or closing parenthesis outside of nested parentheses.
+    pub(crate) fn skip_element(&mut self) {
+        let mut depth = 0;
+        while let Some(t) = self.tokens.get(self.pos) {
+            match t.token {
+                Token::Punct('(') => depth += 1,
+                Token::Punct(')') if depth == 0 => return,
+                Token::Punct(')') => depth -= 1,
+                Token::Punct(',') if depth == 0 => return,
+                _ => {}
+            }
+            self.pos += 1;
+        }
+    }
+
+    // Returns the text from the current token to the end of the statement.
+    pub(crate) fn rest(&self) -> &'a str {
+        match self.tokens.get(self.pos) {
+            Some(t) => self.sql[t.start..].trim(),
+            None => "",
+        }
+    }
+
+    // Returns the start offset of the current token.
+    pub(crate) fn offset(&self) -> usize {
+        self.tokens
+            .get(self.pos)
+            .map(|t| t.start)
+            .unwrap_or(self.sql.len())
+    }
+
+    // Returns the text between an offset and the end of the last consumed token.
+    pub(crate) fn text_from(&self, start: usize) -> &'a str {
+        let end = match self.pos {
+            0 => start,
+            n => self.tokens[n - 1].end.max(start),
+        };
+        self.sql[start..end].trim()
+    }
+
+    // Returns the consumed tokens since a position.
+    pub(crate) fn tokens_from(&self, pos: usize) -> Vec<Token> {
+        self.tokens[pos..self.pos]
+            .iter()
+            .map(|t| t.token.clone())
+            .collect()
+    }
+
+    pub(crate) fn position(&self) -> usize {
+        self.pos
+    }
+
+    pub(crate) fn error(&self, message: &str) -> DBError {
+        let near: String = match self.tokens.get(self.pos) {
+            Some(t) => self.sql[t.start..].chars().take(40).collect(),
+            None => "the end of the statement".to_string(),
+        };
+        DBError::args(format!("{message} near `{near}`"))
+    }
+}
+
+// DefaultValue is a column default as written in the DDL.
+#[derive(Clone, PartialEq, Debug)]
+pub(crate) enum DefaultValue {
+    Null,
+    Bool(bool),
+    Str(String),
+    Number(String),
+    // CurrentTimestamp carries the optional fractional seconds precision.
+    CurrentTimestamp(Option<String>),
+    // Expr is any other expression, kept as written.
+    Expr(String),
+}
+
+// Parses the value following a DEFAULT keyword. Casts following a literal are dropped,
+// the column type decides how the value is reported.
+pub(crate) fn parse_default(cursor: &mut Cursor) -> Result<DefaultValue, DBError> {
+    let start = cursor.offset();
+    let value = match cursor.peek().cloned() {
+        Some(Token::Punct('(')) => {
+            let inner = cursor.parenthesized()?;
+            match cursor.dialect {
+                // MySQL reports expression defaults in their parenthesized form.
+                Dialect::MySQL => DefaultValue::Expr(format!("({inner})")),
+                Dialect::PostgreSQL => {
+                    let mut inner_cursor = Cursor::new(inner, cursor.dialect)?;
+                    match parse_default(&mut inner_cursor)? {
+                        value if inner_cursor.at_end() => value,
+                        _ => DefaultValue::Expr(format!("({inner})")),
+                    }
+                }
+            }
+        }
+        Some(Token::Str(s)) => {
+            cursor.next();
+            DefaultValue::Str(s)
+        }
+        Some(Token::Number(n)) => {
+            cursor.next();
+            DefaultValue::Number(n)
+        }
+        Some(Token::Punct(sign @ ('-' | '+'))) => {
+            cursor.next();
+            match cursor.next() {
+                Some(Token::Number(n)) if sign == '-' => DefaultValue::Number(format!("-{n}")),
+                Some(Token::Number(n)) => DefaultValue::Number(n),
+                _ => return Err(cursor.error("expected a number")),
+            }
+        }
+        Some(Token::Word(w)) => {
+            cursor.next();
+            let upper = w.to_uppercase();
+            match upper.as_str() {
+                "NULL" => DefaultValue::Null,
+                "TRUE" => DefaultValue::Bool(true),
+                "FALSE" => DefaultValue::Bool(false),
+                // MySQL treats NOW() and LOCALTIMESTAMP as synonyms of CURRENT_TIMESTAMP.
+                "CURRENT_TIMESTAMP" | "NOW" | "LOCALTIMESTAMP" | "LOCALTIME"
+                    if cursor.dialect == Dialect::MySQL || upper == "CURRENT_TIMESTAMP" =>
+                {
+                    let precision = if cursor.is_punct('(') {
+                        Some(cursor.parenthesized()?.to_string()).filter(|p| !p.is_empty())
+                    } else {
+                        None
+                    };
+                    DefaultValue::CurrentTimestamp(precision)
+                }
+                _ => {
+                    // A function call or a keyword such as CURRENT_DATE.
+                    if cursor.is_punct('(') {
+                        cursor.parenthesized()?;
+                    }
+                    DefaultValue::Expr(cursor.text_from(start).to_string())
+                }
+            }
+        }
+        _ => return Err(cursor.error("expected a default value")),
+    };
+
+    // An operator continues the value as an expression, such as `CURRENT_DATE + 1`.
+    let mut compound = false;
+    while let Some(Token::Punct(c)) = cursor.peek() {
+        if matches!(c, ',' | ')' | ';' | '(')
+            || (*c == ':' && cursor.dialect == Dialect::PostgreSQL)
+        {
+            break;
+        }
+        compound = true;
+        cursor.next();
+        match cursor.peek() {
+            Some(Token::Punct('(')) => {
+                cursor.parenthesized()?;
+            }
+            Some(Token::Punct(_)) | None => {}
+            Some(_) => {
+                cursor.next();
+                if cursor.is_punct('(') {
+                    cursor.parenthesized()?;
+                }
+            }
+        }
+    }
+    if compound {
+        return Ok(DefaultValue::Expr(cursor.text_from(start).to_string()));
+    }
+
+    if cursor.dialect == Dialect::PostgreSQL && cursor.is_punct(':') {
+        let literal = matches!(
+            value,
+            DefaultValue::Str(_)
+                | DefaultValue::Number(_)
+                | DefaultValue::Bool(_)
+                | DefaultValue::Null
+        );
+        while cursor.is_punct(':') && cursor.peek_at(1) == Some(&Token::Punct(':')) {
+            cursor.next();
+            cursor.next();
+            skip_type(cursor)?;
+        }
+        if !literal {
+            return Ok(DefaultValue::Expr(cursor.text_from(start).to_string()));
+        }
+    }
+
+    Ok(value)
+}
+
+// Consumes a type name with its arguments, such as `character varying(20)` or `int[]`.
+// The type ends before a comma, a closing parenthesis or one of the stop keywords.
+pub(crate) fn parse_type<'a>(
+    cursor: &mut Cursor<'a>,
+    stop_keywords: &[&str],
+) -> Result<Vec<Token>, DBError> {
+    let start = cursor.position();
+    loop {
+        match cursor.peek() {
+            None | Some(Token::Punct(',')) | Some(Token::Punct(')')) | Some(Token::Punct(';')) => {
+                break
+            }
+            Some(Token::Punct('(')) => {
+                cursor.parenthesized()?;
+            }
+            Some(Token::Word(w)) => {
+                let stop = stop_keywords.iter().any(|k| w.eq_ignore_ascii_case(k))
+                    // CHARACTER starts a type unless it is followed by SET.
+                    && (!w.eq_ignore_ascii_case("CHARACTER") || cursor.is_keyword_at(1, "SET"));
+                if stop && cursor.position() > start {
+                    break;
+                }
+                cursor.next();
+            }
+            Some(Token::Punct(':')) => break,
+            _ => {
+                cursor.next();
+            }
+        }
+    }
+    if cursor.position() == start {
+        return Err(cursor.error("expected a type"));
+    }
+    Ok(cursor.tokens_from(start))
+}
+
+fn skip_type(cursor: &mut Cursor) -> Result<(), DBError> {
+    parse_type(
+        cursor,
+        &[
+            "NOT",
+            "NULL",
+            "DEFAULT",
+            "PRIMARY",
+            "UNIQUE",
+            "REFERENCES",
+            "CHECK",
+            "CONSTRAINT",
+            "COLLATE",
+            "GENERATED",
+        ],
+    )?;
+    Ok(())
+}
+
+// TypeName is a parsed type: its lower case words, its arguments and whether it is an array.
+#[derive(Clone, PartialEq, Debug)]
+pub(crate) struct TypeName {
+    pub(crate) words: Vec<String>,
+    pub(crate) args: Vec<String>,
+    // The words following the arguments, such as `with time zone` or `unsigned`.
+    pub(crate) suffix: Vec<String>,
+    pub(crate) array: bool,
+}
+
+impl TypeName {
+    pub(crate) fn from_tokens(tokens: &[Token]) -> TypeName {
+        let mut type_name = TypeName {
+            words: vec![],
+            args: vec![],
+            suffix: vec![],
+            array: false,
+        };
+        let mut depth = 0;
+        let mut seen_args = false;
+        let mut arg = String::new();
+        for token in tokens {
+            match token {
+                Token::Punct('(') if depth == 0 => depth += 1,
+                Token::Punct(')') if depth == 1 => {
+                    depth -= 1;
+                    seen_args = true;
+                    type_name.args.push(arg.trim().to_string());
+                    arg.clear();
+                }
+                Token::Punct(',') if depth == 1 => {
+                    type_name.args.push(arg.trim().to_string());
+                    arg.clear();
+                }
+                Token::Punct('[') | Token::Punct(']') if depth == 0 => type_name.array = true,
+                t if depth > 0 => {
+                    arg.push_str(&match t {
+                        Token::Str(s) => format!("'{}'", s.replace('\'', "''")),
+                        Token::Word(w) | Token::Ident(w) | Token::Number(w) => w.clone(),
+                        Token::Punct(c) => c.to_string(),
+                        Token::Dollar(s) => s.clone(),
+                    });
+                    if let Token::Punct('(') = t {
+                        depth += 1;
+                    }
+                    if let Token::Punct(')') = t {
+                        depth -= 1;
+                    }
+                }
+                Token::Word(w) if seen_args => type_name.suffix.push(w.to_lowercase()),
+                Token::Word(w) => type_name.words.push(w.to_lowercase()),
+                Token::Ident(w) => type_name.words.push(w.clone()),
+                Token::Punct('.') => type_name.words.push(".".to_string()),
+                _ => {}
+            }
+        }
+        type_name
+    }
+
+    // Returns the name, qualified names are joined with a dot.
+    pub(crate) fn name(&self) -> String {
+        self.words.join(" ").replace(" . ", ".")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{split_statements, tokenize, Dialect};
+
+    #[test]
+    fn test_split_statements() {
+        let sql = "-- comment; not a statement\nCREATE TABLE t (a INT COMMENT 'a;b');\n\nDELIMITER $$\nCREATE PROCEDURE p() BEGIN SELECT 1; END$$\nDELIMITER ;\nINSERT INTO t VALUES (1)";
+        assert_eq!(
+            split_statements(sql, Dialect::MySQL),
+            vec![
+                "CREATE TABLE t (a INT COMMENT 'a;b')",
+                "CREATE PROCEDURE p() BEGIN SELECT 1; END",
+                "INSERT INTO t VALUES (1)",
+            ]
+        );
+
+        let sql = "CREATE FUNCTION f() RETURNS int AS $$ SELECT 1; $$ LANGUAGE sql;\nDO $body$ BEGIN PERFORM 1; END $body$;";
+        assert_eq!(
+            split_statements(sql, Dialect::PostgreSQL),
+            vec![
+                "CREATE FUNCTION f() RETURNS int AS $$ SELECT 1; $$ LANGUAGE sql",
+                "DO $body$ BEGIN PERFORM 1; END $body$",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unterminated_quotes() {
+        for sql in [
+            "'",
+            "'é",
+            "'a\\'",
+            "\"a",
+            "`a",
+            "x'0f",
+            "b'01",
+            "N'é",
+            "_utf8mb4'a",
+        ] {
+            let err = tokenize(sql, Dialect::MySQL).unwrap_err();
+            assert!(
+                err.to_string().starts_with("unterminated quoted literal"),
+                "{sql}: {err}"
+            );
+            let statement = format!("CREATE TABLE t (a INT DEFAULT {sql}");
+            assert!(super::mysql::parse_database("db", &statement).is_err());
+        }
+        for sql in ["'", "'é", "\"é"] {
+            assert!(tokenize(sql, Dialect::PostgreSQL).is_err(), "{sql}");
+            let statement = format!("CREATE TABLE t (a text DEFAULT {sql}");
+            assert!(super::postgres::parse_database("db", &statement).is_err());
+        }
+        assert!(tokenize("$$", Dialect::PostgreSQL).is_err());
+    }
+}
@@ -0,0 +1,2171 @@
+use super::{
+    parse_default, parse_type, split_statements, Cursor, DefaultValue, Dialect, Token, TypeName,
+};
+use crate::db::ddl::mysql::{charset_of, quote_identifier, quote_string, AUTO_INCREMENT_SYMBOL};
+use crate::db::error::DBError;
+use crate::db::store;
+
+const DEFAULT_CHARACTER_SET: &str = "utf8mb4";
+const DEFAULT_COLLATION: &str = "utf8mb4_0900_ai_ci";
+const DEFAULT_ENGINE: &str = "InnoDB";
+// The DEFAULT_TIME_ZONE is the session time zone events are created in by default.
+const DEFAULT_TIME_ZONE: &str = "SYSTEM";
+
+// The keywords ending the type of a column definition.
+const COLUMN_OPTIONS: &[&str] = &[
+    "NOT",
+    "NULL",
+    "DEFAULT",
+    "AUTO_INCREMENT",
+    "PRIMARY",
+    "KEY",
+    "UNIQUE",
+    "COMMENT",
+    "CHARACTER",
+    "CHARSET",
+    "COLLATE",
+    "ON",
+    "GENERATED",
+    "AS",
+    "VISIBLE",
+    "INVISIBLE",
+    "COLUMN_FORMAT",
+    "STORAGE",
+    "SRID",
+    "REFERENCES",
+    "CHECK",
+    "CONSTRAINT",
+];
+
+// The keywords ending the type of a routine parameter or result.
+const ROUTINE_TYPE_END: &[&str] = &[
+    "CHARACTER",
+    "CHARSET",
+    "COLLATE",
+    "COMMENT",
+    "LANGUAGE",
+    "NOT",
+    "DETERMINISTIC",
+    "CONTAINS",
+    "NO",
+    "READS",
+    "MODIFIES",
+    "SQL",
+    "BEGIN",
+    "RETURN",
+];
+
+const TEXT_TYPES: &[&str] = &[
+    "char",
+    "varchar",
+    "tinytext",
+    "text",
+    "mediumtext",
+    "longtext",
+    "enum",
+    "set",
+];
+
+const INTEGER_TYPES: &[&str] = &["tinyint", "smallint", "mediumint", "int", "bigint"];
+
+// Column is a column definition, its default is resolved once the nullability is final.
+struct Column {
+    metadata: store::ColumnMetadata,
+    default: Option<DefaultValue>,
+    auto_increment: bool,
+    textual: bool,
+}
+
+struct Table {
+    name: String,
+    columns: Vec<Column>,
+    indexes: Vec<store::IndexMetadata>,
+    foreign_keys: Vec<store::ForeignKeyMetadata>,
+    engine: String,
+    character_set: String,
+    collation: String,
+    create_options: Vec<String>,
+    comment: String,
+    triggers: Vec<store::TriggerMetadata>,
+    partitioning: Option<store::PartitioningMetadata>,
+    // The foreign_key_indexes are the foreign keys of the current statement with the name of
+    // the index MySQL creates when no existing index starts with the foreign key columns.
+    foreign_key_indexes: Vec<(String, Vec<String>)>,
+}
+
+struct Parser {
+    character_set: String,
+    collation: String,
+    tables: Vec<Table>,
+    views: Vec<store::ViewMetadata>,
+    functions: Vec<store::FunctionMetadata>,
+    procedures: Vec<store::ProcedureMetadata>,
+    events: Vec<store::EventMetadata>,
+}
+
+// parse_database replays the DDL statements of a MySQL schema file and returns the database
+// metadata shaped like the MySQL driver sync. Data and unknown statements are skipped.
+// View, routine, trigger and event definitions and expression partition keys are kept as written
+// rather than in the server normalized form, as are event timestamps and partition values, which
+// the server stores evaluated. A recurring event without STARTS starts when the server creates
+// it, so the parsed schedule has no STARTS. Routine and event definers are left empty, and the
+// dependent columns of views are not resolved.
+pub fn parse_database(
+    database_name: &str,
+    sql: &str,
+) -> Result<store::DatabaseSchemaMetadata, DBError> {
+    let mut parser = Parser {
+        character_set: DEFAULT_CHARACTER_SET.to_string(),
+        collation: DEFAULT_COLLATION.to_string(),
+        tables: vec![],
+        views: vec![],
+        functions: vec![],
+        procedures: vec![],
+        events: vec![],
+    };
+    for statement in split_statements(sql, Dialect::MySQL) {
+        parser.parse_statement(statement)?;
+    }
+
+    Ok(parser.finish(database_name))
+}
+
+impl Parser {
+    fn parse_statement(&mut self, statement: &str) -> Result<(), DBError> {
+        let mut cursor = Cursor::new(statement, Dialect::MySQL)?;
+        if cursor.eat_keyword("CREATE") {
+            cursor.eat_keywords(&["OR", "REPLACE"]);
+            skip_create_options(&mut cursor);
+            if cursor.eat_keyword("TABLE") {
+                self.create_table(&mut cursor)
+            } else if cursor.eat_keyword("VIEW") {
+                self.create_view(&mut cursor)
+            } else if cursor.eat_keyword("FUNCTION") {
+                self.create_routine(&mut cursor, statement, false)
+            } else if cursor.eat_keyword("PROCEDURE") {
+                self.create_routine(&mut cursor, statement, true)
+            } else if cursor.eat_keyword("TRIGGER") {
+                self.create_trigger(&mut cursor, statement)
+            } else if cursor.eat_keyword("EVENT") {
+                self.create_event(&mut cursor, statement)
+            } else if cursor.is_keyword("INDEX") || cursor.is_keyword_at(1, "INDEX") {
+                self.create_index(&mut cursor)
+            } else if cursor.eat_keyword("DATABASE") || cursor.eat_keyword("SCHEMA") {
+                cursor.eat_keywords(&["IF", "NOT", "EXISTS"]);
+                cursor.identifier()?;
+                self.database_options(&mut cursor)
+            } else {
+                Ok(())
+            }
+        } else if cursor.eat_keyword("ALTER") {
+            if cursor.eat_keyword("TABLE") {
+                self.alter_table(&mut cursor)
+            } else if cursor.eat_keyword("DATABASE") || cursor.eat_keyword("SCHEMA") {
+                if !cursor.is_keyword("DEFAULT")
+                    && !cursor.is_keyword("CHARACTER")
+                    && !cursor.is_keyword("CHARSET")
+                    && !cursor.is_keyword("COLLATE")
+                {
+                    cursor.identifier()?;
+                }
+                self.database_options(&mut cursor)
+            } else {
+                Ok(())
+            }
+        } else if cursor.eat_keyword("DROP") {
+            self.drop(&mut cursor)
+        } else if cursor.eat_keywords(&["RENAME", "TABLE"]) {
+            loop {
+                let old = table_name(&mut cursor)?;
+                cursor.expect_keyword("TO")?;
+                let new = table_name(&mut cursor)?;
+                if let Some(table) = self.tables.iter_mut().find(|t| t.name == old) {
+                    table.name = new;
+                }
+                if !cursor.eat_punct(',') {
+                    return Ok(());
+                }
+            }
+        } else {
+            Ok(())
+        }
+    }
+
+    fn create_routine(
+        &mut self,
+        cursor: &mut Cursor,
+        statement: &str,
+        procedure: bool,
+    ) -> Result<(), DBError> {
+        let if_not_exists = cursor.eat_keywords(&["IF", "NOT", "EXISTS"]);
+        let existing = if procedure {
+            self.procedure_names()
+        } else {
+            self.function_names()
+        };
+        let Some(name) = routine_name(cursor, if_not_exists, &existing)? else {
+            return Ok(());
+        };
+        let parameters = routine_parameters(cursor.parenthesized()?, procedure)?;
+        let return_type = if !procedure && cursor.eat_keyword("RETURNS") {
+            routine_type(cursor)?
+        } else {
+            String::new()
+        };
+
+        // The characteristics precede the body, DEFINER is the default security.
+        let mut volatility = store::Volatility::Volatile;
+        let mut security = store::SecurityType::Definer;
+        let mut comment = String::new();
+        loop {
+            if cursor.eat_keyword("COMMENT") {
+                comment = cursor.string()?;
+            } else if cursor.eat_keywords(&["NOT", "DETERMINISTIC"]) {
+                volatility = store::Volatility::Volatile;
+            } else if cursor.eat_keyword("DETERMINISTIC") {
+                volatility = store::Volatility::Immutable;
+            } else if cursor.eat_keywords(&["SQL", "SECURITY"]) {
+                if cursor.eat_keyword("INVOKER") {
+                    security = store::SecurityType::Invoker;
+                } else {
+                    cursor.expect_keyword("DEFINER")?;
+                    security = store::SecurityType::Definer;
+                }
+            } else if !(cursor.eat_keywords(&["LANGUAGE", "SQL"])
+                || cursor.eat_keywords(&["CONTAINS", "SQL"])
+                || cursor.eat_keywords(&["NO", "SQL"])
+                || cursor.eat_keywords(&["READS", "SQL", "DATA"])
+                || cursor.eat_keywords(&["MODIFIES", "SQL", "DATA"]))
+            {
+                break;
+            }
+        }
+
+        let definition = statement.to_string();
+        let language = "SQL".to_string();
+        if procedure {
+            self.procedures.push(store::ProcedureMetadata {
+                name,
+                signature: String::new(),
+                definition,
+                parameters,
+                language,
+                volatility,
+                security,
+                owner: String::new(),
+                comment,
+            });
+        } else {
+            self.functions.push(store::FunctionMetadata {
+                name,
+                signature: String::new(),
+                definition,
+                parameters,
+                return_type,
+                language,
+                volatility,
+                security,
+                owner: String::new(),
+                comment,
+            });
+        }
+
+        Ok(())
+    }
+
+    fn create_trigger(&mut self, cursor: &mut Cursor, statement: &str) -> Result<(), DBError> {
+        let if_not_exists = cursor.eat_keywords(&["IF", "NOT", "EXISTS"]);
+        let name = table_name(cursor)?;
+        // Trigger names are unique in a database.
+        if self
+            .tables
+            .iter()
+            .any(|t| t.triggers.iter().any(|tr| tr.name == name))
+        {
+            if if_not_exists {
+                return Ok(());
+            }
+            return Err(DBError::args(format!("trigger {name} already exists")));
+        }
+        let timing = if cursor.eat_keyword("BEFORE") {
+            "BEFORE"
+        } else {
+            cursor.expect_keyword("AFTER")?;
+            "AFTER"
+        };
+        let event = match cursor.next() {
+            Some(Token::Word(w)) => w.to_uppercase(),
+            _ => return Err(cursor.error("expected a trigger event")),
+        };
+        cursor.expect_keyword("ON")?;
+        let table = table_name(cursor)?;
+        cursor.expect_keyword("FOR")?;
+        cursor.expect_keyword("EACH")?;
+        cursor.expect_keyword("ROW")?;
+        // FOLLOWS and PRECEDES place the trigger next to another one of the same table.
+        let position = if cursor.eat_keyword("FOLLOWS") {
+            Some((table_name(cursor)?, 1))
+        } else if cursor.eat_keyword("PRECEDES") {
+            Some((table_name(cursor)?, 0))
+        } else {
+            None
+        };
+
+        let table = self.table_mut(&table)?;
+        let index = match position {
+            Some((other, offset)) => {
+                table
+                    .triggers
+                    .iter()
+                    .position(|t| t.name == other)
+                    .ok_or_else(|| DBError::args(format!("trigger {other} does not exist")))?
+                    + offset
+            }
+            None => table.triggers.len(),
+        };
+        table.triggers.insert(
+            index,
+            store::TriggerMetadata {
+                name,
+                timing: timing.to_string(),
+                events: vec![event],
+                for_each_row: true,
+                definition: statement.to_string(),
+                enabled: true,
+            },
+        );
+
+        Ok(())
+    }
+
+    fn create_event(&mut self, cursor: &mut Cursor, statement: &str) -> Result<(), DBError> {
+        let if_not_exists = cursor.eat_keywords(&["IF", "NOT", "EXISTS"]);
+        let name = table_name(cursor)?;
+        if self.events.iter().any(|e| e.name == name) {
+            if if_not_exists {
+                return Ok(());
+            }
+            return Err(DBError::args(format!("event {name} already exists")));
+        }
+        cursor.expect_keyword("ON")?;
+        cursor.expect_keyword("SCHEDULE")?;
+        let schedule = event_schedule(cursor)?;
+
+        let mut on_completion = "NOT PRESERVE";
+        let mut status = "ENABLED";
+        let mut comment = String::new();
+        loop {
+            if cursor.eat_keywords(&["ON", "COMPLETION"]) {
+                on_completion = if cursor.eat_keyword("NOT") {
+                    "NOT PRESERVE"
+                } else {
+                    "PRESERVE"
+                };
+                cursor.expect_keyword("PRESERVE")?;
+            } else if cursor.eat_keyword("ENABLE") {
+                status = "ENABLED";
+            } else if cursor.eat_keyword("DISABLE") {
+                // DISABLE ON SLAVE or ON REPLICA marks an event created on a replica.
+                status = if cursor.eat_keyword("ON") {
+                    cursor.next();
+                    "SLAVESIDE_DISABLED"
+                } else {
+                    "DISABLED"
+                };
+            } else if cursor.eat_keyword("COMMENT") {
+                comment = cursor.string()?;
+            } else {
+                cursor.expect_keyword("DO")?;
+                break;
+            }
+        }
+
+        self.events.push(store::EventMetadata {
+            name,
+            definition: statement.to_string(),
+            schedule,
+            time_zone: DEFAULT_TIME_ZONE.to_string(),
+            status: status.to_string(),
+            on_completion: on_completion.to_string(),
+            definer: String::new(),
+            body: cursor.rest().to_string(),
+            comment,
+        });
+
+        Ok(())
+    }
+
+    fn function_names(&self) -> Vec<String> {
+        self.functions.iter().map(|f| f.name.clone()).collect()
+    }
+
+    fn procedure_names(&self) -> Vec<String> {
+        self.procedures.iter().map(|p| p.name.clone()).collect()
+    }
+
+    fn table_mut(&mut self, name: &str) -> Result<&mut Table, DBError> {
+        self.tables
+            .iter_mut()
+            .find(|t| t.name == name)
+            .ok_or_else(|| DBError::args(format!("table {name} does not exist")))
+    }
+
+    fn create_table(&mut self, cursor: &mut Cursor) -> Result<(), DBError> {
+        let if_not_exists = cursor.eat_keywords(&["IF", "NOT", "EXISTS"]);
+        let name = table_name(cursor)?;
+        if self.tables.iter().any(|t| t.name == name) {
+            if if_not_exists {
+                return Ok(());
+            }
+            return Err(DBError::args(format!("table {name} already exists")));
+        }
+
+        let mut table = Table {
+            name,
+            columns: vec![],
+            indexes: vec![],
+            foreign_keys: vec![],
+            engine: DEFAULT_ENGINE.to_string(),
+            character_set: self.character_set.clone(),
+            collation: self.collation.clone(),
+            create_options: vec![],
+            comment: String::new(),
+            triggers: vec![],
+            partitioning: None,
+            foreign_key_indexes: vec![],
+        };
+
+        let like = cursor.eat_keyword("LIKE")
+            || (cursor.is_punct('(') && cursor.is_keyword_at(1, "LIKE") && {
+                cursor.next();
+                cursor.next();
+                true
+            });
+        if like {
+            let source = table_name(cursor)?;
+            let source = self
+                .tables
+                .iter()
+                .find(|t| t.name == source)
+                .ok_or_else(|| DBError::args(format!("table {source} does not exist")))?;
+            // Foreign keys are not copied by CREATE TABLE ... LIKE.
+            table.columns = source
+                .columns
+                .iter()
+                .map(|c| Column {
+                    metadata: c.metadata.clone(),
+                    default: c.default.clone(),
+                    auto_increment: c.auto_increment,
+                    textual: c.textual,
+                })
+                .collect();
+            table.indexes = source.indexes.clone();
+            table.engine = source.engine.clone();
+            table.character_set = source.character_set.clone();
+            table.collation = source.collation.clone();
+            table.create_options = source.create_options.clone();
+            table.comment = source.comment.clone();
+            table.partitioning = source.partitioning.clone();
+            self.tables.push(table);
+            return Ok(());
+        }
+
+        cursor.expect_punct('(')?;
+        loop {
+            table.add_element(cursor)?;
+            if !cursor.eat_punct(',') {
+                break;
+            }
+        }
+        cursor.expect_punct(')')?;
+        table.table_options(cursor)?;
+        table.end_statement();
+        self.tables.push(table);
+
+        Ok(())
+    }
+
+    fn create_index(&mut self, cursor: &mut Cursor) -> Result<(), DBError> {
+        let (unique, index_type) = if cursor.eat_keyword("UNIQUE") {
+            (true, "BTREE")
+        } else if cursor.eat_keyword("FULLTEXT") {
+            (false, "FULLTEXT")
+        } else if cursor.eat_keyword("SPATIAL") {
+            (false, "SPATIAL")
+        } else {
+            (false, "BTREE")
+        };
+        cursor.expect_keyword("INDEX")?;
+        let name = cursor.identifier()?;
+        let using = if cursor.eat_keyword("USING") {
+            Some(cursor.identifier()?.to_uppercase())
+        } else {
+            None
+        };
+        cursor.expect_keyword("ON")?;
+        let table = table_name(cursor)?;
+        let mut index = parse_index(cursor, Some(name), false, unique, index_type)?;
+        if let Some(using) = using {
+            index.r#type = using;
+        }
+        let table = self.table_mut(&table)?;
+        table.add_index(index);
+        table.end_statement();
+
+        Ok(())
+    }
+
+    fn create_view(&mut self, cursor: &mut Cursor) -> Result<(), DBError> {
+        let name = table_name(cursor)?;
+        if cursor.is_punct('(') {
+            cursor.parenthesized()?;
+        }
+        cursor.expect_keyword("AS")?;
+        let mut definition = cursor.rest();
+        if let Some(position) = definition.to_uppercase().rfind("WITH") {
+            let tail = definition[position..].to_uppercase();
+            let tail: Vec<&str> = tail.split_whitespace().collect();
+            if tail.ends_with(&["CHECK", "OPTION"]) && tail.len() <= 4 {
+                definition = definition[..position].trim_end();
+            }
+        }
+
+        self.views.retain(|v| v.name != name);
+        self.views.push(store::ViewMetadata {
+            name,
+            definition: definition.to_string(),
+            // MySQL reports VIEW as the comment of every view.
+            comment: "VIEW".to_string(),
+            dependent_columns: vec![],
+        });
+
+        Ok(())
+    }
+
+    fn alter_table(&mut self, cursor: &mut Cursor) -> Result<(), DBError> {
+        let name = table_name(cursor)?;
+        let mut renamed = None;
+        let table = self.table_mut(&name)?;
+        loop {
+            if cursor.eat_keyword("ADD") {
+                if cursor.eat_keyword("PARTITION") {
+                    let partitioning = table
+                        .partitioning
+                        .as_mut()
+                        .ok_or_else(|| cursor.error("the table is not partitioned"))?;
+                    let template = partitioning
+                        .partitions
+                        .first()
+                        .and_then(|p| p.subpartitioning.as_ref())
+                        .map(|s| Subpartitioning {
+                            strategy: s.strategy.clone(),
+                            expression: s.expression.clone(),
+                            count: s.partitions.len(),
+                        });
+                    let partitions =
+                        partition_definitions(cursor, &partitioning.strategy, template.as_ref())?;
+                    partitioning.partitions.extend(partitions);
+                } else if cursor.eat_keyword("COLUMN") || !is_table_element(cursor) {
+                    if cursor.is_punct('(') {
+                        cursor.next();
+                        loop {
+                            table.add_element(cursor)?;
+                            if !cursor.eat_punct(',') {
+                                break;
+                            }
+                        }
+                        cursor.expect_punct(')')?;
+                    } else {
+                        let column = parse_column(cursor)?;
+                        let position = column_position(cursor, table)?;
+                        table.add_column(column, position);
+                    }
+                } else {
+                    table.add_element(cursor)?;
+                }
+            } else if cursor.eat_keyword("DROP") {
+                if cursor.eat_keyword("PARTITION") {
+                    let mut names = vec![cursor.identifier()?];
+                    while cursor.eat_punct(',') {
+                        names.push(cursor.identifier()?);
+                    }
+                    if let Some(partitioning) = table.partitioning.as_mut() {
+                        partitioning.partitions.retain(|p| !names.contains(&p.name));
+                    }
+                } else if cursor.eat_keywords(&["PRIMARY", "KEY"]) {
+                    table.indexes.retain(|i| !i.primary);
+                } else if cursor.eat_keywords(&["FOREIGN", "KEY"]) {
+                    let fk = cursor.identifier()?;
+                    table.foreign_keys.retain(|f| f.name != fk);
+                } else if cursor.eat_keyword("INDEX") || cursor.eat_keyword("KEY") {
+                    let index = cursor.identifier()?;
+                    table.indexes.retain(|i| i.name != index);
+                } else if cursor.eat_keyword("CHECK") || cursor.eat_keyword("CONSTRAINT") {
+                    cursor.identifier()?;
+                } else {
+                    cursor.eat_keyword("COLUMN");
+                    let column = cursor.identifier()?;
+                    table.drop_column(&column);
+                }
+            } else if cursor.eat_keyword("MODIFY") {
+                cursor.eat_keyword("COLUMN");
+                let column = parse_column(cursor)?;
+                let old = column.column.metadata.name.clone();
+                let position = column_position(cursor, table)?;
+                table.replace_column(&old, column, position);
+            } else if cursor.eat_keyword("CHANGE") {
+                cursor.eat_keyword("COLUMN");
+                let old = cursor.identifier()?;
+                let column = parse_column(cursor)?;
+                let position = column_position(cursor, table)?;
+                table.replace_column(&old, column, position);
+            } else if cursor.eat_keyword("RENAME") {
+                if cursor.eat_keyword("COLUMN") {
+                    let old = cursor.identifier()?;
+                    cursor.expect_keyword("TO")?;
+                    let new = cursor.identifier()?;
+                    table.rename_column(&old, &new);
+                } else if cursor.eat_keyword("INDEX") || cursor.eat_keyword("KEY") {
+                    let old = cursor.identifier()?;
+                    cursor.expect_keyword("TO")?;
+                    let new = cursor.identifier()?;
+                    if let Some(index) = table.indexes.iter_mut().find(|i| i.name == old) {
+                        index.name = new;
+                    }
+                } else {
+                    if !cursor.eat_keyword("TO") {
+                        cursor.eat_keyword("AS");
+                    }
+                    renamed = Some(table_name(cursor)?);
+                }
+            } else if cursor.eat_keywords(&["CONVERT", "TO"]) {
+                cursor.eat_keywords(&["CHARACTER", "SET"]);
+                cursor.eat_keyword("CHARSET");
+                let character_set = charset_name(&name_or_string(cursor)?);
+                let collation = if cursor.eat_keyword("COLLATE") {
+                    collation_name(&name_or_string(cursor)?)
+                } else {
+                    default_collation(&character_set)
+                };
+                for column in table.columns.iter_mut().filter(|c| c.textual) {
+                    column.metadata.character_set = character_set.clone();
+                    column.metadata.collation = collation.clone();
+                }
+                table.character_set = character_set;
+                table.collation = collation;
+            } else if cursor.eat_keyword("ALTER") {
+                cursor.eat_keyword("COLUMN");
+                let column = cursor.identifier()?;
+                let default = if cursor.eat_keywords(&["SET", "DEFAULT"]) {
+                    Some(parse_default(cursor)?)
+                } else {
+                    cursor.skip_element();
+                    None
+                };
+                if let Some(column) = table.columns.iter_mut().find(|c| c.metadata.name == column) {
+                    column.default = default;
+                }
+            } else if cursor.at_end() {
+                break;
+            } else {
+                table.table_options(cursor)?;
+            }
+            if !cursor.eat_punct(',') {
+                break;
+            }
+        }
+        if !cursor.at_end() {
+            return Err(cursor.error("unexpected ALTER TABLE clause"));
+        }
+        table.end_statement();
+        if let Some(renamed) = renamed {
+            table.name = renamed;
+        }
+
+        Ok(())
+    }
+
+    fn drop(&mut self, cursor: &mut Cursor) -> Result<(), DBError> {
+        cursor.eat_keyword("TEMPORARY");
+        let kind = match cursor.next() {
+            Some(Token::Word(w)) => w.to_uppercase(),
+            _ => return Ok(()),
+        };
+        if kind == "INDEX" {
+            let index = cursor.identifier()?;
+            cursor.expect_keyword("ON")?;
+            let table = table_name(cursor)?;
+            self.table_mut(&table)?.indexes.retain(|i| i.name != index);
+            return Ok(());
+        }
+        let if_exists = cursor.eat_keywords(&["IF", "EXISTS"]);
+        loop {
+            let name = table_name(cursor)?;
+            let found = match kind.as_str() {
+                "TABLE" => remove(&mut self.tables, |t| t.name == name),
+                "VIEW" => remove(&mut self.views, |v| v.name == name),
+                "FUNCTION" => remove(&mut self.functions, |f| f.name == name),
+                "PROCEDURE" => remove(&mut self.procedures, |p| p.name == name),
+                "EVENT" => remove(&mut self.events, |e| e.name == name),
+                "TRIGGER" => self
+                    .tables
+                    .iter_mut()
+                    .any(|t| remove(&mut t.triggers, |tr| tr.name == name)),
+                _ => return Ok(()),
+            };
+            if !found && !if_exists {
+                return Err(DBError::args(format!(
+                    "{} {name} does not exist",
+                    kind.to_lowercase()
+                )));
+            }
+            if !cursor.eat_punct(',') {
+                return Ok(());
+            }
+        }
+    }
+
+    fn database_options(&mut self, cursor: &mut Cursor) -> Result<(), DBError> {
+        loop {
+            cursor.eat_keyword("DEFAULT");
+            if cursor.eat_keywords(&["CHARACTER", "SET"]) || cursor.eat_keyword("CHARSET") {
+                cursor.eat_punct('=');
+                self.character_set = charset_name(&name_or_string(cursor)?);
+                self.collation = default_collation(&self.character_set);
+            } else if cursor.eat_keyword("COLLATE") {
+                cursor.eat_punct('=');
+                self.collation = collation_name(&name_or_string(cursor)?);
+                self.character_set = charset_of(&self.collation).to_string();
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    fn finish(self, database_name: &str) -> store::DatabaseSchemaMetadata {
+        let mut tables: Vec<store::TableMetadata> =
+            self.tables.into_iter().map(Table::into_metadata).collect();
+        tables.sort_by(|a, b| a.name.cmp(&b.name));
+        let mut views = self.views;
+        views.sort_by(|a, b| a.name.cmp(&b.name));
+        let mut functions = self.functions;
+        functions.sort_by(|a, b| a.name.cmp(&b.name));
+        let mut procedures = self.procedures;
+        procedures.sort_by(|a, b| a.name.cmp(&b.name));
+        let mut events = self.events;
+        events.sort_by(|a, b| a.name.cmp(&b.name));
+
+        store::DatabaseSchemaMetadata {
+            name: database_name.to_string(),
+            schemas: vec![store::SchemaMetadata {
+                name: String::new(),
+                tables,
+                external_tables: vec![],
+                views,
+                functions,
+                procedures,
+                materialized_views: vec![],
+                events,
+                owner: String::new(),
+                comment: String::new(),
+            }],
+            character_set: self.character_set,
+            collation: self.collation,
+            extensions: vec![],
+            datashare: false,
+            service_name: String::new(),
+            owner: String::new(),
+        }
+    }
+}
+
+// ColumnDefinition is a parsed column with the keys declared inline.
+struct ColumnDefinition {
+    column: Column,
+    primary: bool,
+    unique: bool,
+}
+
+// ColumnPosition is the FIRST or AFTER clause of ALTER TABLE.
+enum ColumnPosition {
+    Last,
+    First,
+    After(String),
+}
+
+impl Table {
+    fn add_element(&mut self, cursor: &mut Cursor) -> Result<(), DBError> {
+        let constraint = if cursor.eat_keyword("CONSTRAINT") {
+            if is_table_element(cursor) {
+                None
+            } else {
+                Some(cursor.identifier()?)
+            }
+        } else {
+            None
+        };
+
+        if cursor.eat_keywords(&["PRIMARY", "KEY"]) {
+            let index = parse_index(cursor, Some("PRIMARY".to_string()), true, true, "BTREE")?;
+            for expression in &index.expressions {
+                if let Some(column) = self
+                    .columns
+                    .iter_mut()
+                    .find(|c| &c.metadata.name == expression)
+                {
+                    column.metadata.nullable = false;
+                }
+            }
+            self.add_index(index);
+        } else if cursor.eat_keyword("UNIQUE") {
+            if !cursor.eat_keyword("KEY") {
+                cursor.eat_keyword("INDEX");
+            }
+            let index = parse_index(cursor, constraint, false, true, "BTREE")?;
+            self.add_index(index);
+        } else if cursor.is_keyword("FULLTEXT") || cursor.is_keyword("SPATIAL") {
+            let index_type = cursor.identifier()?.to_uppercase();
+            if !cursor.eat_keyword("KEY") {
+                cursor.eat_keyword("INDEX");
+            }
+            let index = parse_index(cursor, None, false, false, &index_type)?;
+            self.add_index(index);
+        } else if cursor.eat_keyword("KEY") || cursor.eat_keyword("INDEX") {
+            let index = parse_index(cursor, None, false, false, "BTREE")?;
+            self.add_index(index);
+        } else if cursor.eat_keywords(&["FOREIGN", "KEY"]) {
+            let index_name = if cursor.is_punct('(') {
+                None
+            } else {
+                Some(cursor.identifier()?)
+            };
+            let columns = cursor.identifier_list()?;
+            cursor.expect_keyword("REFERENCES")?;
+            let referenced_table = table_name(cursor)?;
+            let referenced_columns = cursor.identifier_list()?;
+            let (on_delete, on_update) = referential_actions(cursor)?;
+
+            let name = match constraint.clone() {
+                Some(name) => name,
+                None => self.next_foreign_key_name(),
+            };
+            // The implicit index takes the constraint name, then the index name of the clause.
+            let implicit_index = constraint
+                .or(index_name)
+                .unwrap_or_else(|| columns[0].clone());
+            self.foreign_key_indexes
+                .push((implicit_index, columns.clone()));
+            self.foreign_keys.retain(|f| f.name != name);
+            self.foreign_keys.push(store::ForeignKeyMetadata {
+                name,
+                columns,
+                referenced_schema: String::new(),
+                referenced_table,
+                referenced_columns,
+                on_delete,
+                on_update,
+                match_type: "NONE".to_string(),
+            });
+        } else if cursor.eat_keyword("CHECK") {
+            cursor.parenthesized()?;
+            cursor.eat_keywords(&["NOT", "ENFORCED"]);
+            cursor.eat_keyword("ENFORCED");
+        } else {
+            let column = parse_column(cursor)?;
+            self.add_column(column, ColumnPosition::Last);
+        }
+
+        Ok(())
+    }
+
+    fn add_column(&mut self, definition: ColumnDefinition, position: ColumnPosition) {
+        let name = definition.column.metadata.name.clone();
+        let at = match position {
+            ColumnPosition::Last => self.columns.len(),
+            ColumnPosition::First => 0,
+            ColumnPosition::After(after) => self
+                .columns
+                .iter()
+                .position(|c| c.metadata.name == after)
+                .map_or(self.columns.len(), |p| p + 1),
+        };
+        self.columns.insert(at, definition.column);
+        if definition.primary {
+            self.add_index(index_on(&name, "PRIMARY", true, true));
+        }
+        if definition.unique {
+            self.add_index(index_on(&name, "", false, true));
+        }
+    }
+
+    fn replace_column(
+        &mut self,
+        old: &str,
+        definition: ColumnDefinition,
+        position: ColumnPosition,
+    ) {
+        let Some(at) = self.columns.iter().position(|c| c.metadata.name == old) else {
+            return;
+        };
+        let new = definition.column.metadata.name.clone();
+        self.columns.remove(at);
+        let position = match position {
+            ColumnPosition::Last if at < self.columns.len() => match at {
+                0 => ColumnPosition::First,
+                n => ColumnPosition::After(self.columns[n - 1].metadata.name.clone()),
+            },
+            position => position,
+        };
+        self.add_column(definition, position);
+        if old != new {
+            self.rename_column(old, &new);
+        }
+    }
+
+    fn rename_column(&mut self, old: &str, new: &str) {
+        for column in self.columns.iter_mut().filter(|c| c.metadata.name == old) {
+            column.metadata.name = new.to_string();
+        }
+        for index in &mut self.indexes {
+            for expression in index.expressions.iter_mut().filter(|e| *e == old) {
+                *expression = new.to_string();
+            }
+        }
+        for fk in &mut self.foreign_keys {
+            for column in fk.columns.iter_mut().filter(|c| *c == old) {
+                *column = new.to_string();
+            }
+        }
+    }
+
+    fn drop_column(&mut self, name: &str) {
+        self.columns.retain(|c| c.metadata.name != name);
+        // Dropping a column removes it from the indexes, an index without columns is dropped.
+        for index in &mut self.indexes {
+            while let Some(at) = index.expressions.iter().position(|e| e == name) {
+                index.expressions.remove(at);
+                index.key_length.remove(at);
+            }
+        }
+        self.indexes.retain(|i| !i.expressions.is_empty());
+    }
+
+    // Adds an index, an unnamed index takes the name of its first column.
+    fn add_index(&mut self, mut index: store::IndexMetadata) {
+        if index.primary {
+            self.indexes.retain(|i| !i.primary);
+        } else if index.name.is_empty() {
+            let column = index.expressions.first().cloned().unwrap_or_default();
+            index.name = column.clone();
+            let mut suffix = 2;
+            while self.indexes.iter().any(|i| i.name == index.name) {
+                index.name = format!("{column}_{suffix}");
+                suffix += 1;
+            }
+        }
+        self.indexes.push(index);
+    }
+
+    fn next_foreign_key_name(&self) -> String {
+        let prefix = format!("{}_ibfk_", self.name);
+        let next = self
+            .foreign_keys
+            .iter()
+            .filter_map(|f| f.name.strip_prefix(&prefix)?.parse::<usize>().ok())
+            .max()
+            .unwrap_or(0)
+            + 1;
+        format!("{prefix}{next}")
+    }
+
+    fn table_options(&mut self, cursor: &mut Cursor) -> Result<(), DBError> {
+        loop {
+            cursor.eat_punct(',');
+            if cursor.at_end() {
+                return Ok(());
+            }
+            if cursor.eat_keywords(&["PARTITION", "BY"]) {
+                self.partitioning = Some(partitioning(cursor)?);
+                if !self.create_options.iter().any(|o| o == "partitioned") {
+                    self.create_options.push("partitioned".to_string());
+                }
+                continue;
+            }
+            if cursor.eat_keywords(&["REMOVE", "PARTITIONING"]) {
+                self.partitioning = None;
+                self.create_options.retain(|o| o != "partitioned");
+                continue;
+            }
+            cursor.eat_keyword("DEFAULT");
+            if cursor.eat_keywords(&["CHARACTER", "SET"]) || cursor.eat_keyword("CHARSET") {
+                cursor.eat_punct('=');
+                self.character_set = charset_name(&name_or_string(cursor)?);
+                self.collation = default_collation(&self.character_set);
+            } else if cursor.eat_keyword("COLLATE") {
+                cursor.eat_punct('=');
+                self.collation = collation_name(&name_or_string(cursor)?);
+                self.character_set = charset_of(&self.collation).to_string();
+            } else if cursor.eat_keyword("ENGINE") {
+                cursor.eat_punct('=');
+                self.engine = engine_name(&name_or_string(cursor)?);
+            } else if cursor.eat_keyword("COMMENT") {
+                cursor.eat_punct('=');
+                self.comment = cursor.string()?;
+            } else if cursor.eat_keyword("AUTO_INCREMENT") {
+                // The counter is not part of the synced metadata.
+                cursor.eat_punct('=');
+                cursor.next();
+            } else if let Some(Token::Word(option)) = cursor.peek().cloned() {
+                if cursor.peek_at(1) != Some(&Token::Punct('='))
+                    && !matches!(
+                        cursor.peek_at(1),
+                        Some(Token::Word(_)) | Some(Token::Number(_)) | Some(Token::Str(_))
+                    )
+                {
+                    return Err(cursor.error("unexpected table option"));
+                }
+                cursor.next();
+                cursor.eat_punct('=');
+                let option = option.to_lowercase();
+                let value = match cursor.next() {
+                    Some(Token::Word(w)) if option == "row_format" => w.to_uppercase(),
+                    Some(Token::Word(w)) | Some(Token::Number(w)) => w,
+                    Some(Token::Str(s)) => format!("\"{s}\""),
+                    _ => return Err(cursor.error("expected a table option value")),
+                };
+                if option != "tablespace" {
+                    self.create_options.push(format!("{option}={value}"));
+                }
+            } else {
+                return Err(cursor.error("unexpected table option"));
+            }
+        }
+    }
+
+    // Ends a statement: adds the implicit foreign key indexes and resolves the character sets.
+    fn end_statement(&mut self) {
+        for (name, columns) in std::mem::take(&mut self.foreign_key_indexes) {
+            let covered = self.indexes.iter().any(|i| {
+                i.expressions.len() >= columns.len()
+                    && i.expressions.iter().zip(&columns).all(|(e, c)| e == c)
+            });
+            if !covered {
+                let mut index = index_on(&columns[0], &name, false, false);
+                index.expressions = columns.clone();
+                index.key_length = vec![-1; columns.len()];
+                self.add_index(index);
+            }
+        }
+        for column in self.columns.iter_mut().filter(|c| c.textual) {
+            let metadata = &mut column.metadata;
+            match (
+                metadata.character_set.is_empty(),
+                metadata.collation.is_empty(),
+            ) {
+                (true, true) => {
+                    metadata.character_set = self.character_set.clone();
+                    metadata.collation = self.collation.clone();
+                }
+                (false, true) => metadata.collation = default_collation(&metadata.character_set),
+                (true, false) => {
+                    metadata.character_set = charset_of(&metadata.collation).to_string()
+                }
+                (false, false) => {}
+            }
+        }
+    }
+
+    fn into_metadata(self) -> store::TableMetadata {
+        let columns = self
+            .columns
+            .into_iter()
+            .enumerate()
+            .map(|(position, column)| {
+                let mut metadata = column.metadata.clone();
+                metadata.position = position as i32 + 1;
+                metadata.default = column_default(&column);
+                metadata
+            })
+            .collect();
+        let mut indexes = self.indexes;
+        indexes.sort_by(|a, b| a.name.cmp(&b.name));
+        let mut foreign_keys = self.foreign_keys;
+        foreign_keys.sort_by(|a, b| a.name.cmp(&b.name));
+        // The sync orders the triggers by timing and event, then in the order they fire.
+        let mut triggers = self.triggers;
+        triggers.sort_by(|a, b| (&a.timing, &a.events).cmp(&(&b.timing, &b.events)));
+
+        store::TableMetadata {
+            name: self.name,
+            columns,
+            indexes,
+            engine: self.engine,
+            collation: Some(self.collation),
+            row_count: 0,
+            data_size: 0,
+            index_size: 0,
+            data_free: 0,
+            create_options: self.create_options.join(" "),
+            comment: self.comment,
+            foreign_keys,
+            owner: String::new(),
+            triggers,
+            partitioning: self.partitioning,
+        }
+    }
+}
+
+fn remove<T>(items: &mut Vec<T>, matches: impl Fn(&T) -> bool) -> bool {
+    let count = items.len();
+    items.retain(|item| !matches(item));
+    items.len() != count
+}
+
+// Skips the ALGORITHM, DEFINER and SQL SECURITY clauses between CREATE and the object type.
+fn skip_create_options(cursor: &mut Cursor) {
+    loop {
+        if cursor.eat_keyword("ALGORITHM") {
+            cursor.eat_punct('=');
+            cursor.next();
+        } else if cursor.eat_keyword("DEFINER") {
+            cursor.eat_punct('=');
+            cursor.next();
+            if cursor.is_punct('(') {
+                let _ = cursor.parenthesized();
+            }
+            if cursor.eat_punct('@') {
+                cursor.next();
+            }
+        } else if cursor.eat_keywords(&["SQL", "SECURITY"]) {
+            cursor.next();
+        } else {
+            return;
+        }
+    }
+}
+
+// Returns the name of a created routine, or None when IF NOT EXISTS skips an existing one.
+fn routine_name(
+    cursor: &mut Cursor,
+    if_not_exists: bool,
+    existing: &[String],
+) -> Result<Option<String>, DBError> {
+    let name = table_name(cursor)?;
+    if existing.contains(&name) {
+        if if_not_exists {
+            return Ok(None);
+        }
+        return Err(DBError::args(format!("routine {name} already exists")));
+    }
+    Ok(Some(name))
+}
+
+// Returns the ON SCHEDULE clause of an event the way the sync formats it. Timestamps are
+// kept as written, while the server stores them evaluated.
+fn event_schedule(cursor: &mut Cursor) -> Result<String, DBError> {
+    if cursor.eat_keyword("AT") {
+        return Ok(format!("AT {}", event_time(cursor)));
+    }
+    cursor.expect_keyword("EVERY")?;
+    let mut schedule = match cursor.next() {
+        Some(Token::Number(value)) => value,
+        Some(Token::Str(value)) if value.chars().all(|c| c.is_ascii_digit()) => value,
+        Some(Token::Str(value)) => quote_string(&value),
+        _ => return Err(cursor.error("expected an interval value")),
+    };
+    schedule = format!("EVERY {schedule} {}", cursor.identifier()?.to_uppercase());
+    if cursor.eat_keyword("STARTS") {
+        schedule += &format!(" STARTS {}", event_time(cursor));
+    }
+    if cursor.eat_keyword("ENDS") {
+        schedule += &format!(" ENDS {}", event_time(cursor));
+    }
+    Ok(schedule)
+}
+
+// Consumes a timestamp of an event schedule, a string literal or an expression.
+fn event_time(cursor: &mut Cursor) -> String {
+    let start = cursor.offset();
+    let position = cursor.position();
+    while !cursor.at_end()
+        && !["ENDS", "ON", "ENABLE", "DISABLE", "COMMENT", "DO"]
+            .iter()
+            .any(|k| cursor.is_keyword(k))
+    {
+        if cursor.is_punct('(') {
+            let _ = cursor.parenthesized();
+        } else {
+            cursor.next();
+        }
+    }
+    match cursor.tokens_from(position).as_slice() {
+        [Token::Str(time)] => quote_string(time),
+        _ => cursor.text_from(start).to_string(),
+    }
+}
+
+// Returns the parameters of a routine typed like INFORMATION_SCHEMA.PARAMETERS, the parameters
+// of functions have no mode and are all IN.
+fn routine_parameters(
+    parameters: &str,
+    procedure: bool,
+) -> Result<Vec<store::RoutineParameter>, DBError> {
+    let mut cursor = Cursor::new(parameters, Dialect::MySQL)?;
+    let mut list = vec![];
+    while !cursor.at_end() {
+        let mut mode = store::ParameterMode::In;
+        if procedure {
+            if cursor.eat_keyword("INOUT") {
+                mode = store::ParameterMode::InOut;
+            } else if cursor.eat_keyword("OUT") {
+                mode = store::ParameterMode::Out;
+            } else {
+                cursor.eat_keyword("IN");
+            }
+        }
+        let name = cursor.identifier()?;
+        let r#type = routine_type(&mut cursor)?;
+        list.push(store::RoutineParameter { name, mode, r#type });
+        if !cursor.eat_punct(',') {
+            break;
+        }
+    }
+    Ok(list)
+}
+
+// Consumes the type of a routine parameter or result, the character set and collation are
+// not part of DTD_IDENTIFIER.
+fn routine_type(cursor: &mut Cursor) -> Result<String, DBError> {
+    let type_name = TypeName::from_tokens(&parse_type(cursor, ROUTINE_TYPE_END)?);
+    loop {
+        if cursor.eat_keywords(&["CHARACTER", "SET"])
+            || cursor.eat_keyword("CHARSET")
+            || cursor.eat_keyword("COLLATE")
+        {
+            name_or_string(cursor)?;
+        } else {
+            return Ok(column_type(&type_name).0);
+        }
+    }
+}
+
+// Consumes a table name, the database qualifier is dropped.
+fn table_name(cursor: &mut Cursor) -> Result<String, DBError> {
+    let mut parts = cursor.object_name()?;
+    Ok(parts.pop().unwrap_or_default())
+}
+
+// Subpartitioning is the SUBPARTITION BY clause of a partitioned table, the count is the number
+// of subpartitions of a partition not listing its own.
+struct Subpartitioning {
+    strategy: store::PartitionStrategy,
+    expression: String,
+    count: usize,
+}
+
+// Parses the partitioning following PARTITION BY. Partitions and subpartitions named by the
+// server are named the way the sync reports them, p0, p1 and so on for partitions and the
+// partition name followed by sp0, sp1 and so on for subpartitions.
+fn partitioning(cursor: &mut Cursor) -> Result<store::PartitioningMetadata, DBError> {
+    let (strategy, expression) = partition_method(cursor)?;
+    let count = if cursor.eat_keyword("PARTITIONS") {
+        partition_count(cursor)?
+    } else {
+        1
+    };
+    let subpartitioning = if cursor.eat_keywords(&["SUBPARTITION", "BY"]) {
+        let (strategy, expression) = partition_method(cursor)?;
+        let count = if cursor.eat_keyword("SUBPARTITIONS") {
+            partition_count(cursor)?
+        } else {
+            1
+        };
+        Some(Subpartitioning {
+            strategy,
+            expression,
+            count,
+        })
+    } else {
+        None
+    };
+    let partitions = if cursor.is_punct('(') {
+        partition_definitions(cursor, &strategy, subpartitioning.as_ref())?
+    } else {
+        (0..count)
+            .map(|i| {
+                let name = format!("p{i}");
+                store::PartitionMetadata {
+                    subpartitioning: subpartitioning
+                        .as_ref()
+                        .map(|s| default_subpartitioning(&name, s)),
+                    name,
+                    value: String::new(),
+                    row_count: 0,
+                }
+            })
+            .collect()
+    };
+
+    Ok(store::PartitioningMetadata {
+        strategy,
+        expression,
+        partitions,
+    })
+}
+
+// Parses a partition strategy and its parenthesized key. A key of plain columns is written the
+// way INFORMATION_SCHEMA.PARTITIONS reports it, an expression key is kept as written.
+fn partition_method(cursor: &mut Cursor) -> Result<(store::PartitionStrategy, String), DBError> {
+    let linear = cursor.eat_keyword("LINEAR");
+    let strategy = if cursor.eat_keyword("HASH") {
+        if linear {
+            store::PartitionStrategy::LinearHash
+        } else {
+            store::PartitionStrategy::Hash
+        }
+    } else if !linear && cursor.eat_keyword("RANGE") {
+        if cursor.eat_keyword("COLUMNS") {
+            store::PartitionStrategy::RangeColumns
+        } else {
+            store::PartitionStrategy::Range
+        }
+    } else if !linear && cursor.eat_keyword("LIST") {
+        if cursor.eat_keyword("COLUMNS") {
+            store::PartitionStrategy::ListColumns
+        } else {
+            store::PartitionStrategy::List
+        }
+    } else {
+        cursor.expect_keyword("KEY")?;
+        if cursor.eat_keyword("ALGORITHM") {
+            cursor.expect_punct('=')?;
+            cursor.next();
+        }
+        if linear {
+            store::PartitionStrategy::LinearKey
+        } else {
+            store::PartitionStrategy::Key
+        }
+    };
+    let key = cursor.parenthesized()?;
+    let mut key_cursor = Cursor::new(key, Dialect::MySQL)?;
+    let mut columns = vec![];
+    while let Ok(column) = key_cursor.identifier() {
+        columns.push(quote_identifier(&column));
+        if !key_cursor.eat_punct(',') {
+            break;
+        }
+    }
+    let expression = if key_cursor.at_end() {
+        columns.join(",")
+    } else {
+        key.to_string()
+    };
+    Ok((strategy, expression))
+}
+
+fn partition_count(cursor: &mut Cursor) -> Result<usize, DBError> {
+    match cursor.next() {
+        Some(Token::Number(n)) => n
+            .parse()
+            .map_err(|_| cursor.error("expected a number of partitions")),
+        _ => Err(cursor.error("expected a number of partitions")),
+    }
+}
+
+// Parses a parenthesized list of partition definitions. The values of a partition are kept as
+// written and separated by commas like PARTITION_DESCRIPTION, and partition options are skipped.
+fn partition_definitions(
+    cursor: &mut Cursor,
+    strategy: &store::PartitionStrategy,
+    subpartitioning: Option<&Subpartitioning>,
+) -> Result<Vec<store::PartitionMetadata>, DBError> {
+    let mut partitions = vec![];
+    cursor.expect_punct('(')?;
+    loop {
+        cursor.expect_keyword("PARTITION")?;
+        let name = cursor.identifier()?;
+        let value = if cursor.eat_keywords(&["VALUES", "LESS", "THAN"]) {
+            if cursor.eat_keyword("MAXVALUE") {
+                "MAXVALUE".to_string()
+            } else {
+                partition_values(cursor)?
+            }
+        } else if cursor.eat_keywords(&["VALUES", "IN"]) {
+            partition_values(cursor)?
+        } else {
+            String::new()
+        };
+        if value.is_empty()
+            && !matches!(
+                strategy,
+                store::PartitionStrategy::Hash
+                    | store::PartitionStrategy::LinearHash
+                    | store::PartitionStrategy::Key
+                    | store::PartitionStrategy::LinearKey
+            )
+        {
+            return Err(cursor.error("expected the VALUES of a partition"));
+        }
+        skip_partition_options(cursor);
+        let subpartitions = match subpartitioning {
+            Some(s) if cursor.eat_punct('(') => {
+                let mut partitions = vec![];
+                loop {
+                    cursor.expect_keyword("SUBPARTITION")?;
+                    partitions.push(store::PartitionMetadata {
+                        name: cursor.identifier()?,
+                        value: String::new(),
+                        subpartitioning: None,
+                        row_count: 0,
+                    });
+                    skip_partition_options(cursor);
+                    if !cursor.eat_punct(',') {
+                        break;
+                    }
+                }
+                cursor.expect_punct(')')?;
+                Some(store::PartitioningMetadata {
+                    strategy: s.strategy.clone(),
+                    expression: s.expression.clone(),
+                    partitions,
+                })
+            }
+            Some(s) => Some(default_subpartitioning(&name, s)),
+            None => None,
+        };
+        partitions.push(store::PartitionMetadata {
+            name,
+            value,
+            subpartitioning: subpartitions,
+            row_count: 0,
+        });
+        if !cursor.eat_punct(',') {
+            break;
+        }
+    }
+    cursor.expect_punct(')')?;
+    Ok(partitions)
+}
+
+fn default_subpartitioning(
+    partition: &str,
+    subpartitioning: &Subpartitioning,
+) -> store::PartitioningMetadata {
+    store::PartitioningMetadata {
+        strategy: subpartitioning.strategy.clone(),
+        expression: subpartitioning.expression.clone(),
+        partitions: (0..subpartitioning.count)
+            .map(|i| store::PartitionMetadata {
+                name: format!("{partition}sp{i}"),
+                value: String::new(),
+                subpartitioning: None,
+                row_count: 0,
+            })
+            .collect(),
+    }
+}
+
+fn partition_values(cursor: &mut Cursor) -> Result<String, DBError> {
+    cursor.expect_punct('(')?;
+    let mut values = vec![];
+    loop {
+        let start = cursor.offset();
+        cursor.skip_element();
+        values.push(cursor.text_from(start));
+        if !cursor.eat_punct(',') {
+            break;
+        }
+    }
+    cursor.expect_punct(')')?;
+    Ok(values.join(","))
+}
+
+// Skips the options of a partition such as ENGINE and COMMENT.
+fn skip_partition_options(cursor: &mut Cursor) {
+    while !cursor.at_end()
+        && !cursor.is_punct(',')
+        && !cursor.is_punct('(')
+        && !cursor.is_punct(')')
+    {
+        cursor.next();
+    }
+}
+
+fn name_or_string(cursor: &mut Cursor) -> Result<String, DBError> {
+    match cursor.peek() {
+        Some(Token::Str(_)) => cursor.string(),
+        _ => cursor.identifier(),
+    }
+}
+
+fn is_table_element(cursor: &Cursor) -> bool {
+    [
+        "PRIMARY",
+        "UNIQUE",
+        "FULLTEXT",
+        "SPATIAL",
+        "KEY",
+        "INDEX",
+        "FOREIGN",
+        "CHECK",
+        "CONSTRAINT",
+    ]
+    .iter()
+    .any(|k| cursor.is_keyword(k))
+}
+
+fn column_position(cursor: &mut Cursor, table: &Table) -> Result<ColumnPosition, DBError> {
+    if cursor.eat_keyword("FIRST") {
+        Ok(ColumnPosition::First)
+    } else if cursor.eat_keyword("AFTER") {
+        let after = cursor.identifier()?;
+        if !table.columns.iter().any(|c| c.metadata.name == after) {
+            return Err(DBError::args(format!("column {after} does not exist")));
+        }
+        Ok(ColumnPosition::After(after))
+    } else {
+        Ok(ColumnPosition::Last)
+    }
+}
+
+fn parse_column(cursor: &mut Cursor) -> Result<ColumnDefinition, DBError> {
+    let name = cursor.identifier()?;
+    let type_name = TypeName::from_tokens(&parse_type(cursor, COLUMN_OPTIONS)?);
+    let (r#type, textual) = column_type(&type_name);
+    let mut definition = ColumnDefinition {
+        column: Column {
+            metadata: store::ColumnMetadata {
+                name,
+                position: 0,
+                default: String::new(),
+                on_update: None,
+                nullable: true,
+                r#type,
+                character_set: String::new(),
+                collation: String::new(),
+                comment: String::new(),
+                identity_generation: store::IdentityGeneration::UNSPECIFIED,
+            },
+            default: None,
+            auto_increment: false,
+            textual,
+        },
+        primary: false,
+        unique: false,
+    };
+
+    let column = &mut definition.column;
+    loop {
+        if cursor.eat_keywords(&["NOT", "NULL"]) {
+            column.metadata.nullable = false;
+        } else if cursor.eat_keyword("NULL") {
+            column.metadata.nullable = true;
+        } else if cursor.eat_keyword("DEFAULT") {
+            column.default = Some(parse_default(cursor)?);
+        } else if cursor.eat_keyword("AUTO_INCREMENT") {
+            column.auto_increment = true;
+        } else if cursor.eat_keywords(&["PRIMARY", "KEY"]) || cursor.eat_keyword("KEY") {
+            definition.primary = true;
+            column.metadata.nullable = false;
+        } else if cursor.eat_keyword("UNIQUE") {
+            cursor.eat_keyword("KEY");
+            definition.unique = true;
+        } else if cursor.eat_keyword("COMMENT") {
+            column.metadata.comment = cursor.string()?;
+        } else if cursor.eat_keywords(&["CHARACTER", "SET"]) || cursor.eat_keyword("CHARSET") {
+            column.metadata.character_set = charset_name(&name_or_string(cursor)?);
+        } else if cursor.eat_keyword("COLLATE") {
+            column.metadata.collation = collation_name(&name_or_string(cursor)?);
+        } else if cursor.eat_keywords(&["ON", "UPDATE"]) {
+            if let DefaultValue::CurrentTimestamp(precision) = parse_default(cursor)? {
+                column.metadata.on_update = Some(current_timestamp(&precision));
+            }
+        } else if cursor.eat_keyword("GENERATED") || cursor.eat_keyword("AS") {
+            // The expression of a generated column is not part of the synced metadata.
+            cursor.eat_keyword("ALWAYS");
+            cursor.eat_keyword("AS");
+            cursor.parenthesized()?;
+            if !cursor.eat_keyword("VIRTUAL") {
+                cursor.eat_keyword("STORED");
+            }
+        } else if cursor.eat_keyword("VISIBLE") || cursor.eat_keyword("INVISIBLE") {
+        } else if cursor.eat_keyword("COLUMN_FORMAT")
+            || cursor.eat_keyword("STORAGE")
+            || cursor.eat_keyword("SRID")
+        {
+            cursor.next();
+        } else if cursor.eat_keyword("REFERENCES") {
+            // MySQL parses and ignores inline references.
+            cursor.skip_element();
+        } else if cursor.eat_keyword("CONSTRAINT") {
+            if !cursor.is_keyword("CHECK") {
+                cursor.identifier()?;
+            }
+        } else if cursor.eat_keyword("CHECK") {
+            cursor.parenthesized()?;
+            cursor.eat_keywords(&["NOT", "ENFORCED"]);
+            cursor.eat_keyword("ENFORCED");
+        } else {
+            return Ok(definition);
+        }
+    }
+}
+
+fn parse_index(
+    cursor: &mut Cursor,
+    name: Option<String>,
+    primary: bool,
+    unique: bool,
+    index_type: &str,
+) -> Result<store::IndexMetadata, DBError> {
+    let mut index = index_on("", &name.unwrap_or_default(), primary, unique);
+    index.r#type = index_type.to_string();
+    index.expressions.clear();
+    index.key_length.clear();
+    if !cursor.is_punct('(') && !cursor.is_keyword("USING") {
+        let name = cursor.identifier()?;
+        if !primary {
+            index.name = name;
+        }
+    }
+    index_options(cursor, &mut index)?;
+
+    cursor.expect_punct('(')?;
+    loop {
+        if cursor.is_punct('(') {
+            let expression = cursor.parenthesized()?;
+            index.expressions.push(format!("({expression})"));
+            index.key_length.push(-1);
+        } else {
+            index.expressions.push(cursor.identifier()?);
+            let length = if cursor.is_punct('(') {
+                let length = cursor.parenthesized()?;
+                length
+                    .parse::<i64>()
+                    .map_err(|_| cursor.error("expected a key length"))?
+            } else {
+                -1
+            };
+            index.key_length.push(length);
+        }
+        if !cursor.eat_keyword("ASC") {
+            cursor.eat_keyword("DESC");
+        }
+        if !cursor.eat_punct(',') {
+            break;
+        }
+    }
+    cursor.expect_punct(')')?;
+    index_options(cursor, &mut index)?;
+
+    Ok(index)
+}
+
+fn index_options(cursor: &mut Cursor, index: &mut store::IndexMetadata) -> Result<(), DBError> {
+    loop {
+        if cursor.eat_keyword("USING") {
+            index.r#type = cursor.identifier()?.to_uppercase();
+        } else if cursor.eat_keyword("COMMENT") {
+            index.comment = cursor.string()?;
+        } else if cursor.eat_keyword("VISIBLE") {
+            index.visible = true;
+        } else if cursor.eat_keyword("INVISIBLE") {
+            index.visible = false;
+        } else if cursor.eat_keywords(&["WITH", "PARSER"]) {
+            cursor.identifier()?;
+        } else if cursor.eat_keyword("KEY_BLOCK_SIZE")
+            || cursor.eat_keyword("ENGINE_ATTRIBUTE")
+            || cursor.eat_keyword("SECONDARY_ENGINE_ATTRIBUTE")
+        {
+            cursor.eat_punct('=');
+            cursor.next();
+        } else {
+            return Ok(());
+        }
+    }
+}
+
+fn index_on(column: &str, name: &str, primary: bool, unique: bool) -> store::IndexMetadata {
+    store::IndexMetadata {
+        name: name.to_string(),
+        expressions: vec![column.to_string()],
+        key_length: vec![-1],
+        r#type: "BTREE".to_string(),
+        unique,
+        primary,
+        visible: true,
+        comment: String::new(),
+        definition: String::new(),
+        include_columns: vec![],
+        predicate: String::new(),
+    }
+}
+
+// Returns the ON DELETE and ON UPDATE actions of a foreign key, NO ACTION when omitted.
+fn referential_actions(cursor: &mut Cursor) -> Result<(String, String), DBError> {
+    let mut on_delete = "NO ACTION".to_string();
+    let mut on_update = "NO ACTION".to_string();
+    loop {
+        if cursor.eat_keyword("MATCH") {
+            cursor.identifier()?;
+        } else if cursor.eat_keywords(&["ON", "DELETE"]) {
+            on_delete = referential_action(cursor)?;
+        } else if cursor.eat_keywords(&["ON", "UPDATE"]) {
+            on_update = referential_action(cursor)?;
+        } else {
+            return Ok((on_delete, on_update));
+        }
+    }
+}
+
+fn referential_action(cursor: &mut Cursor) -> Result<String, DBError> {
+    for action in [
+        &["RESTRICT"][..],
+        &["CASCADE"],
+        &["SET", "NULL"],
+        &["SET", "DEFAULT"],
+        &["NO", "ACTION"],
+    ] {
+        if cursor.eat_keywords(action) {
+            return Ok(action.join(" "));
+        }
+    }
+    Err(cursor.error("expected a referential action"))
+}
+
+// Returns the COLUMN_TYPE MySQL reports for a declared type and whether the type stores text.
+fn column_type(type_name: &TypeName) -> (String, bool) {
+    let modifier = |w: &&String| ["unsigned", "zerofill", "signed"].contains(&w.as_str());
+    let words: Vec<&str> = type_name
+        .words
+        .iter()
+        .filter(|w| !modifier(w))
+        .map(String::as_str)
+        .collect();
+    let zerofill = type_name
+        .words
+        .iter()
+        .chain(&type_name.suffix)
+        .any(|w| w == "zerofill");
+    let unsigned = zerofill
+        || type_name
+            .words
+            .iter()
+            .chain(&type_name.suffix)
+            .any(|w| w == "unsigned");
+
+    let mut args = type_name.args.clone();
+    let base = match words.join(" ").as_str() {
+        "integer" | "int4" => "int",
+        "int1" => "tinyint",
+        "int2" => "smallint",
+        "int3" | "middleint" => "mediumint",
+        "int8" => "bigint",
+        "bool" | "boolean" => {
+            args = vec!["1".to_string()];
+            "tinyint"
+        }
+        "dec" | "numeric" | "fixed" => "decimal",
+        "double precision" | "real" | "float8" => "double",
+        "float4" => "float",
+        "character" | "nchar" | "national char" | "national character" => "char",
+        "character varying"
+        | "char varying"
+        | "nvarchar"
+        | "national varchar"
+        | "national char varying"
+        | "national character varying"
+        | "varcharacter" => "varchar",
+        "long varbinary" => "mediumblob",
+        "long" | "long varchar" => "mediumtext",
+        other => other,
+    }
+    .to_string();
+
+    match base.as_str() {
+        // The display width is deprecated and only kept for tinyint(1) and zerofill.
+        b if INTEGER_TYPES.contains(&b) && !(zerofill || b == "tinyint" && args == ["1"]) => {
+            args.clear();
+        }
+        "decimal" => match args.len() {
+            0 => args = vec!["10".to_string(), "0".to_string()],
+            1 => args.push("0".to_string()),
+            _ => {}
+        },
+        "char" | "binary" | "bit" if args.is_empty() => args = vec!["1".to_string()],
+        "year" => args.clear(),
+        _ => {}
+    }
+
+    let mut r#type = base.clone();
+    if !args.is_empty() {
+        r#type = format!("{type}({})", args.join(","));
+    }
+    if unsigned {
+        r#type.push_str(" unsigned");
+    }
+    if zerofill {
+        r#type.push_str(" zerofill");
+    }
+
+    (r#type, TEXT_TYPES.contains(&base.as_str()))
+}
+
+// Returns the COLUMN_DEFAULT of a column in the form of the MySQL driver sync.
+fn column_default(column: &Column) -> String {
+    let metadata = &column.metadata;
+    match &column.default {
+        None | Some(DefaultValue::Null) => {
+            if column.auto_increment {
+                AUTO_INCREMENT_SYMBOL.to_string()
+            } else if metadata.nullable {
+                "NULL".to_string()
+            } else {
+                String::new()
+            }
+        }
+        Some(DefaultValue::Bool(b)) => if *b { "1" } else { "0" }.to_string(),
+        Some(DefaultValue::Str(value)) | Some(DefaultValue::Number(value)) => {
+            match decimal_scale(&metadata.r#type) {
+                Some(scale) if value.parse::<f64>().is_ok() => format_decimal(value, scale),
+                _ => value.clone(),
+            }
+        }
+        Some(DefaultValue::CurrentTimestamp(precision)) => current_timestamp(precision),
+        Some(DefaultValue::Expr(expression)) => expression.clone(),
+    }
+}
+
+fn current_timestamp(precision: &Option<String>) -> String {
+    match precision {
+        Some(precision) => format!("CURRENT_TIMESTAMP({precision})"),
+        None => "CURRENT_TIMESTAMP".to_string(),
+    }
+}
+
+fn decimal_scale(column_type: &str) -> Option<usize> {
+    let args = column_type.strip_prefix("decimal(")?;
+    let (_, scale) = args.split_once(',')?;
+    scale.split(')').next()?.parse().ok()
+}
+
+// Formats a decimal literal with the scale of the column, the way MySQL stores the default.
+fn format_decimal(value: &str, scale: usize) -> String {
+    let value = value.trim_start_matches('+');
+    let (integer, fraction) = value.split_once('.').unwrap_or((value, ""));
+    let integer = match integer {
+        "" => "0",
+        "-" => "-0",
+        integer => integer,
+    };
+    if scale == 0 {
+        return integer.to_string();
+    }
+    let fraction: String = fraction
+        .chars()
+        .chain(std::iter::repeat('0'))
+        .take(scale)
+        .collect();
+    format!("{integer}.{fraction}")
+}
+
+fn charset_name(name: &str) -> String {
+    match name.to_lowercase().as_str() {
+        "utf8" => "utf8mb3".to_string(),
+        name => name.to_string(),
+    }
+}
+
+fn collation_name(name: &str) -> String {
+    let name = name.to_lowercase();
+    match name.strip_prefix("utf8_") {
+        Some(rest) => format!("utf8mb3_{rest}"),
+        None => name,
+    }
+}
+
+fn default_collation(character_set: &str) -> String {
+    match character_set {
+        "utf8mb4" => DEFAULT_COLLATION.to_string(),
+        "latin1" => "latin1_swedish_ci".to_string(),
+        "binary" => "binary".to_string(),
+        character_set => format!("{character_set}_general_ci"),
+    }
+}
+
+fn engine_name(name: &str) -> String {
+    match name.to_lowercase().as_str() {
+        "innodb" => "InnoDB".to_string(),
+        "myisam" => "MyISAM".to_string(),
+        "memory" | "heap" => "MEMORY".to_string(),
+        "csv" => "CSV".to_string(),
+        "archive" => "ARCHIVE".to_string(),
+        "blackhole" => "BLACKHOLE".to_string(),
+        _ => name.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::parse_database;
+    use crate::db::store::{ParameterMode, PartitionStrategy, SecurityType, Volatility};
+
+    #[test]
+    fn test_parse_fixture() {
+        let sql = std::fs::read_to_string("tests/fixtures/mysql_schema.sql").unwrap()
+            + &std::fs::read_to_string("tests/fixtures/mysql_routines.sql").unwrap();
+        let database = parse_database("test_db", &sql).unwrap();
+        assert_eq!(database.name, "test_db");
+        assert_eq!(database.schemas.len(), 1);
+        let schema = &database.schemas[0];
+        assert_eq!(schema.name, "");
+        let names: Vec<&str> = schema.tables.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "customers",
+                "order_archive",
+                "order_items",
+                "orders",
+                "products"
+            ]
+        );
+
+        let customers = &schema.tables[0];
+        assert_eq!(customers.columns.len(), 11);
+        assert_eq!(customers.engine, "InnoDB");
+        assert_eq!(customers.collation.as_deref(), Some("utf8mb4_unicode_ci"));
+        assert_eq!(customers.comment, "Customer information");
+        let column = |name: &str| customers.columns.iter().find(|c| c.name == name).unwrap();
+        assert_eq!(column("customer_id").default, "AUTO_INCREMENT");
+        assert!(!column("customer_id").nullable);
+        assert_eq!(column("email").r#type, "varchar(255)");
+        assert_eq!(column("email").character_set, "utf8mb4");
+        assert_eq!(column("phone").default, "NULL");
+        assert_eq!(column("is_active").r#type, "tinyint(1)");
+        assert_eq!(column("is_active").default, "1");
+        assert_eq!(
+            column("updated_at").on_update.as_deref(),
+            Some("CURRENT_TIMESTAMP")
+        );
+        let index_names: Vec<&str> = customers.indexes.iter().map(|i| i.name.as_str()).collect();
+        assert_eq!(
+            index_names,
+            vec!["PRIMARY", "email", "idx_city", "idx_email", "idx_name"]
+        );
+        assert!(customers.indexes[1].unique);
+
+        let order_archive = &schema.tables[1];
+        assert_eq!(order_archive.create_options, "partitioned");
+        let partitioning = order_archive.partitioning.as_ref().unwrap();
+        assert_eq!(partitioning.strategy, PartitionStrategy::Range);
+        assert_eq!(partitioning.expression, "YEAR(order_date)");
+        let partitions: Vec<(&str, &str)> = partitioning
+            .partitions
+            .iter()
+            .map(|p| (p.name.as_str(), p.value.as_str()))
+            .collect();
+        assert_eq!(partitions, vec![("p2023", "2024"), ("pmax", "MAXVALUE")]);
+        let subpartitioning = partitioning.partitions[1].subpartitioning.as_ref().unwrap();
+        assert_eq!(subpartitioning.strategy, PartitionStrategy::Hash);
+        assert_eq!(subpartitioning.expression, "`order_id`");
+        let subpartitions: Vec<&str> = subpartitioning
+            .partitions
+            .iter()
+            .map(|p| p.name.as_str())
+            .collect();
+        assert_eq!(subpartitions, vec!["pmaxsp0", "pmaxsp1"]);
+
+        let order_items = &schema.tables[2];
+        let unit_price = order_items
+            .columns
+            .iter()
+            .find(|c| c.name == "unit_price")
+            .unwrap();
+        assert_eq!(unit_price.r#type, "decimal(10,2)");
+
+        let orders = &schema.tables[3];
+        let total_amount = orders
+            .columns
+            .iter()
+            .find(|c| c.name == "total_amount")
+            .unwrap();
+        assert_eq!(total_amount.default, "0.00");
+        assert_eq!(orders.foreign_keys.len(), 1);
+        let fk = &orders.foreign_keys[0];
+        assert_eq!(fk.name, "fk_orders_customer");
+        assert_eq!(fk.referenced_table, "customers");
+        assert_eq!(fk.on_delete, "CASCADE");
+
+        assert_eq!(schema.views.len(), 1);
+        assert_eq!(schema.views[0].comment, "VIEW");
+        assert_eq!(schema.procedures.len(), 1);
+        assert_eq!(schema.procedures[0].name, "get_customer_orders");
+        assert_eq!(schema.functions.len(), 1);
+        assert_eq!(schema.functions[0].name, "calculate_order_total");
+        let parameter = &schema.procedures[0].parameters[0];
+        assert_eq!(parameter.name, "customer_email");
+        assert_eq!(parameter.mode, ParameterMode::In);
+        assert_eq!(parameter.r#type, "varchar(255)");
+        let function = &schema.functions[0];
+        assert_eq!(function.parameters[0].r#type, "int");
+        assert_eq!(function.return_type, "decimal(10,2)");
+        assert_eq!(function.language, "SQL");
+        assert_eq!(function.volatility, Volatility::Immutable);
+        assert_eq!(function.security, SecurityType::Definer);
+
+        let products = schema.tables.iter().find(|t| t.name == "products").unwrap();
+        let trigger = &products.triggers[0];
+        assert_eq!(trigger.name, "trg_products_trim_name");
+        assert_eq!(trigger.timing, "BEFORE");
+        assert_eq!(trigger.events, vec!["INSERT"]);
+        assert!(trigger.for_each_row);
+
+        let event = &schema.events[0];
+        assert_eq!(event.name, "purge_cancelled_orders");
+        assert_eq!(event.schedule, "EVERY 1 DAY STARTS '2024-01-01 03:00:00'");
+        assert_eq!(event.status, "DISABLED");
+        assert_eq!(event.on_completion, "PRESERVE");
+        assert_eq!(event.comment, "Purge cancelled orders");
+        assert_eq!(event.body, "DELETE FROM orders WHERE status = 'cancelled'");
+    }
+
+    #[test]
+    fn test_parse_alter_table() {
+        let sql = "CREATE TABLE t (a INT, b VARCHAR(10)) DEFAULT CHARSET=latin1;
+            ALTER TABLE t ADD COLUMN c INT NOT NULL FIRST, MODIFY b VARCHAR(20), DROP COLUMN a;
+            ALTER TABLE t ADD UNIQUE KEY uk_c (c);
+            RENAME TABLE t TO u;";
+        let database = parse_database("db", sql).unwrap();
+        let table = &database.schemas[0].tables[0];
+        assert_eq!(table.name, "u");
+        let columns: Vec<(&str, i32, &str)> = table
+            .columns
+            .iter()
+            .map(|c| (c.name.as_str(), c.position, c.r#type.as_str()))
+            .collect();
+        assert_eq!(columns, vec![("c", 1, "int"), ("b", 2, "varchar(20)")]);
+        assert_eq!(table.columns[1].character_set, "latin1");
+        assert_eq!(table.indexes[0].name, "uk_c");
+
+        assert!(parse_database("db", "ALTER TABLE missing ADD COLUMN a INT").is_err());
+    }
+
+    #[test]
+    fn test_parse_events() {
+        let sql = "CREATE EVENT once ON SCHEDULE AT '2030-01-01 00:00:00' DO DELETE FROM t;
+            CREATE EVENT IF NOT EXISTS once ON SCHEDULE EVERY 1 HOUR DO DELETE FROM t;
+            CREATE EVENT replica ON SCHEDULE EVERY '1:30' HOUR_MINUTE
+                STARTS CURRENT_TIMESTAMP + INTERVAL 1 DAY DISABLE ON SLAVE DO DELETE FROM t WHERE a = 1;
+            CREATE EVENT dropped ON SCHEDULE EVERY 1 DAY DO DO 1;
+            DROP EVENT dropped;";
+        let database = parse_database("db", sql).unwrap();
+        let events = &database.schemas[0].events;
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].schedule, "AT '2030-01-01 00:00:00'");
+        assert_eq!(events[0].status, "ENABLED");
+        assert_eq!(events[0].on_completion, "NOT PRESERVE");
+        assert_eq!(events[0].body, "DELETE FROM t");
+        assert_eq!(
+            events[1].schedule,
+            "EVERY '1:30' HOUR_MINUTE STARTS CURRENT_TIMESTAMP + INTERVAL 1 DAY"
+        );
+        assert_eq!(events[1].status, "SLAVESIDE_DISABLED");
+        assert_eq!(events[1].body, "DELETE FROM t WHERE a = 1");
+    }
+
+    #[test]
+    fn test_parse_triggers() {
+        let sql = "CREATE TABLE t (a INT);
+            CREATE TRIGGER t_first BEFORE INSERT ON t FOR EACH ROW SET NEW.a = 1;
+            CREATE DEFINER = CURRENT_USER TRIGGER t_last BEFORE INSERT ON t
+                FOR EACH ROW FOLLOWS t_first SET NEW.a = NEW.a + 1;
+            CREATE TRIGGER t_zero BEFORE INSERT ON t FOR EACH ROW PRECEDES t_first SET NEW.a = 0;
+            CREATE TRIGGER t_audit AFTER DELETE ON t FOR EACH ROW DO 1;
+            CREATE TRIGGER t_dropped BEFORE UPDATE ON t FOR EACH ROW DO 1;
+            DROP TRIGGER IF EXISTS db.t_dropped;";
+        let database = parse_database("db", sql).unwrap();
+        let names: Vec<&str> = database.schemas[0].tables[0]
+            .triggers
+            .iter()
+            .map(|t| t.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["t_audit", "t_zero", "t_first", "t_last"]);
+
+        let duplicate = "CREATE TABLE t (a INT);
+            CREATE TRIGGER t_first BEFORE INSERT ON t FOR EACH ROW DO 1;
+            CREATE TRIGGER t_first AFTER INSERT ON t FOR EACH ROW DO 1;";
+        assert!(parse_database("db", duplicate).is_err());
+    }
+
+    #[test]
+    fn test_parse_partitions() {
+        let sql = "CREATE TABLE sales (id INT, region VARCHAR(10), sold DATE)
+            PARTITION BY LIST COLUMNS (region)
+            SUBPARTITION BY KEY (id) (
+                PARTITION east VALUES IN ('ny', 'nj') ENGINE = InnoDB
+                    (SUBPARTITION s0 COMMENT = 'first', SUBPARTITION s1),
+                PARTITION west VALUES IN ('ca') (SUBPARTITION s2, SUBPARTITION s3)
+            );
+            ALTER TABLE sales ADD PARTITION (
+                PARTITION south VALUES IN ('tx') (SUBPARTITION s4, SUBPARTITION s5)
+            );
+            ALTER TABLE sales DROP PARTITION east;
+            CREATE TABLE logs (id INT) PARTITION BY LINEAR HASH (id) PARTITIONS 3;
+            CREATE TABLE logs_copy LIKE logs;
+            CREATE TABLE plain (id INT, sold DATE);
+            ALTER TABLE plain PARTITION BY RANGE COLUMNS (sold, id) (
+                PARTITION p0 VALUES LESS THAN ('2024-01-01', 10),
+                PARTITION p1 VALUES LESS THAN (MAXVALUE, MAXVALUE)
+            );
+            ALTER TABLE logs REMOVE PARTITIONING;";
+        let database = parse_database("db", sql).unwrap();
+        let tables = &database.schemas[0].tables;
+        let names: Vec<&str> = tables.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, vec!["logs", "logs_copy", "plain", "sales"]);
+
+        assert!(tables[0].partitioning.is_none());
+        assert_eq!(tables[0].create_options, "");
+
+        let logs = tables[1].partitioning.as_ref().unwrap();
+        assert_eq!(logs.strategy, PartitionStrategy::LinearHash);
+        let partitions: Vec<&str> = logs.partitions.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(partitions, vec!["p0", "p1", "p2"]);
+
+        let plain = tables[2].partitioning.as_ref().unwrap();
+        assert_eq!(tables[2].create_options, "partitioned");
+        assert_eq!(plain.strategy, PartitionStrategy::RangeColumns);
+        assert_eq!(plain.expression, "`sold`,`id`");
+        assert_eq!(plain.partitions[0].value, "'2024-01-01',10");
+        assert_eq!(plain.partitions[1].value, "MAXVALUE,MAXVALUE");
+
+        let sales = tables[3].partitioning.as_ref().unwrap();
+        assert_eq!(sales.strategy, PartitionStrategy::ListColumns);
+        assert_eq!(sales.expression, "`region`");
+        let partitions: Vec<(&str, &str, Vec<&str>)> = sales
+            .partitions
+            .iter()
+            .map(|p| {
+                let subpartitioning = p.subpartitioning.as_ref().unwrap();
+                assert_eq!(subpartitioning.strategy, PartitionStrategy::Key);
+                let subpartitions = subpartitioning
+                    .partitions
+                    .iter()
+                    .map(|s| s.name.as_str())
+                    .collect();
+                (p.name.as_str(), p.value.as_str(), subpartitions)
+            })
+            .collect();
+        assert_eq!(
+            partitions,
+            vec![
+                ("west", "'ca'", vec!["s2", "s3"]),
+                ("south", "'tx'", vec!["s4", "s5"]),
+            ]
+        );
+
+        let missing_values = "CREATE TABLE t (a INT) PARTITION BY RANGE (a) (PARTITION p0)";
+        assert!(parse_database("db", missing_values).is_err());
+    }
+}
@@ -0,0 +1,2976 @@
+use super::{
+    parse_default, parse_type, split_statements, Cursor, DefaultValue, Dialect, Token, TypeName,
+};
+use crate::db::error::DBError;
+use crate::db::store;
+
+const PUBLIC_SCHEMA: &str = "public";
+
+// The keywords ending the type of a column definition.
+const COLUMN_OPTIONS: &[&str] = &[
+    "NOT",
+    "NULL",
+    "DEFAULT",
+    "PRIMARY",
+    "UNIQUE",
+    "REFERENCES",
+    "CHECK",
+    "CONSTRAINT",
+    "COLLATE",
+    "GENERATED",
+    "OPTIONS",
+    "DEFERRABLE",
+    "INITIALLY",
+];
+
+// The keywords quote_ident quotes, the unreserved keywords are left out.
+const RESERVED_KEYWORDS: &[&str] = &[
+    "all",
+    "analyse",
+    "analyze",
+    "and",
+    "any",
+    "array",
+    "as",
+    "asc",
+    "asymmetric",
+    "authorization",
+    "between",
+    "bigint",
+    "binary",
+    "bit",
+    "boolean",
+    "both",
+    "case",
+    "cast",
+    "char",
+    "character",
+    "check",
+    "coalesce",
+    "collate",
+    "collation",
+    "column",
+    "concurrently",
+    "constraint",
+    "create",
+    "cross",
+    "current_catalog",
+    "current_date",
+    "current_role",
+    "current_schema",
+    "current_time",
+    "current_timestamp",
+    "current_user",
+    "dec",
+    "decimal",
+    "default",
+    "deferrable",
+    "desc",
+    "distinct",
+    "do",
+    "else",
+    "end",
+    "except",
+    "exists",
+    "extract",
+    "false",
+    "fetch",
+    "float",
+    "for",
+    "foreign",
+    "freeze",
+    "from",
+    "full",
+    "grant",
+    "greatest",
+    "group",
+    "grouping",
+    "having",
+    "ilike",
+    "in",
+    "initially",
+    "inner",
+    "inout",
+    "int",
+    "integer",
+    "intersect",
+    "interval",
+    "into",
+    "is",
+    "isnull",
+    "join",
+    "lateral",
+    "leading",
+    "least",
+    "left",
+    "like",
+    "limit",
+    "localtime",
+    "localtimestamp",
+    "national",
+    "natural",
+    "nchar",
+    "none",
+    "normalize",
+    "not",
+    "notnull",
+    "null",
+    "nullif",
+    "numeric",
+    "offset",
+    "on",
+    "only",
+    "or",
+    "order",
+    "out",
+    "outer",
+    "overlaps",
+    "overlay",
+    "placing",
+    "position",
+    "precision",
+    "primary",
+    "real",
+    "references",
+    "returning",
+    "right",
+    "row",
+    "select",
+    "session_user",
+    "setof",
+    "similar",
+    "smallint",
+    "some",
+    "substring",
+    "symmetric",
+    "system_user",
+    "table",
+    "tablesample",
+    "then",
+    "time",
+    "timestamp",
+    "to",
+    "trailing",
+    "treat",
+    "trim",
+    "true",
+    "union",
+    "unique",
+    "user",
+    "using",
+    "values",
+    "varchar",
+    "variadic",
+    "verbose",
+    "when",
+    "where",
+    "window",
+    "with",
+];
+
+// The builtin types information_schema reports under their own name.
+const BUILTIN_TYPES: &[&str] = &[
+    "text",
+    "date",
+    "json",
+    "jsonb",
+    "uuid",
+    "bytea",
+    "interval",
+    "money",
+    "inet",
+    "cidr",
+    "macaddr",
+    "macaddr8",
+    "xml",
+    "tsvector",
+    "tsquery",
+    "point",
+    "line",
+    "lseg",
+    "box",
+    "path",
+    "polygon",
+    "circle",
+    "oid",
+    "name",
+    "regclass",
+    "int4range",
+    "int8range",
+    "numrange",
+    "tsrange",
+    "tstzrange",
+    "daterange",
+    "pg_lsn",
+    "txid_snapshot",
+    "pg_snapshot",
+    "jsonpath",
+];
+
+// The SQL value functions deparsed as upper case keywords.
+const VALUE_FUNCTIONS: &[&str] = &[
+    "CURRENT_DATE",
+    "CURRENT_TIME",
+    "LOCALTIME",
+    "LOCALTIMESTAMP",
+    "CURRENT_USER",
+    "CURRENT_ROLE",
+    "SESSION_USER",
+    "CURRENT_CATALOG",
+    "CURRENT_SCHEMA",
+];
+
+// The keywords starting a clause of CREATE FUNCTION, which end the return type.
+const ROUTINE_CLAUSES: &[&str] = &[
+    "LANGUAGE",
+    "TRANSFORM",
+    "WINDOW",
+    "IMMUTABLE",
+    "STABLE",
+    "VOLATILE",
+    "NOT",
+    "LEAKPROOF",
+    "CALLED",
+    "RETURNS",
+    "STRICT",
+    "EXTERNAL",
+    "SECURITY",
+    "PARALLEL",
+    "COST",
+    "ROWS",
+    "SUPPORT",
+    "SET",
+    "AS",
+    "BEGIN",
+    "RETURN",
+];
+
+// ColumnType is a declared type in the form of information_schema.
+struct ColumnType {
+    // The r#type is the type reported by the PostgreSQL driver sync.
+    r#type: String,
+    // The cast is the type name literals are cast to in column defaults.
+    cast: String,
+    // The serial is the integer type behind a serial pseudo type.
+    serial: bool,
+}
+
+// Constraint is a key or a foreign key declared in a table definition.
+enum Constraint {
+    PrimaryKey {
+        name: Option<String>,
+        columns: Vec<String>,
+        include: Vec<String>,
+    },
+    Unique {
+        name: Option<String>,
+        columns: Vec<String>,
+        include: Vec<String>,
+    },
+    ForeignKey {
+        name: Option<String>,
+        columns: Vec<String>,
+        reference: Reference,
+    },
+}
+
+struct Reference {
+    table: Vec<String>,
+    columns: Vec<String>,
+    match_type: String,
+    on_delete: String,
+    on_update: String,
+}
+
+// Partition is a table attached to a partitioned table. Partitions are parsed as tables and
+// nested under their parent once the file is replayed.
+struct Partition {
+    schema: String,
+    name: String,
+    parent_schema: String,
+    parent: String,
+    // The bound is in the form of pg_get_expr, e.g. FOR VALUES IN ('a', 'b') or DEFAULT.
+    bound: String,
+}
+
+struct Parser {
+    schemas: Vec<store::SchemaMetadata>,
+    extensions: Vec<store::ExtensionMetadata>,
+    search_path: Vec<String>,
+    // The types are the user defined types created by the file, by schema and name.
+    types: Vec<(String, String)>,
+    // The servers are the foreign servers with their dbname option.
+    servers: Vec<(String, String)>,
+    // The routine_arguments are the input argument types of each routine by schema and
+    // signature, DROP FUNCTION matches routines by them.
+    routine_arguments: Vec<(String, String, Vec<String>)>,
+    partitions: Vec<Partition>,
+}
+
+// parse_database replays the DDL statements of a PostgreSQL schema file and returns the
+// database metadata shaped like the PostgreSQL driver sync. Data and unknown statements,
+// including DO blocks, are skipped.
+// View, routine, trigger, expression index and expression default definitions are kept as
+// written rather than deparsed by the server, as are expression partition keys and partition
+// bound values, and partial index predicates keep their own parentheses. Other index definitions
+// are built the way pg_get_indexdef prints them. Partitions are nested in the partitioning of
+// their parent table rather than listed as tables. Routine owners are left empty, the dependent
+// columns of views are not resolved and extension versions are only known when the file names
+// them.
+pub fn parse_database(
+    database_name: &str,
+    sql: &str,
+) -> Result<store::DatabaseSchemaMetadata, DBError> {
+    let mut parser = Parser {
+        schemas: vec![empty_schema(PUBLIC_SCHEMA, "standard public schema")],
+        extensions: vec![],
+        search_path: vec!["$user".to_string(), PUBLIC_SCHEMA.to_string()],
+        types: vec![],
+        servers: vec![],
+        routine_arguments: vec![],
+        partitions: vec![],
+    };
+    for statement in split_statements(sql, Dialect::PostgreSQL) {
+        parser.parse_statement(statement)?;
+    }
+
+    Ok(parser.finish(database_name))
+}
+
+fn empty_schema(name: &str, comment: &str) -> store::SchemaMetadata {
+    store::SchemaMetadata {
+        name: name.to_string(),
+        tables: vec![],
+        external_tables: vec![],
+        views: vec![],
+        functions: vec![],
+        procedures: vec![],
+        materialized_views: vec![],
+        events: vec![],
+        owner: String::new(),
+        comment: comment.to_string(),
+    }
+}
+
+impl Parser {
+    fn parse_statement(&mut self, statement: &str) -> Result<(), DBError> {
+        let mut cursor = Cursor::new(statement, Dialect::PostgreSQL)?;
+        if cursor.eat_keyword("CREATE") {
+            let or_replace = cursor.eat_keywords(&["OR", "REPLACE"]);
+            if cursor.is_keyword("TEMP") || cursor.is_keyword("TEMPORARY") {
+                // Temporary objects do not outlive the session.
+                return Ok(());
+            }
+            cursor.eat_keyword("UNLOGGED");
+            if cursor.eat_keyword("SCHEMA") {
+                self.create_schema(&mut cursor)
+            } else if cursor.eat_keyword("TABLE") {
+                self.create_table(&mut cursor, false)
+            } else if cursor.eat_keywords(&["FOREIGN", "TABLE"]) {
+                self.create_table(&mut cursor, true)
+            } else if cursor.is_keyword("INDEX") || cursor.is_keyword_at(1, "INDEX") {
+                self.create_index(&mut cursor)
+            } else if cursor.eat_keyword("VIEW") || cursor.eat_keywords(&["RECURSIVE", "VIEW"]) {
+                self.create_view(&mut cursor, false)
+            } else if cursor.eat_keywords(&["MATERIALIZED", "VIEW"]) {
+                self.create_view(&mut cursor, true)
+            } else if cursor.eat_keyword("FUNCTION") {
+                self.create_routine(&mut cursor, statement, false, or_replace)
+            } else if cursor.eat_keyword("PROCEDURE") {
+                self.create_routine(&mut cursor, statement, true, or_replace)
+            } else if cursor.eat_keyword("TRIGGER")
+                || cursor.eat_keywords(&["CONSTRAINT", "TRIGGER"])
+            {
+                self.create_trigger(&mut cursor, statement, or_replace)
+            } else if cursor.eat_keyword("EXTENSION") {
+                self.create_extension(&mut cursor)
+            } else if cursor.eat_keyword("SERVER") {
+                self.create_server(&mut cursor)
+            } else if cursor.eat_keyword("TYPE") || cursor.eat_keyword("DOMAIN") {
+                let (schema, name) = self.creation_name(cursor.object_name()?)?;
+                self.types.push((schema, name));
+                Ok(())
+            } else {
+                Ok(())
+            }
+        } else if cursor.eat_keyword("ALTER") {
+            if cursor.eat_keyword("TABLE") || cursor.eat_keywords(&["FOREIGN", "TABLE"]) {
+                self.alter_table(&mut cursor)
+            } else if cursor.eat_keyword("SCHEMA") {
+                self.alter_schema(&mut cursor)
+            } else {
+                Ok(())
+            }
+        } else if cursor.eat_keyword("DROP") {
+            self.drop(&mut cursor)
+        } else if cursor.eat_keywords(&["COMMENT", "ON"]) {
+            self.comment_on(&mut cursor)
+        } else if cursor.eat_keyword("SET") {
+            cursor.eat_keyword("SESSION");
+            cursor.eat_keyword("LOCAL");
+            if cursor.eat_keyword("SEARCH_PATH") {
+                if !cursor.eat_keyword("TO") {
+                    cursor.expect_punct('=')?;
+                }
+                let mut search_path = vec![];
+                loop {
+                    match cursor.next() {
+                        Some(Token::Word(w)) if w.eq_ignore_ascii_case("DEFAULT") => {
+                            search_path = vec!["$user".to_string(), PUBLIC_SCHEMA.to_string()];
+                        }
+                        Some(Token::Word(w)) => search_path.push(w.to_lowercase()),
+                        Some(Token::Ident(w)) | Some(Token::Str(w)) => search_path.push(w),
+                        _ => return Err(cursor.error("expected a schema name")),
+                    }
+                    if !cursor.eat_punct(',') {
+                        break;
+                    }
+                }
+                self.search_path = search_path;
+            }
+            Ok(())
+        } else if cursor.eat_keyword("SELECT") {
+            // pg_dump resets the search path with set_config.
+            let _ = cursor.object_name();
+            if cursor.eat_punct('(')
+                && matches!(cursor.next(), Some(Token::Str(s)) if s == "search_path")
+                && cursor.eat_punct(',')
+            {
+                if let Some(Token::Str(path)) = cursor.next() {
+                    self.search_path = path
+                        .split(',')
+                        .map(|s| s.trim().trim_matches('"').to_string())
+                        .filter(|s| !s.is_empty())
+                        .collect();
+                }
+            }
+            Ok(())
+        } else {
+            Ok(())
+        }
+    }
+
+    // Returns the schema of a new object, the first existing schema of the search path
+    // when the name is not qualified.
+    fn creation_name(&self, mut parts: Vec<String>) -> Result<(String, String), DBError> {
+        let name = parts.pop().unwrap_or_default();
+        let schema = match parts.pop() {
+            Some(schema) => schema,
+            None => self
+                .search_path
+                .iter()
+                .find(|s| self.schemas.iter().any(|schema| &schema.name == *s))
+                .cloned()
+                .unwrap_or_else(|| PUBLIC_SCHEMA.to_string()),
+        };
+        Ok((schema, name))
+    }
+
+    // Resolves the schema of an existing object through the search path.
+    fn resolve<F>(&self, mut parts: Vec<String>, exists: F) -> Option<(String, String)>
+    where
+        F: Fn(&store::SchemaMetadata, &str) -> bool,
+    {
+        let name = parts.pop()?;
+        let schemas: Vec<String> = match parts.pop() {
+            Some(schema) => vec![schema],
+            None => self.search_path.clone(),
+        };
+        schemas.into_iter().find_map(|schema_name| {
+            let schema = self.schemas.iter().find(|s| s.name == schema_name)?;
+            exists(schema, &name).then(|| (schema_name, name.clone()))
+        })
+    }
+
+    fn resolve_table(&self, parts: Vec<String>) -> Option<(String, String)> {
+        self.resolve(parts, |s, n| s.tables.iter().any(|t| t.name == n))
+    }
+
+    fn schema_mut(&mut self, name: &str) -> Result<&mut store::SchemaMetadata, DBError> {
+        self.schemas
+            .iter_mut()
+            .find(|s| s.name == name)
+            .ok_or_else(|| DBError::args(format!("schema {name} does not exist")))
+    }
+
+    fn table_mut(
+        &mut self,
+        schema: &str,
+        name: &str,
+    ) -> Result<&mut store::TableMetadata, DBError> {
+        self.schema_mut(schema)?
+            .tables
+            .iter_mut()
+            .find(|t| t.name == name)
+            .ok_or_else(|| DBError::args(format!("table {schema}.{name} does not exist")))
+    }
+
+    fn create_schema(&mut self, cursor: &mut Cursor) -> Result<(), DBError> {
+        let if_not_exists = cursor.eat_keywords(&["IF", "NOT", "EXISTS"]);
+        let (name, owner) = if cursor.eat_keyword("AUTHORIZATION") {
+            let owner = cursor.identifier()?;
+            (owner.clone(), owner)
+        } else {
+            let name = cursor.identifier()?;
+            let owner = if cursor.eat_keyword("AUTHORIZATION") {
+                cursor.identifier()?
+            } else {
+                String::new()
+            };
+            (name, owner)
+        };
+        if self.schemas.iter().any(|s| s.name == name) {
+            if if_not_exists {
+                return Ok(());
+            }
+            return Err(DBError::args(format!("schema {name} already exists")));
+        }
+        let mut schema = empty_schema(&name, "");
+        schema.owner = owner;
+        self.schemas.push(schema);
+
+        Ok(())
+    }
+
+    fn alter_schema(&mut self, cursor: &mut Cursor) -> Result<(), DBError> {
+        let name = cursor.identifier()?;
+        if cursor.eat_keywords(&["OWNER", "TO"]) {
+            let owner = cursor.identifier()?;
+            self.schema_mut(&name)?.owner = owner;
+        } else if cursor.eat_keywords(&["RENAME", "TO"]) {
+            let new = cursor.identifier()?;
+            self.schema_mut(&name)?.name = new.clone();
+            for path in self.search_path.iter_mut().filter(|p| **p == name) {
+                *path = new.clone();
+            }
+        }
+        Ok(())
+    }
+
+    fn create_table(&mut self, cursor: &mut Cursor, foreign: bool) -> Result<(), DBError> {
+        let if_not_exists = cursor.eat_keywords(&["IF", "NOT", "EXISTS"]);
+        let (schema_name, name) = self.creation_name(cursor.object_name()?)?;
+        let schema = self.schema_mut(&schema_name)?;
+        if schema.tables.iter().any(|t| t.name == name)
+            || schema.external_tables.iter().any(|t| t.name == name)
+        {
+            if if_not_exists {
+                return Ok(());
+            }
+            return Err(DBError::args(format!(
+                "table {schema_name}.{name} already exists"
+            )));
+        }
+        if !foreign && cursor.eat_keywords(&["PARTITION", "OF"]) {
+            return self.create_partition(cursor, schema_name, name);
+        }
+        if !cursor.is_punct('(') {
+            // CREATE TABLE AS is not part of the synced metadata.
+            return Ok(());
+        }
+
+        let mut columns = vec![];
+        let mut constraints = vec![];
+        cursor.expect_punct('(')?;
+        if !cursor.is_punct(')') {
+            loop {
+                self.table_element(cursor, &schema_name, &name, &mut columns, &mut constraints)?;
+                if !cursor.eat_punct(',') {
+                    break;
+                }
+            }
+        }
+        cursor.expect_punct(')')?;
+        for (position, column) in columns.iter_mut().enumerate() {
+            column.position = position as i32 + 1;
+        }
+
+        if foreign {
+            while !cursor.at_end() && !cursor.is_keyword("SERVER") {
+                cursor.next();
+            }
+            cursor.expect_keyword("SERVER")?;
+            let server = cursor.identifier()?;
+            let database = self
+                .servers
+                .iter()
+                .find(|(s, _)| *s == server)
+                .map(|(_, database)| database.clone())
+                .unwrap_or_default();
+            self.schema_mut(&schema_name)?
+                .external_tables
+                .push(store::ExternalTableMetadata {
+                    name,
+                    external_server_name: server,
+                    external_database_name: database,
+                    columns,
+                });
+            return Ok(());
+        }
+
+        if cursor.eat_keyword("INHERITS") {
+            cursor.parenthesized()?;
+        }
+        let partitioning = partition_by(cursor)?;
+        self.schema_mut(&schema_name)?
+            .tables
+            .push(table_metadata(&name, columns, partitioning));
+        for constraint in constraints {
+            self.add_constraint(&schema_name, &name, constraint)?;
+        }
+
+        Ok(())
+    }
+
+    // Parses the rest of CREATE TABLE ... PARTITION OF, the partition takes the columns of its
+    // parent. Constraints and column options of the partition are skipped.
+    fn create_partition(
+        &mut self,
+        cursor: &mut Cursor,
+        schema: String,
+        name: String,
+    ) -> Result<(), DBError> {
+        let parts = cursor.object_name()?;
+        let (parent_schema, parent) = self
+            .resolve_table(parts.clone())
+            .ok_or_else(|| DBError::args(format!("table {} does not exist", parts.join("."))))?;
+        if cursor.is_punct('(') {
+            cursor.parenthesized()?;
+        }
+        let bound = partition_bound(cursor)?;
+        let partitioning = partition_by(cursor)?;
+        let columns = self.table_mut(&parent_schema, &parent)?.columns.clone();
+        self.schema_mut(&schema)?
+            .tables
+            .push(table_metadata(&name, columns, partitioning));
+        self.partitions.push(Partition {
+            schema,
+            name,
+            parent_schema,
+            parent,
+            bound,
+        });
+
+        Ok(())
+    }
+
+    fn table_element(
+        &self,
+        cursor: &mut Cursor,
+        schema: &str,
+        table: &str,
+        columns: &mut Vec<store::ColumnMetadata>,
+        constraints: &mut Vec<Constraint>,
+    ) -> Result<(), DBError> {
+        if cursor.eat_keyword("LIKE") {
+            return Err(cursor.error("CREATE TABLE ... LIKE is not supported"));
+        }
+        let is_constraint = [
+            "CONSTRAINT",
+            "PRIMARY",
+            "UNIQUE",
+            "FOREIGN",
+            "CHECK",
+            "EXCLUDE",
+        ]
+        .iter()
+        .any(|k| cursor.is_keyword(k));
+        if is_constraint {
+            if let Some(constraint) = table_constraint(cursor)? {
+                constraints.push(constraint);
+            }
+            return Ok(());
+        }
+        let column = self.parse_column(cursor, schema, table, constraints)?;
+        columns.push(column);
+        Ok(())
+    }
+
+    fn parse_column(
+        &self,
+        cursor: &mut Cursor,
+        schema: &str,
+        table: &str,
+        constraints: &mut Vec<Constraint>,
+    ) -> Result<store::ColumnMetadata, DBError> {
+        let name = cursor.identifier()?;
+        let type_name = TypeName::from_tokens(&parse_type(cursor, COLUMN_OPTIONS)?);
+        let column_type = self.column_type(&type_name);
+        let mut column = store::ColumnMetadata {
+            name: name.clone(),
+            position: 0,
+            default: String::new(),
+            on_update: None,
+            nullable: true,
+            r#type: column_type.r#type.clone(),
+            character_set: String::new(),
+            collation: String::new(),
+            comment: String::new(),
+            identity_generation: store::IdentityGeneration::UNSPECIFIED,
+        };
+        if column_type.serial {
+            column.nullable = false;
+            let sequence = format!("{table}_{name}_seq");
+            column.default = format!(
+                "nextval('{}'::regclass)",
+                qualified_name(schema, &sequence).replace('\'', "''")
+            );
+        }
+
+        loop {
+            let constraint_name = if cursor.eat_keyword("CONSTRAINT") {
+                Some(cursor.identifier()?)
+            } else {
+                None
+            };
+            if cursor.eat_keywords(&["NOT", "NULL"]) {
+                column.nullable = false;
+            } else if cursor.eat_keyword("NULL") {
+                column.nullable = true;
+            } else if cursor.eat_keyword("DEFAULT") {
+                column.default = default_value(&parse_default(cursor)?, &column_type);
+            } else if cursor.eat_keyword("COLLATE") {
+                column.collation = cursor.object_name()?.join(".");
+            } else if cursor.eat_keyword("GENERATED") {
+                if cursor.eat_keywords(&["BY", "DEFAULT"]) {
+                    column.identity_generation = store::IdentityGeneration::ByDefault;
+                } else {
+                    cursor.expect_keyword("ALWAYS")?;
+                    column.identity_generation = store::IdentityGeneration::Always;
+                }
+                cursor.expect_keyword("AS")?;
+                if cursor.eat_keyword("IDENTITY") {
+                    column.nullable = false;
+                    if cursor.is_punct('(') {
+                        cursor.parenthesized()?;
+                    }
+                } else {
+                    // A stored generated column is not an identity column.
+                    column.identity_generation = store::IdentityGeneration::UNSPECIFIED;
+                    cursor.parenthesized()?;
+                    cursor.expect_keyword("STORED")?;
+                }
+            } else if cursor.eat_keywords(&["PRIMARY", "KEY"]) {
+                column.nullable = false;
+                constraints.push(Constraint::PrimaryKey {
+                    name: constraint_name,
+                    columns: vec![name.clone()],
+                    include: vec![],
+                });
+            } else if cursor.eat_keyword("UNIQUE") {
+                if cursor.eat_keyword("NULLS") {
+                    cursor.eat_keyword("NOT");
+                    cursor.expect_keyword("DISTINCT")?;
+                }
+                constraints.push(Constraint::Unique {
+                    name: constraint_name,
+                    columns: vec![name.clone()],
+                    include: vec![],
+                });
+            } else if cursor.eat_keyword("REFERENCES") {
+                constraints.push(Constraint::ForeignKey {
+                    name: constraint_name,
+                    columns: vec![name.clone()],
+                    reference: reference(cursor)?,
+                });
+            } else if cursor.eat_keyword("CHECK") {
+                cursor.parenthesized()?;
+                cursor.eat_keywords(&["NO", "INHERIT"]);
+            } else if cursor.eat_keyword("OPTIONS") {
+                cursor.parenthesized()?;
+            } else if constraint_deferrability(cursor) {
+            } else if constraint_name.is_some() {
+                return Err(cursor.error("expected a column constraint"));
+            } else {
+                return Ok(column);
+            }
+        }
+    }
+
+    // Returns the type of a column the way information_schema reports it.
+    fn column_type(&self, type_name: &TypeName) -> ColumnType {
+        let mut name = type_name.name();
+        for word in &type_name.suffix {
+            name = format!("{name} {word}");
+        }
+        let name = name.strip_prefix("pg_catalog.").unwrap_or(&name);
+        let length = type_name.args.first().cloned();
+        let mut serial = false;
+        let (r#type, length) = match name {
+            "smallint" | "int2" => ("smallint", None),
+            "integer" | "int" | "int4" => ("integer", None),
+            "bigint" | "int8" => ("bigint", None),
+            "smallserial" | "serial2" => {
+                serial = true;
+                ("smallint", None)
+            }
+            "serial" | "serial4" => {
+                serial = true;
+                ("integer", None)
+            }
+            "bigserial" | "serial8" => {
+                serial = true;
+                ("bigint", None)
+            }
+            "real" | "float4" => ("real", None),
+            "double precision" | "float8" => ("double precision", None),
+            "float" => match length.and_then(|p| p.parse::<u32>().ok()) {
+                Some(p) if p <= 24 => ("real", None),
+                _ => ("double precision", None),
+            },
+            "numeric" | "decimal" => ("numeric", None),
+            "boolean" | "bool" => ("boolean", None),
+            "varchar" | "character varying" | "char varying" => ("character varying", length),
+            "char" | "character" => ("character", length.or(Some("1".to_string()))),
+            "bpchar" => ("character", length),
+            "bit" => ("bit", length.or(Some("1".to_string()))),
+            "varbit" | "bit varying" => ("bit varying", length),
+            "timestamp" | "timestamp without time zone" => ("timestamp without time zone", None),
+            "timestamptz" | "timestamp with time zone" => ("timestamp with time zone", None),
+            "time" | "time without time zone" => ("time without time zone", None),
+            "timetz" | "time with time zone" => ("time with time zone", None),
+            name if BUILTIN_TYPES.contains(&name) => (name, None),
+            name => {
+                let (schema, udt) = match name.split_once('.') {
+                    Some((schema, udt)) => (schema.to_string(), udt.to_string()),
+                    None => {
+                        let schema = self
+                            .search_path
+                            .iter()
+                            .find(|s| self.types.iter().any(|(ts, tn)| ts == *s && tn == name))
+                            .cloned()
+                            .unwrap_or_else(|| PUBLIC_SCHEMA.to_string());
+                        (schema, name.to_string())
+                    }
+                };
+                let cast = qualified_name(&schema, &udt);
+                if type_name.array {
+                    return ColumnType {
+                        r#type: format!("_{udt}"),
+                        cast: format!("{cast}[]"),
+                        serial: false,
+                    };
+                }
+                return ColumnType {
+                    r#type: format!("{schema}.{udt}"),
+                    cast,
+                    serial: false,
+                };
+            }
+        };
+
+        let cast = match r#type {
+            "character" => "bpchar",
+            r#type => r#type,
+        };
+        if type_name.array {
+            return ColumnType {
+                r#type: format!("_{}", udt_name(r#type)),
+                cast: format!("{cast}[]"),
+                serial,
+            };
+        }
+        ColumnType {
+            r#type: match length {
+                Some(length) => format!("{type}({length})"),
+                None => r#type.to_string(),
+            },
+            cast: cast.to_string(),
+            serial,
+        }
+    }
+
+    // Returns the type of a routine argument the way format_type reports it.
+    fn argument_type(&self, type_name: &TypeName) -> String {
+        let column_type = self.column_type(&TypeName {
+            args: vec![],
+            ..type_name.clone()
+        });
+        match column_type.cast.as_str() {
+            "bpchar" => "character".to_string(),
+            "bpchar[]" => "character[]".to_string(),
+            cast => cast.to_string(),
+        }
+    }
+
+    fn add_constraint(
+        &mut self,
+        schema: &str,
+        table: &str,
+        constraint: Constraint,
+    ) -> Result<(), DBError> {
+        match constraint {
+            Constraint::PrimaryKey {
+                name,
+                columns,
+                include,
+            } => {
+                let name = name.unwrap_or_else(|| format!("{table}_pkey"));
+                let table_metadata = self.table_mut(schema, table)?;
+                for column in table_metadata
+                    .columns
+                    .iter_mut()
+                    .filter(|c| columns.contains(&c.name))
+                {
+                    column.nullable = false;
+                }
+                let index = key_index(schema, table, &name, true, &columns, &include);
+                table_metadata.indexes.push(index);
+            }
+            Constraint::Unique {
+                name,
+                columns,
+                include,
+            } => {
+                let name = name.unwrap_or_else(|| format!("{table}_{}_key", columns.join("_")));
+                let index = key_index(schema, table, &name, false, &columns, &include);
+                self.table_mut(schema, table)?.indexes.push(index);
+            }
+            Constraint::ForeignKey {
+                name,
+                columns,
+                reference,
+            } => {
+                let name = name.unwrap_or_else(|| format!("{table}_{}_fkey", columns.join("_")));
+                let (referenced_schema, referenced_table) =
+                    self.resolve_table(reference.table.clone()).ok_or_else(|| {
+                        DBError::args(format!(
+                            "referenced table {} does not exist",
+                            reference.table.join(".")
+                        ))
+                    })?;
+                let referenced_columns = if reference.columns.is_empty() {
+                    self.table_mut(&referenced_schema, &referenced_table)?
+                        .indexes
+                        .iter()
+                        .find(|i| i.primary)
+                        .map(|i| i.expressions.clone())
+                        .ok_or_else(|| {
+                            DBError::args(format!(
+                                "referenced table {referenced_table} has no primary key"
+                            ))
+                        })?
+                } else {
+                    reference.columns
+                };
+                self.table_mut(schema, table)?
+                    .foreign_keys
+                    .push(store::ForeignKeyMetadata {
+                        name,
+                        columns,
+                        referenced_schema,
+                        referenced_table,
+                        referenced_columns,
+                        on_delete: reference.on_delete,
+                        on_update: reference.on_update,
+                        match_type: reference.match_type,
+                    });
+            }
+        }
+
+        Ok(())
+    }
+
+    fn create_index(&mut self, cursor: &mut Cursor) -> Result<(), DBError> {
+        let unique = cursor.eat_keyword("UNIQUE");
+        cursor.expect_keyword("INDEX")?;
+        cursor.eat_keyword("CONCURRENTLY");
+        let if_not_exists = cursor.eat_keywords(&["IF", "NOT", "EXISTS"]);
+        let name = if cursor.is_keyword("ON") {
+            None
+        } else {
+            Some(cursor.identifier()?)
+        };
+        cursor.expect_keyword("ON")?;
+        cursor.eat_keyword("ONLY");
+        let Some((schema, table)) = self.resolve_table(cursor.object_name()?) else {
+            // Indexes on materialized views are not part of the synced metadata.
+            return Ok(());
+        };
+        let method = if cursor.eat_keyword("USING") {
+            cursor.identifier()?
+        } else {
+            "btree".to_string()
+        };
+
+        // Each key part is kept as the expression and as its text in the definition.
+        let mut expressions = vec![];
+        let mut keys = vec![];
+        let mut name_parts = vec![];
+        cursor.expect_punct('(')?;
+        loop {
+            let start = cursor.offset();
+            let (expression, key) = if cursor.is_punct('(') {
+                let expression = cursor.parenthesized()?.to_string();
+                name_parts.push("expr".to_string());
+                (expression.clone(), format!("({expression})"))
+            } else if cursor.peek_at(1) == Some(&Token::Punct('(')) {
+                let function = cursor.identifier()?;
+                cursor.parenthesized()?;
+                name_parts.push(function);
+                let expression = cursor.text_from(start).to_string();
+                (expression.clone(), expression)
+            } else {
+                let column = cursor.identifier()?;
+                name_parts.push(column.clone());
+                (display_identifier(&column), display_identifier(&column))
+            };
+            let mut key = key;
+            loop {
+                if cursor.eat_keyword("COLLATE") {
+                    key = format!("{key} COLLATE {}", cursor.object_name()?.join("."));
+                } else if cursor.eat_keyword("ASC") {
+                } else if cursor.eat_keyword("DESC") {
+                    key.push_str(" DESC");
+                } else if cursor.eat_keyword("NULLS") {
+                    let order = cursor.identifier()?.to_uppercase();
+                    key = format!("{key} NULLS {order}");
+                } else if matches!(cursor.peek(), Some(Token::Word(_)) | Some(Token::Ident(_))) {
+                    key = format!("{key} {}", cursor.object_name()?.join("."));
+                } else {
+                    break;
+                }
+            }
+            expressions.push(expression);
+            keys.push(key);
+            if !cursor.eat_punct(',') {
+                break;
+            }
+        }
+        cursor.expect_punct(')')?;
+
+        let mut include = vec![];
+        let mut predicate = String::new();
+        loop {
+            if cursor.eat_keyword("INCLUDE") {
+                include = cursor.identifier_list()?;
+            } else if cursor.eat_keyword("NULLS") {
+                cursor.eat_keyword("NOT");
+                cursor.expect_keyword("DISTINCT")?;
+            } else if cursor.eat_keyword("WITH") {
+                cursor.parenthesized()?;
+            } else if cursor.eat_keyword("TABLESPACE") {
+                cursor.identifier()?;
+            } else if cursor.eat_keyword("WHERE") {
+                predicate = normalize_predicate(cursor.rest());
+                break;
+            } else {
+                break;
+            }
+        }
+
+        let name = name.unwrap_or_else(|| format!("{table}_{}_idx", name_parts.join("_")));
+        let table_metadata = self.table_mut(&schema, &table)?;
+        if table_metadata.indexes.iter().any(|i| i.name == name) {
+            if if_not_exists {
+                return Ok(());
+            }
+            return Err(DBError::args(format!("index {name} already exists")));
+        }
+        let definition = index_definition(
+            &schema, &table, &name, unique, &method, &keys, &include, &predicate,
+        );
+        table_metadata.indexes.push(store::IndexMetadata {
+            name,
+            key_length: vec![-1; expressions.len()],
+            expressions,
+            r#type: method,
+            unique,
+            primary: false,
+            visible: true,
+            comment: String::new(),
+            definition,
+            include_columns: include.iter().map(|c| display_identifier(c)).collect(),
+            predicate,
+        });
+
+        Ok(())
+    }
+
+    fn create_view(&mut self, cursor: &mut Cursor, materialized: bool) -> Result<(), DBError> {
+        let if_not_exists = cursor.eat_keywords(&["IF", "NOT", "EXISTS"]);
+        let (schema_name, name) = self.creation_name(cursor.object_name()?)?;
+        if cursor.is_punct('(') {
+            cursor.parenthesized()?;
+        }
+        if cursor.eat_keyword("USING") {
+            cursor.identifier()?;
+        }
+        if cursor.eat_keyword("WITH") {
+            cursor.parenthesized()?;
+        }
+        if cursor.eat_keyword("TABLESPACE") {
+            cursor.identifier()?;
+        }
+        cursor.expect_keyword("AS")?;
+        let definition = strip_view_suffix(cursor.rest()).to_string();
+
+        let schema = self.schema_mut(&schema_name)?;
+        if materialized {
+            if let Some(view) = schema.materialized_views.iter().find(|v| v.name == name) {
+                if if_not_exists {
+                    return Ok(());
+                }
+                return Err(DBError::args(format!(
+                    "materialized view {} already exists",
+                    view.name
+                )));
+            }
+            schema
+                .materialized_views
+                .push(store::MaterializedViewMetadata {
+                    name,
+                    definition,
+                    comment: String::new(),
+                    dependent_columns: vec![],
+                });
+        } else if let Some(view) = schema.views.iter_mut().find(|v| v.name == name) {
+            view.definition = definition;
+        } else {
+            schema.views.push(store::ViewMetadata {
+                name,
+                definition,
+                comment: String::new(),
+                dependent_columns: vec![],
+            });
+        }
+
+        Ok(())
+    }
+
+    fn create_routine(
+        &mut self,
+        cursor: &mut Cursor,
+        statement: &str,
+        procedure: bool,
+        or_replace: bool,
+    ) -> Result<(), DBError> {
+        let (schema_name, name) = self.creation_name(cursor.object_name()?)?;
+        let arguments = cursor.parenthesized()?;
+        let (identity, types, mut parameters) = self.routine_arguments(arguments, procedure)?;
+        let signature = format!("{name}({identity})");
+
+        let exists = self
+            .routine_arguments
+            .iter()
+            .any(|(s, sig, _)| *s == schema_name && *sig == signature);
+        if exists && !or_replace {
+            return Err(DBError::args(format!(
+                "routine {schema_name}.{signature} already exists"
+            )));
+        }
+
+        // The clauses follow in any order, a SQL standard body ends them.
+        let mut return_type = None;
+        let mut language = "sql".to_string();
+        let mut volatility = store::Volatility::Volatile;
+        let mut security = store::SecurityType::Invoker;
+        while let Some(token) = cursor.next() {
+            let Token::Word(word) = token else {
+                continue;
+            };
+            match word.to_uppercase().as_str() {
+                "RETURNS" if !cursor.is_keyword("NULL") => {
+                    return_type = Some(self.return_type(cursor, &mut parameters)?);
+                }
+                "LANGUAGE" => {
+                    language = match cursor.next() {
+                        Some(Token::Word(w)) => w.to_lowercase(),
+                        Some(Token::Ident(w)) | Some(Token::Str(w)) => w,
+                        _ => return Err(cursor.error("expected a language")),
+                    };
+                }
+                "IMMUTABLE" => volatility = store::Volatility::Immutable,
+                "STABLE" => volatility = store::Volatility::Stable,
+                "VOLATILE" => volatility = store::Volatility::Volatile,
+                "SECURITY" if cursor.eat_keyword("DEFINER") => {
+                    security = store::SecurityType::Definer;
+                }
+                "SECURITY" if cursor.eat_keyword("INVOKER") => {
+                    security = store::SecurityType::Invoker;
+                }
+                "BEGIN" | "RETURN" => break,
+                _ => {}
+            }
+        }
+        // Without RETURNS, a function returns its output argument or a record of them.
+        let return_type = return_type.unwrap_or_else(|| {
+            let outputs: Vec<&store::RoutineParameter> = parameters
+                .iter()
+                .filter(|p| {
+                    matches!(
+                        p.mode,
+                        store::ParameterMode::Out | store::ParameterMode::InOut
+                    )
+                })
+                .collect();
+            match outputs.as_slice() {
+                [output] => output.r#type.clone(),
+                _ => "record".to_string(),
+            }
+        });
+
+        self.drop_routine(&schema_name, &name, Some(&types));
+        self.routine_arguments
+            .push((schema_name.clone(), signature.clone(), types));
+
+        let definition = statement.to_string();
+        let schema = self.schema_mut(&schema_name)?;
+        if procedure {
+            schema.procedures.push(store::ProcedureMetadata {
+                name,
+                signature,
+                definition,
+                parameters,
+                language,
+                volatility,
+                security,
+                owner: String::new(),
+                comment: String::new(),
+            });
+        } else {
+            schema.functions.push(store::FunctionMetadata {
+                name,
+                signature,
+                definition,
+                parameters,
+                return_type,
+                language,
+                volatility,
+                security,
+                owner: String::new(),
+                comment: String::new(),
+            });
+        }
+
+        Ok(())
+    }
+
+    fn create_trigger(
+        &mut self,
+        cursor: &mut Cursor,
+        statement: &str,
+        or_replace: bool,
+    ) -> Result<(), DBError> {
+        let name = cursor.identifier()?;
+        let timing = if cursor.eat_keywords(&["INSTEAD", "OF"]) {
+            "INSTEAD OF"
+        } else if cursor.eat_keyword("BEFORE") {
+            "BEFORE"
+        } else {
+            cursor.expect_keyword("AFTER")?;
+            "AFTER"
+        };
+        let mut events = vec![];
+        loop {
+            let event = match cursor.next() {
+                Some(Token::Word(w)) => w.to_uppercase(),
+                _ => return Err(cursor.error("expected a trigger event")),
+            };
+            // The columns of UPDATE OF are not part of the metadata.
+            if event == "UPDATE" && cursor.eat_keyword("OF") {
+                loop {
+                    cursor.identifier()?;
+                    if !cursor.eat_punct(',') {
+                        break;
+                    }
+                }
+            }
+            events.push(event);
+            if !cursor.eat_keyword("OR") {
+                break;
+            }
+        }
+        // pg_get_triggerdef writes the events in this order.
+        events.sort_by_key(|e| {
+            ["INSERT", "DELETE", "UPDATE", "TRUNCATE"]
+                .iter()
+                .position(|k| k == e)
+        });
+        cursor.expect_keyword("ON")?;
+        let parts = cursor.object_name()?;
+        let (schema, table) = self
+            .resolve_table(parts.clone())
+            .ok_or_else(|| DBError::args(format!("relation {} does not exist", parts.join("."))))?;
+
+        // FOR EACH STATEMENT is the default, the clauses before EXECUTE are skipped otherwise.
+        let mut for_each_row = false;
+        while !cursor.at_end() && !cursor.is_keyword("EXECUTE") {
+            if cursor.eat_keyword("FOR") {
+                cursor.eat_keyword("EACH");
+                for_each_row = cursor.eat_keyword("ROW");
+            } else if cursor.is_punct('(') {
+                cursor.parenthesized()?;
+            } else {
+                cursor.next();
+            }
+        }
+
+        let table_metadata = self.table_mut(&schema, &table)?;
+        if table_metadata.triggers.iter().any(|t| t.name == name) {
+            if !or_replace {
+                return Err(DBError::args(format!(
+                    "trigger {name} for relation {table} already exists"
+                )));
+            }
+            table_metadata.triggers.retain(|t| t.name != name);
+        }
+        table_metadata.triggers.push(store::TriggerMetadata {
+            name,
+            timing: timing.to_string(),
+            events,
+            for_each_row,
+            definition: statement.to_string(),
+            enabled: true,
+        });
+
+        Ok(())
+    }
+
+    // Returns the type following RETURNS like pg_get_function_result. The columns of a
+    // RETURNS TABLE function are added to its parameters.
+    fn return_type(
+        &self,
+        cursor: &mut Cursor,
+        parameters: &mut Vec<store::RoutineParameter>,
+    ) -> Result<String, DBError> {
+        if cursor.is_keyword("TABLE") && cursor.peek_at(1) == Some(&Token::Punct('(')) {
+            cursor.next();
+            let (columns, _, table_parameters) =
+                self.routine_arguments(cursor.parenthesized()?, false)?;
+            parameters.extend(
+                table_parameters
+                    .into_iter()
+                    .map(|p| store::RoutineParameter {
+                        mode: store::ParameterMode::Table,
+                        ..p
+                    }),
+            );
+            return Ok(format!("TABLE({columns})"));
+        }
+        let setof = cursor.eat_keyword("SETOF");
+        let tokens = parse_type(cursor, ROUTINE_CLAUSES)?;
+        let return_type = self.argument_type(&TypeName::from_tokens(&tokens));
+        Ok(if setof {
+            format!("SETOF {return_type}")
+        } else {
+            return_type
+        })
+    }
+
+    // Returns the identity arguments of a routine like pg_get_function_identity_arguments,
+    // with the input argument types and the parameters.
+    fn routine_arguments(
+        &self,
+        arguments: &str,
+        procedure: bool,
+    ) -> Result<(String, Vec<String>, Vec<store::RoutineParameter>), DBError> {
+        let mut cursor = Cursor::new(arguments, Dialect::PostgreSQL)?;
+        let mut identity = vec![];
+        let mut types = vec![];
+        let mut parameters = vec![];
+        while !cursor.at_end() {
+            let start = cursor.position();
+            cursor.skip_element();
+            let mut tokens = cursor.tokens_from(start);
+            cursor.eat_punct(',');
+
+            let is_word =
+                |t: &Token, w: &str| matches!(t, Token::Word(x) if x.eq_ignore_ascii_case(w));
+            let mode = match tokens.first() {
+                Some(t)
+                    if ["IN", "OUT", "INOUT", "VARIADIC"]
+                        .iter()
+                        .any(|m| is_word(t, m)) =>
+                {
+                    match tokens.remove(0) {
+                        Token::Word(w) => w.to_uppercase(),
+                        _ => String::new(),
+                    }
+                }
+                _ => "IN".to_string(),
+            };
+            if let Some(at) = tokens
+                .iter()
+                .position(|t| is_word(t, "DEFAULT") || *t == Token::Punct('='))
+            {
+                tokens.truncate(at);
+            }
+            let named = tokens.len() > 1
+                && match (&tokens[0], &tokens[1]) {
+                    (Token::Ident(_), _) => true,
+                    (Token::Word(_), Token::Punct(_)) => false,
+                    (Token::Word(first), Token::Word(second)) => !is_type_prefix(first, second),
+                    _ => false,
+                };
+            let (argument_name, type_tokens) = if named {
+                let argument_name = match &tokens[0] {
+                    Token::Word(w) => w.to_lowercase(),
+                    Token::Ident(w) => w.clone(),
+                    _ => String::new(),
+                };
+                (Some(argument_name), &tokens[1..])
+            } else {
+                (None, &tokens[..])
+            };
+            let argument_type = self.argument_type(&TypeName::from_tokens(type_tokens));
+
+            if mode != "OUT" {
+                types.push(argument_type.clone());
+            }
+            parameters.push(store::RoutineParameter {
+                name: argument_name.clone().unwrap_or_default(),
+                mode: match mode.as_str() {
+                    "OUT" => store::ParameterMode::Out,
+                    "INOUT" => store::ParameterMode::InOut,
+                    "VARIADIC" => store::ParameterMode::Variadic,
+                    _ => store::ParameterMode::In,
+                },
+                r#type: argument_type.clone(),
+            });
+            let mut parts = vec![];
+            // Procedures name the mode of every argument.
+            if mode != "IN" || procedure {
+                parts.push(mode);
+            }
+            if let Some(argument_name) = argument_name {
+                parts.push(display_identifier(&argument_name));
+            }
+            parts.push(argument_type);
+            identity.push(parts.join(" "));
+        }
+
+        Ok((identity.join(", "), types, parameters))
+    }
+
+    // Returns the signatures of the routines of a name, only the ones with the given input
+    // types when set.
+    fn routine_signatures(
+        &self,
+        schema_name: &str,
+        name: &str,
+        types: Option<&[String]>,
+    ) -> Vec<String> {
+        self.routine_arguments
+            .iter()
+            .filter(|(s, signature, routine_types)| {
+                s == schema_name
+                    && signature.split('(').next() == Some(name)
+                    && types.is_none_or(|t| t == routine_types.as_slice())
+            })
+            .map(|(_, signature, _)| signature.clone())
+            .collect()
+    }
+
+    // Drops the routines of a name, only the ones with the given input types when set.
+    fn drop_routine(&mut self, schema_name: &str, name: &str, types: Option<&[String]>) -> bool {
+        let signatures = self.routine_signatures(schema_name, name, types);
+        self.routine_arguments
+            .retain(|(s, signature, _)| s != schema_name || !signatures.contains(signature));
+        if let Some(schema) = self.schemas.iter_mut().find(|s| s.name == schema_name) {
+            schema
+                .functions
+                .retain(|f| !signatures.contains(&f.signature));
+            schema
+                .procedures
+                .retain(|p| !signatures.contains(&p.signature));
+        }
+        !signatures.is_empty()
+    }
+
+    fn create_extension(&mut self, cursor: &mut Cursor) -> Result<(), DBError> {
+        let if_not_exists = cursor.eat_keywords(&["IF", "NOT", "EXISTS"]);
+        let name = cursor.identifier()?;
+        if self.extensions.iter().any(|e| e.name == name) {
+            if if_not_exists {
+                return Ok(());
+            }
+            return Err(DBError::args(format!("extension {name} already exists")));
+        }
+        cursor.eat_keyword("WITH");
+        let (mut schema, _) = self.creation_name(vec![String::new()])?;
+        let mut version = String::new();
+        loop {
+            if cursor.eat_keyword("SCHEMA") {
+                schema = cursor.identifier()?;
+            } else if cursor.eat_keyword("VERSION") {
+                version = match cursor.next() {
+                    Some(Token::Str(v)) | Some(Token::Word(v)) | Some(Token::Number(v)) => v,
+                    _ => return Err(cursor.error("expected an extension version")),
+                };
+            } else if !cursor.eat_keyword("CASCADE") {
+                break;
+            }
+        }
+        self.extensions.push(store::ExtensionMetadata {
+            name,
+            schema,
+            version,
+            description: String::new(),
+        });
+
+        Ok(())
+    }
+
+    fn create_server(&mut self, cursor: &mut Cursor) -> Result<(), DBError> {
+        cursor.eat_keywords(&["IF", "NOT", "EXISTS"]);
+        let name = cursor.identifier()?;
+        let mut database = String::new();
+        while !cursor.at_end() {
+            if cursor.eat_keyword("OPTIONS") {
+                cursor.expect_punct('(')?;
+                loop {
+                    let option = cursor.identifier()?;
+                    let value = cursor.string()?;
+                    if option == "dbname" {
+                        database = value;
+                    }
+                    if !cursor.eat_punct(',') {
+                        break;
+                    }
+                }
+                cursor.expect_punct(')')?;
+            } else {
+                cursor.next();
+            }
+        }
+        self.servers.retain(|(s, _)| *s != name);
+        self.servers.push((name, database));
+
+        Ok(())
+    }
+
+    fn alter_table(&mut self, cursor: &mut Cursor) -> Result<(), DBError> {
+        let if_exists = cursor.eat_keywords(&["IF", "EXISTS"]);
+        cursor.eat_keyword("ONLY");
+        let parts = cursor.object_name()?;
+        let Some((schema, table)) = self.resolve_table(parts.clone()) else {
+            let external = self.resolve(parts.clone(), |s, n| {
+                s.external_tables.iter().any(|t| t.name == n)
+            });
+            if if_exists || external.is_some() {
+                return Ok(());
+            }
+            return Err(DBError::args(format!(
+                "table {} does not exist",
+                parts.join(".")
+            )));
+        };
+
+        if cursor.eat_keyword("RENAME") {
+            if cursor.eat_keyword("TO") {
+                let new = cursor.identifier()?;
+                let table_metadata = self.table_mut(&schema, &table)?;
+                let (old, renamed) = (
+                    qualified_name(&schema, &table),
+                    qualified_name(&schema, &new),
+                );
+                for index in table_metadata.indexes.iter_mut() {
+                    index.definition = index.definition.replacen(
+                        &format!(" ON {old} USING "),
+                        &format!(" ON {renamed} USING "),
+                        1,
+                    );
+                }
+                table_metadata.name = new.clone();
+                for partition in self.partitions.iter_mut() {
+                    if partition.schema == schema && partition.name == table {
+                        partition.name = new.clone();
+                    }
+                    if partition.parent_schema == schema && partition.parent == table {
+                        partition.parent = new.clone();
+                    }
+                }
+            } else if cursor.eat_keyword("CONSTRAINT") {
+                let old = cursor.identifier()?;
+                cursor.expect_keyword("TO")?;
+                let new = cursor.identifier()?;
+                let table_metadata = self.table_mut(&schema, &table)?;
+                for index in table_metadata.indexes.iter_mut().filter(|i| i.name == old) {
+                    index.definition = index.definition.replacen(
+                        &format!("INDEX {} ON", display_identifier(&old)),
+                        &format!("INDEX {} ON", display_identifier(&new)),
+                        1,
+                    );
+                    index.name = new.clone();
+                }
+                for fk in table_metadata
+                    .foreign_keys
+                    .iter_mut()
+                    .filter(|f| f.name == old)
+                {
+                    fk.name = new.clone();
+                }
+            } else {
+                cursor.eat_keyword("COLUMN");
+                let old = cursor.identifier()?;
+                cursor.expect_keyword("TO")?;
+                let new = cursor.identifier()?;
+                let table_metadata = self.table_mut(&schema, &table)?;
+                for column in table_metadata.columns.iter_mut().filter(|c| c.name == old) {
+                    column.name = new.clone();
+                }
+            }
+            return Ok(());
+        }
+
+        loop {
+            if cursor.eat_keyword("ADD") {
+                let is_constraint = [
+                    "CONSTRAINT",
+                    "PRIMARY",
+                    "UNIQUE",
+                    "FOREIGN",
+                    "CHECK",
+                    "EXCLUDE",
+                ]
+                .iter()
+                .any(|k| cursor.is_keyword(k));
+                if is_constraint {
+                    if let Some(constraint) = table_constraint(cursor)? {
+                        self.add_constraint(&schema, &table, constraint)?;
+                    }
+                } else {
+                    cursor.eat_keyword("COLUMN");
+                    let if_not_exists = cursor.eat_keywords(&["IF", "NOT", "EXISTS"]);
+                    let mut constraints = vec![];
+                    let mut column =
+                        self.parse_column(cursor, &schema, &table, &mut constraints)?;
+                    let table_metadata = self.table_mut(&schema, &table)?;
+                    if table_metadata.columns.iter().any(|c| c.name == column.name) {
+                        if !if_not_exists {
+                            return Err(DBError::args(format!(
+                                "column {} already exists",
+                                column.name
+                            )));
+                        }
+                    } else {
+                        column.position = table_metadata
+                            .columns
+                            .iter()
+                            .map(|c| c.position)
+                            .max()
+                            .unwrap_or(0)
+                            + 1;
+                        table_metadata.columns.push(column);
+                        for constraint in constraints {
+                            self.add_constraint(&schema, &table, constraint)?;
+                        }
+                    }
+                }
+            } else if cursor.eat_keyword("DROP") {
+                if cursor.eat_keyword("CONSTRAINT") {
+                    cursor.eat_keywords(&["IF", "EXISTS"]);
+                    let name = cursor.identifier()?;
+                    let table_metadata = self.table_mut(&schema, &table)?;
+                    table_metadata.indexes.retain(|i| {
+                        i.name != name || !(i.primary || i.unique) || i.definition.is_empty()
+                    });
+                    table_metadata.foreign_keys.retain(|f| f.name != name);
+                } else {
+                    cursor.eat_keyword("COLUMN");
+                    cursor.eat_keywords(&["IF", "EXISTS"]);
+                    let name = cursor.identifier()?;
+                    let table_metadata = self.table_mut(&schema, &table)?;
+                    table_metadata.columns.retain(|c| c.name != name);
+                    let display = display_identifier(&name);
+                    table_metadata.indexes.retain(|i| {
+                        !i.expressions.contains(&display) && !i.include_columns.contains(&display)
+                    });
+                    table_metadata
+                        .foreign_keys
+                        .retain(|f| !f.columns.contains(&name));
+                }
+                if !cursor.eat_keyword("CASCADE") {
+                    cursor.eat_keyword("RESTRICT");
+                }
+            } else if cursor.eat_keyword("ALTER") {
+                cursor.eat_keyword("COLUMN");
+                let name = cursor.identifier()?;
+                self.alter_column(cursor, &schema, &table, &name)?;
+            } else if cursor.eat_keywords(&["OWNER", "TO"]) {
+                let owner = cursor.identifier()?;
+                self.table_mut(&schema, &table)?.owner = owner;
+            } else if cursor.is_keyword("ENABLE") || cursor.is_keyword("DISABLE") {
+                let enabled = cursor.eat_keyword("ENABLE");
+                cursor.eat_keyword("DISABLE");
+                if !cursor.eat_keyword("REPLICA") {
+                    cursor.eat_keyword("ALWAYS");
+                }
+                if cursor.eat_keyword("TRIGGER") {
+                    // ALL and USER name every trigger, the internal ones are not parsed.
+                    let name = if cursor.eat_keyword("ALL") || cursor.eat_keyword("USER") {
+                        None
+                    } else {
+                        Some(cursor.identifier()?)
+                    };
+                    let table_metadata = self.table_mut(&schema, &table)?;
+                    for trigger in table_metadata
+                        .triggers
+                        .iter_mut()
+                        .filter(|t| name.as_ref().is_none_or(|n| *n == t.name))
+                    {
+                        trigger.enabled = enabled;
+                    }
+                } else {
+                    cursor.skip_element();
+                }
+            } else if cursor.eat_keywords(&["ATTACH", "PARTITION"]) {
+                let parts = cursor.object_name()?;
+                let (partition_schema, partition) =
+                    self.resolve_table(parts.clone()).ok_or_else(|| {
+                        DBError::args(format!("table {} does not exist", parts.join(".")))
+                    })?;
+                let bound = partition_bound(cursor)?;
+                self.partitions.push(Partition {
+                    schema: partition_schema,
+                    name: partition,
+                    parent_schema: schema.clone(),
+                    parent: table.clone(),
+                    bound,
+                });
+            } else if cursor.eat_keywords(&["DETACH", "PARTITION"]) {
+                if let Some((partition_schema, partition)) =
+                    self.resolve_table(cursor.object_name()?)
+                {
+                    remove(&mut self.partitions, |p| {
+                        p.schema == partition_schema && p.name == partition
+                    });
+                }
+                if !cursor.eat_keyword("CONCURRENTLY") {
+                    cursor.eat_keyword("FINALIZE");
+                }
+            } else if !cursor.at_end() {
+                cursor.skip_element();
+            }
+            if !cursor.eat_punct(',') {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn alter_column(
+        &mut self,
+        cursor: &mut Cursor,
+        schema: &str,
+        table: &str,
+        name: &str,
+    ) -> Result<(), DBError> {
+        let column_type =
+            if cursor.eat_keywords(&["SET", "DATA", "TYPE"]) || cursor.eat_keyword("TYPE") {
+                let type_name = TypeName::from_tokens(&parse_type(cursor, &["COLLATE", "USING"])?);
+                Some(self.column_type(&type_name))
+            } else {
+                None
+            };
+        let collation = if column_type.is_some() && cursor.eat_keyword("COLLATE") {
+            Some(cursor.object_name()?.join("."))
+        } else {
+            None
+        };
+        let default = if column_type.is_none() && cursor.eat_keywords(&["SET", "DEFAULT"]) {
+            Some(parse_default(cursor)?)
+        } else {
+            None
+        };
+
+        let column = self
+            .table_mut(schema, table)?
+            .columns
+            .iter_mut()
+            .find(|c| c.name == name)
+            .ok_or_else(|| DBError::args(format!("column {name} does not exist")))?;
+        if let Some(column_type) = column_type {
+            column.r#type = column_type.r#type;
+            column.collation = collation.unwrap_or_default();
+            if cursor.eat_keyword("USING") {
+                cursor.skip_element();
+            }
+        } else if let Some(default) = default {
+            // The cast of a literal follows the column type, the serial flag is irrelevant.
+            let column_type = ColumnType {
+                r#type: column.r#type.clone(),
+                cast: default_cast(&column.r#type),
+                serial: false,
+            };
+            column.default = default_value(&default, &column_type);
+        } else if cursor.eat_keywords(&["DROP", "DEFAULT"]) {
+            column.default = String::new();
+        } else if cursor.eat_keywords(&["SET", "NOT", "NULL"]) {
+            column.nullable = false;
+        } else if cursor.eat_keywords(&["DROP", "NOT", "NULL"]) {
+            column.nullable = true;
+        } else if cursor.eat_keywords(&["DROP", "IDENTITY"]) {
+            cursor.eat_keywords(&["IF", "EXISTS"]);
+            column.identity_generation = store::IdentityGeneration::UNSPECIFIED;
+        } else if cursor.eat_keyword("ADD") {
+            cursor.expect_keyword("GENERATED")?;
+            column.identity_generation = if cursor.eat_keywords(&["BY", "DEFAULT"]) {
+                store::IdentityGeneration::ByDefault
+            } else {
+                cursor.expect_keyword("ALWAYS")?;
+                store::IdentityGeneration::Always
+            };
+            column.nullable = false;
+            cursor.skip_element();
+        } else {
+            cursor.skip_element();
+        }
+
+        Ok(())
+    }
+
+    fn drop(&mut self, cursor: &mut Cursor) -> Result<(), DBError> {
+        let kind = if cursor.eat_keywords(&["MATERIALIZED", "VIEW"]) {
+            "MATERIALIZED VIEW".to_string()
+        } else if cursor.eat_keywords(&["FOREIGN", "TABLE"]) {
+            "FOREIGN TABLE".to_string()
+        } else {
+            match cursor.next() {
+                Some(Token::Word(w)) => w.to_uppercase(),
+                _ => return Ok(()),
+            }
+        };
+        cursor.eat_keyword("CONCURRENTLY");
+        let if_exists = cursor.eat_keywords(&["IF", "EXISTS"]);
+        loop {
+            let parts = cursor.object_name()?;
+            let object = parts.join(".");
+            let found = match kind.as_str() {
+                "SCHEMA" => remove(&mut self.schemas, |s| s.name == object),
+                "EXTENSION" => remove(&mut self.extensions, |e| e.name == object),
+                "SERVER" => remove(&mut self.servers, |(s, _)| *s == object),
+                "TYPE" | "DOMAIN" => {
+                    let found = self.resolve(parts.clone(), |_, _| true);
+                    match found {
+                        Some((schema, name)) => {
+                            remove(&mut self.types, |(s, n)| *s == schema && *n == name)
+                        }
+                        None => false,
+                    }
+                }
+                "TABLE" => {
+                    // The partitions of a dropped table are dropped with it.
+                    if let Some((schema, table)) = self.resolve_table(parts.clone()) {
+                        remove(&mut self.partitions, |p| {
+                            p.schema == schema && p.name == table
+                        });
+                    }
+                    self.drop_relation(parts, |s| &mut s.tables, |t| &t.name)
+                }
+                "TRIGGER" => {
+                    cursor.expect_keyword("ON")?;
+                    match self.resolve_table(cursor.object_name()?) {
+                        Some((schema, table)) => {
+                            remove(&mut self.table_mut(&schema, &table)?.triggers, |t| {
+                                t.name == object
+                            })
+                        }
+                        None => false,
+                    }
+                }
+                "FOREIGN TABLE" => {
+                    self.drop_relation(parts, |s| &mut s.external_tables, |t| &t.name)
+                }
+                "VIEW" => self.drop_relation(parts, |s| &mut s.views, |v| &v.name),
+                "MATERIALIZED VIEW" => {
+                    self.drop_relation(parts, |s| &mut s.materialized_views, |v| &v.name)
+                }
+                "INDEX" => {
+                    let found = self.resolve(parts, |s, n| {
+                        s.tables
+                            .iter()
+                            .any(|t| t.indexes.iter().any(|i| i.name == n))
+                    });
+                    match found {
+                        Some((schema, name)) => {
+                            for table in &mut self.schema_mut(&schema)?.tables {
+                                table.indexes.retain(|i| i.name != name);
+                            }
+                            true
+                        }
+                        None => false,
+                    }
+                }
+                "FUNCTION" | "PROCEDURE" | "ROUTINE" => {
+                    let types = if cursor.is_punct('(') {
+                        let arguments = cursor.parenthesized()?;
+                        Some(self.routine_arguments(arguments, false)?.1)
+                    } else {
+                        None
+                    };
+                    let found = self.resolve(parts, |s, n| {
+                        s.functions.iter().any(|f| f.name == n)
+                            || s.procedures.iter().any(|p| p.name == n)
+                    });
+                    match found {
+                        Some((schema, name)) => self.drop_routine(&schema, &name, types.as_deref()),
+                        None => false,
+                    }
+                }
+                _ => return Ok(()),
+            };
+            if !found && !if_exists {
+                return Err(DBError::args(format!(
+                    "{} {object} does not exist",
+                    kind.to_lowercase()
+                )));
+            }
+            if !cursor.eat_punct(',') {
+                return Ok(());
+            }
+        }
+    }
+
+    fn drop_relation<T, L, N>(&mut self, parts: Vec<String>, list: L, name_of: N) -> bool
+    where
+        L: Fn(&mut store::SchemaMetadata) -> &mut Vec<T>,
+        N: Fn(&T) -> &String,
+    {
+        let name = parts.last().cloned().unwrap_or_default();
+        let schemas: Vec<String> = match parts.len() {
+            1 => self.search_path.clone(),
+            _ => vec![parts[parts.len() - 2].clone()],
+        };
+        for schema_name in schemas {
+            if let Some(schema) = self.schemas.iter_mut().find(|s| s.name == schema_name) {
+                if remove(list(schema), |item| *name_of(item) == name) {
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    fn comment_on(&mut self, cursor: &mut Cursor) -> Result<(), DBError> {
+        let kind = if cursor.eat_keywords(&["MATERIALIZED", "VIEW"]) {
+            "MATERIALIZED VIEW".to_string()
+        } else {
+            match cursor.next() {
+                Some(Token::Word(w)) => w.to_uppercase(),
+                _ => return Ok(()),
+            }
+        };
+        if ![
+            "TABLE",
+            "COLUMN",
+            "VIEW",
+            "MATERIALIZED VIEW",
+            "INDEX",
+            "SCHEMA",
+            "FUNCTION",
+            "PROCEDURE",
+            "ROUTINE",
+        ]
+        .contains(&kind.as_str())
+        {
+            return Ok(());
+        }
+        let mut parts = cursor.object_name()?;
+        let types = if cursor.is_punct('(') {
+            let arguments = cursor.parenthesized()?;
+            Some(self.routine_arguments(arguments, false)?.1)
+        } else {
+            None
+        };
+        cursor.expect_keyword("IS")?;
+        let comment = if cursor.eat_keyword("NULL") {
+            String::new()
+        } else {
+            cursor.string()?
+        };
+
+        let object = parts.join(".");
+        let missing = || DBError::args(format!("{} {object} does not exist", kind.to_lowercase()));
+        match kind.as_str() {
+            "SCHEMA" => {
+                self.schema_mut(&parts.join("."))?.comment = comment;
+            }
+            "TABLE" => {
+                let (schema, table) = self.resolve_table(parts).ok_or_else(missing)?;
+                self.table_mut(&schema, &table)?.comment = comment;
+            }
+            "COLUMN" => {
+                let column = parts.pop().unwrap_or_default();
+                if let Some((schema, table)) = self.resolve_table(parts.clone()) {
+                    let table = self.table_mut(&schema, &table)?;
+                    let column = table
+                        .columns
+                        .iter_mut()
+                        .find(|c| c.name == column)
+                        .ok_or_else(missing)?;
+                    column.comment = comment;
+                } else if let Some((schema, table)) =
+                    self.resolve(parts, |s, n| s.external_tables.iter().any(|t| t.name == n))
+                {
+                    let schema = self.schema_mut(&schema)?;
+                    let table = schema
+                        .external_tables
+                        .iter_mut()
+                        .find(|t| t.name == table)
+                        .ok_or_else(missing)?;
+                    let column = table
+                        .columns
+                        .iter_mut()
+                        .find(|c| c.name == column)
+                        .ok_or_else(missing)?;
+                    column.comment = comment;
+                }
+            }
+            "VIEW" => {
+                let (schema, name) = self
+                    .resolve(parts, |s, n| s.views.iter().any(|v| v.name == n))
+                    .ok_or_else(missing)?;
+                let schema = self.schema_mut(&schema)?;
+                if let Some(view) = schema.views.iter_mut().find(|v| v.name == name) {
+                    view.comment = comment;
+                }
+            }
+            "MATERIALIZED VIEW" => {
+                let (schema, name) = self
+                    .resolve(parts, |s, n| {
+                        s.materialized_views.iter().any(|v| v.name == n)
+                    })
+                    .ok_or_else(missing)?;
+                let schema = self.schema_mut(&schema)?;
+                if let Some(view) = schema
+                    .materialized_views
+                    .iter_mut()
+                    .find(|v| v.name == name)
+                {
+                    view.comment = comment;
+                }
+            }
+            "FUNCTION" | "PROCEDURE" | "ROUTINE" => {
+                let (schema_name, name) = self
+                    .resolve(parts, |s, n| {
+                        s.functions.iter().any(|f| f.name == n)
+                            || s.procedures.iter().any(|p| p.name == n)
+                    })
+                    .ok_or_else(missing)?;
+                let signatures = self.routine_signatures(&schema_name, &name, types.as_deref());
+                let signature = match signatures.as_slice() {
+                    [signature] => signature,
+                    [] => return Err(missing()),
+                    _ => {
+                        return Err(DBError::args(format!(
+                            "{} name {object} is not unique",
+                            kind.to_lowercase()
+                        )))
+                    }
+                };
+                let schema = self.schema_mut(&schema_name)?;
+                if let Some(function) = schema
+                    .functions
+                    .iter_mut()
+                    .find(|f| f.signature == *signature)
+                {
+                    function.comment = comment;
+                } else if let Some(procedure) = schema
+                    .procedures
+                    .iter_mut()
+                    .find(|p| p.signature == *signature)
+                {
+                    procedure.comment = comment;
+                }
+            }
+            _ => {
+                // Indexes of materialized views are not synced, their comments are dropped.
+                let found = self.resolve(parts, |s, n| {
+                    s.tables
+                        .iter()
+                        .any(|t| t.indexes.iter().any(|i| i.name == n))
+                });
+                if let Some((schema, name)) = found {
+                    for table in &mut self.schema_mut(&schema)?.tables {
+                        for index in table.indexes.iter_mut().filter(|i| i.name == name) {
+                            index.comment = comment.clone();
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn finish(self, database_name: &str) -> store::DatabaseSchemaMetadata {
+        let mut schemas = self.schemas;
+        // The partitions are nested under their parent in the order of the sync.
+        let mut partitions = self.partitions;
+        partitions.sort_by(|a, b| (&a.schema, &a.name).cmp(&(&b.schema, &b.name)));
+        let mut partition_tables = vec![];
+        for partition in &partitions {
+            let schema = schemas.iter_mut().find(|s| s.name == partition.schema);
+            if let Some(tables) = schema.map(|s| &mut s.tables) {
+                if let Some(position) = tables.iter().position(|t| t.name == partition.name) {
+                    partition_tables.push((partition.schema.clone(), tables.remove(position)));
+                }
+            }
+        }
+        for schema in &mut schemas {
+            for table in &mut schema.tables {
+                if let Some(partitioning) = table.partitioning.as_mut() {
+                    // pg_get_indexdef names a partitioned table with ONLY.
+                    let name = qualified_name(&schema.name, &table.name);
+                    for index in table.indexes.iter_mut() {
+                        index.definition = index.definition.replacen(
+                            &format!(" ON {name} USING "),
+                            &format!(" ON ONLY {name} USING "),
+                            1,
+                        );
+                    }
+                    attach_partitions(
+                        partitioning,
+                        &schema.name,
+                        &table.name,
+                        &partitions,
+                        &partition_tables,
+                    );
+                }
+            }
+        }
+        schemas.sort_by(|a, b| a.name.cmp(&b.name));
+        for schema in &mut schemas {
+            schema.tables.sort_by(|a, b| a.name.cmp(&b.name));
+            for table in &mut schema.tables {
+                table.indexes.sort_by(|a, b| a.name.cmp(&b.name));
+                table.foreign_keys.sort_by(|a, b| a.name.cmp(&b.name));
+                table.triggers.sort_by(|a, b| a.name.cmp(&b.name));
+            }
+            schema.external_tables.sort_by(|a, b| a.name.cmp(&b.name));
+            schema.views.sort_by(|a, b| a.name.cmp(&b.name));
+            schema
+                .materialized_views
+                .sort_by(|a, b| a.name.cmp(&b.name));
+            schema
+                .functions
+                .sort_by(|a, b| (&a.name, &a.signature).cmp(&(&b.name, &b.signature)));
+            schema
+                .procedures
+                .sort_by(|a, b| (&a.name, &a.signature).cmp(&(&b.name, &b.signature)));
+        }
+        let mut extensions = self.extensions;
+        extensions.sort_by(|a, b| a.name.cmp(&b.name));
+
+        store::DatabaseSchemaMetadata {
+            name: database_name.to_string(),
+            schemas,
+            character_set: String::new(),
+            collation: String::new(),
+            extensions,
+            datashare: false,
+            service_name: String::new(),
+            owner: String::new(),
+        }
+    }
+}
+
+fn table_metadata(
+    name: &str,
+    columns: Vec<store::ColumnMetadata>,
+    partitioning: Option<store::PartitioningMetadata>,
+) -> store::TableMetadata {
+    store::TableMetadata {
+        name: name.to_string(),
+        columns,
+        indexes: vec![],
+        engine: String::new(),
+        collation: None,
+        row_count: 0,
+        data_size: 0,
+        index_size: 0,
+        data_free: 0,
+        create_options: String::new(),
+        comment: String::new(),
+        foreign_keys: vec![],
+        owner: String::new(),
+        triggers: vec![],
+        partitioning,
+    }
+}
+
+// Adds the partitions of a partitioned table to its partitioning, recursing into the partitions
+// that are partitioned themselves. A partition of a dropped table is dropped with it, so it is
+// never attached.
+fn attach_partitions(
+    partitioning: &mut store::PartitioningMetadata,
+    schema: &str,
+    table: &str,
+    partitions: &[Partition],
+    partition_tables: &[(String, store::TableMetadata)],
+) {
+    for partition in partitions
+        .iter()
+        .filter(|p| p.parent_schema == schema && p.parent == table)
+    {
+        let Some((_, partition_table)) = partition_tables
+            .iter()
+            .find(|(s, t)| *s == partition.schema && t.name == partition.name)
+        else {
+            continue;
+        };
+        let mut subpartitioning = partition_table.partitioning.clone();
+        if let Some(subpartitioning) = subpartitioning.as_mut() {
+            attach_partitions(
+                subpartitioning,
+                &partition.schema,
+                &partition.name,
+                partitions,
+                partition_tables,
+            );
+        }
+        partitioning.partitions.push(store::PartitionMetadata {
+            // A partition may live in another schema than its parent.
+            name: if partition.schema == schema {
+                partition.name.clone()
+            } else {
+                format!("{}.{}", partition.schema, partition.name)
+            },
+            value: partition.bound.clone(),
+            subpartitioning,
+            row_count: 0,
+        });
+    }
+}
+
+// Parses the PARTITION BY clause of a partitioned table, the partitions are attached later.
+// A key of plain columns is written the way pg_get_partkeydef writes it, an expression key is
+// kept as written.
+fn partition_by(cursor: &mut Cursor) -> Result<Option<store::PartitioningMetadata>, DBError> {
+    if !cursor.eat_keywords(&["PARTITION", "BY"]) {
+        return Ok(None);
+    }
+    let strategy = if cursor.eat_keyword("RANGE") {
+        store::PartitionStrategy::Range
+    } else if cursor.eat_keyword("LIST") {
+        store::PartitionStrategy::List
+    } else {
+        cursor.expect_keyword("HASH")?;
+        store::PartitionStrategy::Hash
+    };
+    let key = cursor.parenthesized()?;
+    let mut key_cursor = Cursor::new(key, Dialect::PostgreSQL)?;
+    let mut columns = vec![];
+    while let Ok(column) = key_cursor.identifier() {
+        columns.push(display_identifier(&column));
+        if !key_cursor.eat_punct(',') {
+            break;
+        }
+    }
+    let expression = if key_cursor.at_end() {
+        columns.join(", ")
+    } else {
+        key.to_string()
+    };
+
+    Ok(Some(store::PartitioningMetadata {
+        strategy,
+        expression,
+        partitions: vec![],
+    }))
+}
+
+// Parses the bound of a partition and returns it in the form of pg_get_expr, the bound values
+// are kept as written.
+fn partition_bound(cursor: &mut Cursor) -> Result<String, DBError> {
+    if cursor.eat_keyword("DEFAULT") {
+        return Ok("DEFAULT".to_string());
+    }
+    cursor.expect_keyword("FOR")?;
+    cursor.expect_keyword("VALUES")?;
+    if cursor.eat_keyword("IN") {
+        Ok(format!("FOR VALUES IN ({})", bound_values(cursor)?))
+    } else if cursor.eat_keyword("FROM") {
+        let from = bound_values(cursor)?;
+        cursor.expect_keyword("TO")?;
+        Ok(format!(
+            "FOR VALUES FROM ({from}) TO ({})",
+            bound_values(cursor)?
+        ))
+    } else {
+        cursor.expect_keyword("WITH")?;
+        cursor.expect_punct('(')?;
+        cursor.expect_keyword("MODULUS")?;
+        let modulus = match cursor.next() {
+            Some(Token::Number(n)) => n,
+            _ => return Err(cursor.error("expected a modulus")),
+        };
+        cursor.expect_punct(',')?;
+        cursor.expect_keyword("REMAINDER")?;
+        let remainder = match cursor.next() {
+            Some(Token::Number(n)) => n,
+            _ => return Err(cursor.error("expected a remainder")),
+        };
+        cursor.expect_punct(')')?;
+        Ok(format!(
+            "FOR VALUES WITH (modulus {modulus}, remainder {remainder})"
+        ))
+    }
+}
+
+// Parses a parenthesized list of partition bound values, MINVALUE and MAXVALUE are upper cased.
+fn bound_values(cursor: &mut Cursor) -> Result<String, DBError> {
+    cursor.expect_punct('(')?;
+    let mut values = vec![];
+    loop {
+        let start = cursor.offset();
+        cursor.skip_element();
+        let value = cursor.text_from(start);
+        if value.eq_ignore_ascii_case("MINVALUE") || value.eq_ignore_ascii_case("MAXVALUE") {
+            values.push(value.to_uppercase());
+        } else {
+            values.push(value.to_string());
+        }
+        if !cursor.eat_punct(',') {
+            break;
+        }
+    }
+    cursor.expect_punct(')')?;
+    Ok(values.join(", "))
+}
+
+fn remove<T>(items: &mut Vec<T>, matches: impl Fn(&T) -> bool) -> bool {
+    let count = items.len();
+    items.retain(|item| !matches(item));
+    items.len() != count
+}
+
+// Parses a table constraint, CHECK and EXCLUDE constraints are skipped.
+fn table_constraint(cursor: &mut Cursor) -> Result<Option<Constraint>, DBError> {
+    let name = if cursor.eat_keyword("CONSTRAINT") {
+        Some(cursor.identifier()?)
+    } else {
+        None
+    };
+    let constraint = if cursor.eat_keywords(&["PRIMARY", "KEY"]) {
+        let columns = cursor.identifier_list()?;
+        let include = include_columns(cursor)?;
+        Some(Constraint::PrimaryKey {
+            name,
+            columns,
+            include,
+        })
+    } else if cursor.eat_keyword("UNIQUE") {
+        if cursor.eat_keyword("NULLS") {
+            cursor.eat_keyword("NOT");
+            cursor.expect_keyword("DISTINCT")?;
+        }
+        let columns = cursor.identifier_list()?;
+        let include = include_columns(cursor)?;
+        Some(Constraint::Unique {
+            name,
+            columns,
+            include,
+        })
+    } else if cursor.eat_keywords(&["FOREIGN", "KEY"]) {
+        let columns = cursor.identifier_list()?;
+        cursor.expect_keyword("REFERENCES")?;
+        Some(Constraint::ForeignKey {
+            name,
+            columns,
+            reference: reference(cursor)?,
+        })
+    } else if cursor.eat_keyword("CHECK") || cursor.eat_keyword("EXCLUDE") {
+        cursor.skip_element();
+        None
+    } else {
+        return Err(cursor.error("expected a table constraint"));
+    };
+    while !cursor.at_end() && !cursor.is_punct(',') && !cursor.is_punct(')') {
+        if !constraint_deferrability(cursor) {
+            // Index parameters such as WITH (...) and USING INDEX TABLESPACE.
+            cursor.skip_element();
+        }
+    }
+
+    Ok(constraint)
+}
+
+fn include_columns(cursor: &mut Cursor) -> Result<Vec<String>, DBError> {
+    if cursor.eat_keyword("INCLUDE") {
+        cursor.identifier_list()
+    } else {
+        Ok(vec![])
+    }
+}
+
+fn constraint_deferrability(cursor: &mut Cursor) -> bool {
+    cursor.eat_keyword("DEFERRABLE")
+        || cursor.eat_keywords(&["NOT", "DEFERRABLE"])
+        || cursor.eat_keywords(&["INITIALLY", "DEFERRED"])
+        || cursor.eat_keywords(&["INITIALLY", "IMMEDIATE"])
+        || cursor.eat_keywords(&["NOT", "VALID"])
+}
+
+fn reference(cursor: &mut Cursor) -> Result<Reference, DBError> {
+    let table = cursor.object_name()?;
+    let columns = if cursor.is_punct('(') {
+        cursor.identifier_list()?
+    } else {
+        vec![]
+    };
+    let mut reference = Reference {
+        table,
+        columns,
+        match_type: "SIMPLE".to_string(),
+        on_delete: "NO ACTION".to_string(),
+        on_update: "NO ACTION".to_string(),
+    };
+    loop {
+        if cursor.eat_keyword("MATCH") {
+            reference.match_type = cursor.identifier()?.to_uppercase();
+        } else if cursor.eat_keywords(&["ON", "DELETE"]) {
+            reference.on_delete = referential_action(cursor)?;
+        } else if cursor.eat_keywords(&["ON", "UPDATE"]) {
+            reference.on_update = referential_action(cursor)?;
+        } else {
+            return Ok(reference);
+        }
+    }
+}
+
+fn referential_action(cursor: &mut Cursor) -> Result<String, DBError> {
+    for action in [
+        &["RESTRICT"][..],
+        &["CASCADE"],
+        &["SET", "NULL"],
+        &["SET", "DEFAULT"],
+        &["NO", "ACTION"],
+    ] {
+        if cursor.eat_keywords(action) {
+            if action.len() > 1 && action[0] == "SET" && cursor.is_punct('(') {
+                // The column list of SET NULL (columns) is not part of the synced metadata.
+                cursor.parenthesized()?;
+            }
+            return Ok(action.join(" "));
+        }
+    }
+    Err(cursor.error("expected a referential action"))
+}
+
+// Returns the default of a column in the form information_schema reports it.
+fn default_value(value: &DefaultValue, column_type: &ColumnType) -> String {
+    let quoted = |s: &str| format!("'{}'::{}", s.replace('\'', "''"), column_type.cast);
+    match value {
+        DefaultValue::Null => String::new(),
+        DefaultValue::Bool(b) => b.to_string(),
+        DefaultValue::Number(n) => {
+            // A constant is typed by the literal, the implicit cast to the column is hidden.
+            let integer = !n.contains(['.', 'e', 'E']);
+            let negative = n.starts_with('-');
+            if integer && n.parse::<i32>().is_ok() {
+                if negative {
+                    format!("'{n}'::integer")
+                } else {
+                    n.clone()
+                }
+            } else if integer && n.parse::<i64>().is_ok() {
+                format!("'{n}'::bigint")
+            } else if negative {
+                format!("'{n}'::numeric")
+            } else {
+                n.clone()
+            }
+        }
+        DefaultValue::Str(s) => match column_type.cast.as_str() {
+            "integer" => match s.trim().parse::<i32>() {
+                Ok(n) if n >= 0 => n.to_string(),
+                _ => quoted(s.trim()),
+            },
+            "numeric" if !s.starts_with('-') && s.parse::<f64>().is_ok() => s.clone(),
+            "boolean" => {
+                let truthy = ["t", "true", "y", "yes", "on", "1"];
+                truthy
+                    .contains(&s.trim().to_lowercase().as_str())
+                    .to_string()
+            }
+            _ => quoted(s),
+        },
+        DefaultValue::CurrentTimestamp(None) => "CURRENT_TIMESTAMP".to_string(),
+        DefaultValue::CurrentTimestamp(Some(precision)) => {
+            format!("CURRENT_TIMESTAMP({precision})")
+        }
+        DefaultValue::Expr(expression) => normalize_expression(expression),
+    }
+}
+
+// Returns the cast of literals for a type reported by information_schema.
+fn default_cast(column_type: &str) -> String {
+    let base = column_type.split('(').next().unwrap_or_default();
+    match base {
+        "character" => "bpchar".to_string(),
+        base => match base.split_once('.') {
+            Some((PUBLIC_SCHEMA, name)) => name.to_string(),
+            _ => base.to_string(),
+        },
+    }
+}
+
+// Folds the names of unquoted function calls and SQL value functions the way the server
+// deparses them. Other expressions are kept as written.
+fn normalize_expression(expression: &str) -> String {
+    let upper = expression.to_uppercase();
+    let keyword = upper.split('(').next().unwrap_or_default();
+    if VALUE_FUNCTIONS.contains(&keyword) {
+        return upper;
+    }
+    match expression.split_once('(') {
+        Some((function, arguments))
+            if function
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.') =>
+        {
+            format!("{}({arguments}", function.to_lowercase())
+        }
+        _ => expression.to_string(),
+    }
+}
+
+// Returns the internal name of a builtin type, array columns report it prefixed by `_`.
+fn udt_name(r#type: &str) -> &str {
+    match r#type {
+        "smallint" => "int2",
+        "integer" => "int4",
+        "bigint" => "int8",
+        "real" => "float4",
+        "double precision" => "float8",
+        "boolean" => "bool",
+        "character varying" => "varchar",
+        "character" => "bpchar",
+        "bit varying" => "varbit",
+        "timestamp without time zone" => "timestamp",
+        "timestamp with time zone" => "timestamptz",
+        "time without time zone" => "time",
+        "time with time zone" => "timetz",
+        r#type => r#type,
+    }
+}
+
+// Reports whether two words start a multi word type rather than an argument name and a type.
+fn is_type_prefix(first: &str, second: &str) -> bool {
+    let pair = format!("{} {}", first.to_lowercase(), second.to_lowercase());
+    [
+        "double precision",
+        "character varying",
+        "char varying",
+        "bit varying",
+        "timestamp with",
+        "timestamp without",
+        "time with",
+        "time without",
+    ]
+    .contains(&pair.as_str())
+}
+
+fn key_index(
+    schema: &str,
+    table: &str,
+    name: &str,
+    primary: bool,
+    columns: &[String],
+    include: &[String],
+) -> store::IndexMetadata {
+    let expressions: Vec<String> = columns.iter().map(|c| display_identifier(c)).collect();
+    let include_columns: Vec<String> = include.iter().map(|c| display_identifier(c)).collect();
+    store::IndexMetadata {
+        name: name.to_string(),
+        key_length: vec![-1; expressions.len()],
+        definition: index_definition(
+            schema,
+            table,
+            name,
+            true,
+            "btree",
+            &expressions,
+            include,
+            "",
+        ),
+        expressions,
+        r#type: "btree".to_string(),
+        unique: true,
+        primary,
+        visible: true,
+        comment: String::new(),
+        include_columns,
+        predicate: String::new(),
+    }
+}
+
+// Returns the index definition the way pg_get_indexdef prints it.
+#[allow(clippy::too_many_arguments)]
+fn index_definition(
+    schema: &str,
+    table: &str,
+    name: &str,
+    unique: bool,
+    method: &str,
+    keys: &[String],
+    include: &[String],
+    predicate: &str,
+) -> String {
+    let mut definition = format!(
+        "CREATE {}INDEX {} ON {} USING {method} ({})",
+        if unique { "UNIQUE " } else { "" },
+        display_identifier(name),
+        qualified_name(schema, table),
+        keys.join(", ")
+    );
+    if !include.is_empty() {
+        let include: Vec<String> = include.iter().map(|c| display_identifier(c)).collect();
+        definition.push_str(&format!(" INCLUDE ({})", include.join(", ")));
+    }
+    if !predicate.is_empty() {
+        definition.push_str(&format!(" WHERE ({predicate})"));
+    }
+    definition
+}
+
+// Collapses the whitespace of a partial index predicate and removes enclosing parentheses.
+fn normalize_predicate(predicate: &str) -> String {
+    let mut predicate = predicate
+        .split_whitespace()
+        .collect::<Vec<&str>>()
+        .join(" ");
+    while predicate.starts_with('(') && predicate.ends_with(')') {
+        let inner = &predicate[1..predicate.len() - 1];
+        let mut depth = 0;
+        let balanced = inner.chars().all(|c| {
+            match c {
+                '(' => depth += 1,
+                ')' => depth -= 1,
+                _ => {}
+            }
+            depth >= 0
+        });
+        if !balanced {
+            break;
+        }
+        predicate = inner.trim().to_string();
+    }
+    predicate
+}
+
+// Removes the trailing WITH [NO] DATA and WITH CHECK OPTION clauses of a view query.
+fn strip_view_suffix(query: &str) -> &str {
+    let upper = query.to_uppercase();
+    for suffix in [
+        "WITH NO DATA",
+        "WITH DATA",
+        "WITH CHECK OPTION",
+        "WITH LOCAL CHECK OPTION",
+        "WITH CASCADED CHECK OPTION",
+    ] {
+        let words: Vec<&str> = upper.split_whitespace().collect();
+        let suffix_words: Vec<&str> = suffix.split_whitespace().collect();
+        if words.ends_with(&suffix_words) {
+            if let Some(position) = upper.rfind("WITH") {
+                return query[..position].trim_end();
+            }
+        }
+    }
+    query
+}
+
+// Returns a name qualified by its schema unless the schema is public, which the driver
+// connection has in its search path.
+fn qualified_name(schema: &str, name: &str) -> String {
+    if schema == PUBLIC_SCHEMA {
+        display_identifier(name)
+    } else {
+        format!(
+            "{}.{}",
+            display_identifier(schema),
+            display_identifier(name)
+        )
+    }
+}
+
+// Quotes an identifier when quote_ident would.
+fn display_identifier(name: &str) -> String {
+    let plain = name.starts_with(|c: char| c.is_ascii_lowercase() || c == '_')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '$')
+        && !RESERVED_KEYWORDS.contains(&name);
+    if plain {
+        name.to_string()
+    } else {
+        format!("\"{}\"", name.replace('"', "\"\""))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::parse_database;
+    use crate::db::store::{
+        IdentityGeneration, ParameterMode, PartitionStrategy, SecurityType, Volatility,
+    };
+
+    #[test]
+    fn test_parse_fixture() {
+        let sql = std::fs::read_to_string("tests/fixtures/postgres_schema.sql").unwrap();
+        let database = parse_database("test_db", &sql).unwrap();
+        let names: Vec<&str> = database.schemas.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["inventory", "public", "sales"]);
+        let extensions: Vec<&str> = database
+            .extensions
+            .iter()
+            .map(|e| e.name.as_str())
+            .collect();
+        assert_eq!(extensions, vec!["pg_trgm", "postgres_fdw"]);
+
+        let inventory = &database.schemas[0];
+        let products = &inventory.tables[0];
+        let partial = products
+            .indexes
+            .iter()
+            .find(|i| i.name == "idx_products_in_stock")
+            .unwrap();
+        assert_eq!(partial.predicate, "stock_quantity > 0");
+        assert_eq!(partial.include_columns, vec!["price"]);
+        assert_eq!(
+            partial.definition,
+            "CREATE INDEX idx_products_in_stock ON inventory.products USING btree (category) INCLUDE (price) WHERE (stock_quantity > 0)"
+        );
+        let trigger = &products.triggers[0];
+        assert_eq!(trigger.name, "trg_products_trim_name");
+        assert_eq!(trigger.timing, "BEFORE");
+        assert_eq!(trigger.events, vec!["INSERT", "UPDATE"]);
+        assert!(trigger.for_each_row && trigger.enabled);
+        assert_eq!(inventory.external_tables.len(), 1);
+        assert_eq!(inventory.external_tables[0].name, "remote_products");
+        assert_eq!(
+            inventory.external_tables[0].external_server_name,
+            "loopback"
+        );
+
+        let sales = &database.schemas[2];
+        let customers = sales.tables.iter().find(|t| t.name == "customers").unwrap();
+        assert_eq!(customers.comment, "Customer information");
+        assert_eq!(
+            customers.columns[0].default,
+            "nextval('sales.customers_customer_id_seq'::regclass)"
+        );
+        assert!(!customers.columns[0].nullable);
+        assert_eq!(customers.columns[1].r#type, "character varying(255)");
+        assert_eq!(customers.columns[7].default, "'USA'::character varying");
+        assert!(customers
+            .indexes
+            .iter()
+            .any(|i| i.name == "customers_email_key" && i.unique && !i.primary));
+
+        let orders = sales.tables.iter().find(|t| t.name == "orders").unwrap();
+        let status = orders.columns.iter().find(|c| c.name == "status").unwrap();
+        assert_eq!(status.r#type, "sales.order_status");
+        assert_eq!(status.default, "'pending'::sales.order_status");
+        assert_eq!(orders.foreign_keys[0].name, "fk_orders_customer");
+        assert_eq!(orders.foreign_keys[0].match_type, "SIMPLE");
+
+        // Partitions are nested under their parent rather than listed as tables.
+        assert_eq!(sales.tables.len(), 5);
+        let order_events = sales.tables.iter().find(|t| t.name == "order_events");
+        assert_eq!(
+            order_events.unwrap().indexes[0].definition,
+            "CREATE INDEX idx_order_events_event ON ONLY sales.order_events USING btree (event_id)"
+        );
+        let partitioning = order_events.unwrap().partitioning.as_ref().unwrap();
+        assert_eq!(partitioning.strategy, PartitionStrategy::Range);
+        assert_eq!(partitioning.expression, "created_at");
+        let partitions: Vec<(&str, &str)> = partitioning
+            .partitions
+            .iter()
+            .map(|p| (p.name.as_str(), p.value.as_str()))
+            .collect();
+        assert_eq!(
+            partitions,
+            vec![
+                (
+                    "order_events_2024",
+                    "FOR VALUES FROM ('2024-01-01') TO ('2025-01-01')"
+                ),
+                (
+                    "order_events_2025",
+                    "FOR VALUES FROM ('2025-01-01') TO ('2026-01-01')"
+                ),
+                ("order_events_default", "DEFAULT"),
+            ]
+        );
+        let subpartitioning = partitioning.partitions[1].subpartitioning.as_ref().unwrap();
+        assert_eq!(subpartitioning.strategy, PartitionStrategy::Hash);
+        assert_eq!(subpartitioning.expression, "event_id");
+        assert_eq!(
+            subpartitioning.partitions[1].value,
+            "FOR VALUES WITH (modulus 2, remainder 1)"
+        );
+
+        let signatures: Vec<&str> = sales
+            .functions
+            .iter()
+            .map(|f| f.signature.as_str())
+            .collect();
+        assert_eq!(
+            signatures,
+            vec![
+                "calculate_order_total(order_id_param integer)",
+                "calculate_order_total(order_id_param integer, apply_discount boolean)",
+            ]
+        );
+        let total = &sales.functions[0];
+        assert_eq!(total.parameters[0].name, "order_id_param");
+        assert_eq!(total.return_type, "numeric");
+        assert_eq!(total.language, "plpgsql");
+        assert_eq!(total.volatility, Volatility::Stable);
+        assert!(!total.comment.is_empty());
+        assert_eq!(
+            sales.procedures[0].signature,
+            "cancel_order(IN order_id_param integer)"
+        );
+        assert_eq!(sales.views[0].name, "customer_order_summary");
+        assert_eq!(sales.materialized_views[0].name, "monthly_sales");
+    }
+
+    #[test]
+    fn test_parse_partitions() {
+        let sql = "CREATE SCHEMA app;
+            CREATE SCHEMA archive;
+            CREATE TABLE app.events (id int, kind text, \"Day\" date)
+                PARTITION BY RANGE (id, \"Day\");
+            CREATE TABLE archive.events_old PARTITION OF app.events
+                FOR VALUES FROM (minvalue, MINVALUE) TO (100, '2024-01-01');
+            CREATE TABLE app.events_new PARTITION OF app.events (PRIMARY KEY (id, \"Day\"))
+                FOR VALUES FROM (100, '2024-01-01') TO (MAXVALUE, MAXVALUE)
+                PARTITION BY LIST (lower(kind));
+            CREATE TABLE app.events_new_a PARTITION OF app.events_new FOR VALUES IN ('a', 'b');
+            CREATE TABLE app.events_gone PARTITION OF app.events_new DEFAULT;
+            DROP TABLE app.events_gone;
+            CREATE TABLE app.events_dumped (id int, kind text, \"Day\" date);
+            ALTER TABLE ONLY app.events_new ATTACH PARTITION app.events_dumped FOR VALUES IN ('c');
+            CREATE TABLE app.events_detached (id int, kind text, \"Day\" date);
+            ALTER TABLE app.events_new ATTACH PARTITION app.events_detached FOR VALUES IN ('d');
+            ALTER TABLE app.events_new DETACH PARTITION app.events_detached;
+            ALTER TABLE app.events_new_a RENAME TO events_new_ab;
+            CREATE TABLE app.logs (id int) PARTITION BY HASH (id);
+            CREATE TABLE app.logs_0 PARTITION OF app.logs FOR VALUES WITH (MODULUS 2, REMAINDER 0);
+            DROP TABLE app.logs;";
+        let database = parse_database("db", sql).unwrap();
+        let app = &database.schemas[0];
+        let tables: Vec<&str> = app.tables.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(tables, vec!["events", "events_detached"]);
+        assert!(database.schemas[1].tables.is_empty());
+
+        let partitioning = app.tables[0].partitioning.as_ref().unwrap();
+        assert_eq!(partitioning.expression, "id, \"Day\"");
+        let partitions: Vec<(&str, &str)> = partitioning
+            .partitions
+            .iter()
+            .map(|p| (p.name.as_str(), p.value.as_str()))
+            .collect();
+        assert_eq!(
+            partitions,
+            vec![
+                (
+                    "events_new",
+                    "FOR VALUES FROM (100, '2024-01-01') TO (MAXVALUE, MAXVALUE)"
+                ),
+                (
+                    "archive.events_old",
+                    "FOR VALUES FROM (MINVALUE, MINVALUE) TO (100, '2024-01-01')"
+                ),
+            ]
+        );
+        let subpartitioning = partitioning.partitions[0].subpartitioning.as_ref().unwrap();
+        assert_eq!(subpartitioning.strategy, PartitionStrategy::List);
+        assert_eq!(subpartitioning.expression, "lower(kind)");
+        let subpartitions: Vec<(&str, &str)> = subpartitioning
+            .partitions
+            .iter()
+            .map(|p| (p.name.as_str(), p.value.as_str()))
+            .collect();
+        assert_eq!(
+            subpartitions,
+            vec![
+                ("events_dumped", "FOR VALUES IN ('c')"),
+                ("events_new_ab", "FOR VALUES IN ('a', 'b')"),
+            ]
+        );
+        assert!(partitioning.partitions[1].subpartitioning.is_none());
+
+        assert!(parse_database("db", "CREATE TABLE t PARTITION OF missing DEFAULT").is_err());
+    }
+
+    #[test]
+    fn test_parse_statements() {
+        let sql = "CREATE SCHEMA app;
+            SET search_path TO app, public;
+            CREATE TABLE parent (id bigint GENERATED ALWAYS AS IDENTITY PRIMARY KEY, tags text[]);
+            CREATE TABLE child (
+                id int,
+                parent_id bigint REFERENCES parent ON DELETE CASCADE,
+                \"user\" varchar(20) DEFAULT 'x''y',
+                amount integer DEFAULT -1
+            );
+            CREATE INDEX ON child (parent_id, \"user\");
+            ALTER TABLE child RENAME TO kid;
+            CREATE FUNCTION f(a int) RETURNS int AS 'SELECT a' LANGUAGE sql;
+            CREATE FUNCTION f(a text) RETURNS int AS 'SELECT 1' LANGUAGE sql;
+            DROP FUNCTION f(integer);
+            COMMENT ON FUNCTION f IS 'Counts';
+            CREATE FUNCTION g(OUT x int, OUT y text) LANGUAGE sql STABLE SECURITY DEFINER
+                AS 'SELECT 1, ''a''';
+            CREATE FUNCTION h(n numeric(10, 2)) RETURNS TABLE(id int) IMMUTABLE
+                RETURN 1;
+            CREATE TRIGGER touch AFTER UPDATE OR DELETE ON kid
+                FOR EACH STATEMENT EXECUTE FUNCTION f();
+            CREATE TRIGGER audit AFTER INSERT ON kid EXECUTE FUNCTION f();
+            ALTER TABLE kid DISABLE TRIGGER touch;
+            DROP TRIGGER audit ON kid;";
+        let database = parse_database("db", sql).unwrap();
+        let app = database.schemas.iter().find(|s| s.name == "app").unwrap();
+
+        let parent = &app.tables[1];
+        assert_eq!(
+            parent.columns[0].identity_generation,
+            IdentityGeneration::Always
+        );
+        assert_eq!(parent.columns[0].default, "");
+        assert_eq!(parent.columns[1].r#type, "_text");
+
+        let kid = &app.tables[0];
+        assert_eq!(kid.name, "kid");
+        assert_eq!(kid.columns[2].default, "'x''y'::character varying");
+        assert_eq!(kid.columns[3].default, "'-1'::integer");
+        assert_eq!(kid.foreign_keys[0].name, "child_parent_id_fkey");
+        assert_eq!(kid.triggers.len(), 1);
+        assert_eq!(kid.triggers[0].events, vec!["DELETE", "UPDATE"]);
+        assert!(!kid.triggers[0].for_each_row && !kid.triggers[0].enabled);
+        assert_eq!(kid.foreign_keys[0].referenced_columns, vec!["id"]);
+        assert_eq!(
+            kid.indexes[0].definition,
+            "CREATE INDEX child_parent_id_user_idx ON app.kid USING btree (parent_id, \"user\")"
+        );
+
+        let signatures: Vec<&str> = app.functions.iter().map(|f| f.signature.as_str()).collect();
+        assert_eq!(
+            signatures,
+            vec!["f(a text)", "g(OUT x integer, OUT y text)", "h(n numeric)"]
+        );
+        let f = &app.functions[0];
+        assert_eq!(f.return_type, "integer");
+        assert_eq!(f.comment, "Counts");
+        let g = &app.functions[1];
+        assert_eq!(g.return_type, "record");
+        assert_eq!(g.parameters[1].mode, ParameterMode::Out);
+        assert_eq!(g.volatility, Volatility::Stable);
+        assert_eq!(g.security, SecurityType::Definer);
+        let h = &app.functions[2];
+        assert_eq!(h.return_type, "TABLE(id integer)");
+        assert_eq!(h.parameters[1].mode, ParameterMode::Table);
+        assert_eq!(h.volatility, Volatility::Immutable);
+        assert_eq!(h.language, "sql");
+
+        assert!(parse_database("db", "ALTER TABLE missing ADD COLUMN a int").is_err());
+    }
+}
@@ -1,16 +1,15 @@
 use crate::db;
+use crate::db::error::{Context, DBError};
 use crate::db::postgres::system;
-use crate::db::{error::DBError, util};
+use crate::db::util;
 
-use sqlx::{PgPool, Pool, Postgres, Row};
+use sqlx::{PgConnection, Pool, Postgres, Row};
 use std::collections::HashMap;
 
 use async_trait::async_trait;
 use std::fmt::Debug;
 use std::fmt::Formatter;
 
-use regex::Regex;
-
 pub struct Driver {
     engine: db::Engine,
     database_name: String,
@@ -33,12 +32,21 @@ impl db::DB for Driver {
     }
 
     async fn sync_instance(&self) -> Result<db::store::InstanceMetadata, DBError> {
-        let version = self.get_version().await?;
-        let databases = self.load_database().await?;
+        let mut conn = self.pool.acquire().await.context("acquire")?;
+
+        let version = self.get_version(&mut conn).await.context("get_version")?;
+        let databases = self
+            .load_database(&mut conn)
+            .await
+            .context("load_database")?;
+        let instance_roles = self
+            .load_instance_role(&mut conn)
+            .await
+            .context("load_instance_role")?;
 
         Ok(db::store::InstanceMetadata {
             version,
-            instance_roles: vec![], // TODO: Implement roles if needed
+            instance_roles,
             databases: databases
                 .into_iter()
                 .filter(|db| !system::SYSTEM_DATABASES.contains(db.name.as_str()))
@@ -48,45 +56,22 @@ impl db::DB for Driver {
     }
 
     async fn sync_database(&self) -> Result<db::store::DatabaseSchemaMetadata, DBError> {
-        let databases = self.load_database().await?;
-        let mut database = databases
-            .into_iter()
-            .find(|db| db.name == self.database_name)
-            .ok_or_else(|| DBError::Args(format!("Database '{}' not found", self.database_name)))?;
-
-        let txn = self.pool.begin().await?;
-
-        let schemas = self.load_schema().await?;
-        let columns = self.load_column().await?;
-        let indexs = self.load_index().await?;
-        let tables = self.load_table(&columns, &indexs).await?;
-        let views = self.load_view().await?;
-        let mat_views = self.get_materialized_view().await?;
-
-        for schema in schemas {
-            let schema_name = schema.name.clone();
-            let tables_in_schema = tables.get(&schema_name).cloned().unwrap_or_default();
-            let views_in_schema = views.get(&schema_name).cloned().unwrap_or_default();
-            let mat_views_in_schema = mat_views.get(&schema_name).cloned().unwrap_or_default();
-
-            let schema_metadata = db::store::SchemaMetadata {
-                name: schema.name,
-                tables: tables_in_schema,
-                external_tables: vec![], // TODO: Implement external tables if needed
-                views: views_in_schema,
-                functions: vec![], // TODO: Implement functions if needed
-                materialized_views: mat_views_in_schema,
-                procedures: vec![],
-                owner: schema.owner,
-                comment: schema.comment,
-            };
-
-            database.schemas.push(schema_metadata);
-        }
-
-        txn.commit().await?;
+        self.sync_database_with(&mut db::util::Warnings::new(false))
+            .await
+    }
 
-        Ok(database)
+    async fn sync_database_lenient(
+        &self,
+    ) -> Result<
+        (
+            db::store::DatabaseSchemaMetadata,
+            Vec<db::store::SyncWarning>,
+        ),
+        DBError,
+    > {
+        let mut warnings = util::Warnings::new(true);
+        let database = self.sync_database_with(&mut warnings).await?;
+        Ok((database, warnings.into_inner()))
     }
 }
 
@@ -103,14 +88,37 @@ impl Driver {
     }
 
     pub async fn create_driver(cfg: &db::ConnectionConfig) -> Result<Driver, DBError> {
-        let opt = sqlx::postgres::PgConnectOptions::default()
+        let mut opt = sqlx::postgres::PgConnectOptions::default()
             .host(&cfg.host)
             .port(cfg.port)
             .username(&cfg.username)
             .password(&cfg.password)
-            .database(&cfg.database);
+            .database(&cfg.database)
+            .ssl_mode(match cfg.tls.mode {
+                db::SslMode::Disable => sqlx::postgres::PgSslMode::Disable,
+                db::SslMode::Prefer => sqlx::postgres::PgSslMode::Prefer,
+                db::SslMode::Require => sqlx::postgres::PgSslMode::Require,
+                db::SslMode::VerifyCa => sqlx::postgres::PgSslMode::VerifyCa,
+                db::SslMode::VerifyFull => sqlx::postgres::PgSslMode::VerifyFull,
+            });
+        if let Some(socket) = &cfg.socket {
+            opt = opt.socket(socket);
+        }
+        if let Some(ca) = &cfg.tls.ca {
+            opt = opt.ssl_root_cert(ca);
+        }
+        if let Some(cert) = &cfg.tls.cert {
+            opt = opt.ssl_client_cert(cert);
+        }
+        if let Some(key) = &cfg.tls.key {
+            opt = opt.ssl_client_key(key);
+        }
 
-        let pool = PgPool::connect_with(opt).await?;
+        if let Some(timeout) = cfg.pool.statement_timeout {
+            opt = opt.options([("statement_timeout", timeout.as_millis())]);
+        }
+        let pool = util::connect_pool(util::pool_options(&cfg.pool), opt, cfg.pool.connect_timeout)
+            .await?;
 
         Ok(Driver {
             engine: cfg.engine.clone(),
@@ -119,20 +127,127 @@ impl Driver {
         })
     }
 
-    async fn get_version(&self) -> Result<String, DBError> {
+    async fn sync_database_with(
+        &self,
+        warnings: &mut util::Warnings,
+    ) -> Result<db::store::DatabaseSchemaMetadata, DBError> {
+        // All catalog queries run on one connection inside a read only snapshot, so concurrent
+        // DDL cannot produce metadata mixed from different points in time.
+        let mut txn = self.pool.begin().await.context("begin")?;
+        sqlx::query("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ, READ ONLY")
+            .execute(&mut *txn)
+            .await?;
+
+        let databases = self
+            .load_database(&mut txn)
+            .await
+            .context("load_database")?;
+        let mut database = databases
+            .into_iter()
+            .find(|db| db.name == self.database_name)
+            .ok_or_else(|| DBError::args(format!("Database '{}' not found", self.database_name)))?;
+
+        database.extensions = self
+            .load_extension(&mut txn)
+            .await
+            .context("load_extension")?;
+        let schemas = self.load_schema(&mut txn).await.context("load_schema")?;
+        let columns = self
+            .load_column(&mut txn, warnings)
+            .await
+            .context("load_column")?;
+        let indexs = self.load_index(&mut txn).await.context("load_index")?;
+        let foreign_keys = self
+            .load_foreign_key(&mut txn, warnings)
+            .await
+            .context("load_foreign_key")?;
+        let triggers = self.load_trigger(&mut txn).await.context("load_trigger")?;
+        let partitions = self
+            .load_partition(&mut txn, warnings)
+            .await
+            .context("load_partition")?;
+        let tables = self
+            .load_table(
+                &mut txn,
+                &columns,
+                &indexs,
+                &foreign_keys,
+                &triggers,
+                &partitions,
+            )
+            .await
+            .context("load_table")?;
+        let external_tables = self
+            .load_external_table(&mut txn, &columns)
+            .await
+            .context("load_external_table")?;
+        let view_dependencies = self
+            .load_view_dependency(&mut txn)
+            .await
+            .context("load_view_dependency")?;
+        let views = self
+            .load_view(&mut txn, &view_dependencies)
+            .await
+            .context("load_view")?;
+        let mat_views = self
+            .get_materialized_view(&mut txn, &view_dependencies)
+            .await
+            .context("get_materialized_view")?;
+        let (functions, procedures) = self
+            .load_routines(&mut txn)
+            .await
+            .context("load_routines")?;
+
+        for schema in schemas {
+            let schema_name = schema.name.clone();
+            let tables_in_schema = tables.get(&schema_name).cloned().unwrap_or_default();
+            let external_tables_in_schema = external_tables
+                .get(&schema_name)
+                .cloned()
+                .unwrap_or_default();
+            let views_in_schema = views.get(&schema_name).cloned().unwrap_or_default();
+            let mat_views_in_schema = mat_views.get(&schema_name).cloned().unwrap_or_default();
+            let functions_in_schema = functions.get(&schema_name).cloned().unwrap_or_default();
+            let procedures_in_schema = procedures.get(&schema_name).cloned().unwrap_or_default();
+
+            let schema_metadata = db::store::SchemaMetadata {
+                name: schema.name,
+                tables: tables_in_schema,
+                external_tables: external_tables_in_schema,
+                views: views_in_schema,
+                functions: functions_in_schema,
+                materialized_views: mat_views_in_schema,
+                events: vec![],
+                procedures: procedures_in_schema,
+                owner: schema.owner,
+                comment: schema.comment,
+            };
+
+            database.schemas.push(schema_metadata);
+        }
+
+        txn.commit().await?;
+
+        Ok(database)
+    }
+
+    async fn get_version(&self, conn: &mut PgConnection) -> Result<String, DBError> {
         let version: String = sqlx::query("SHOW server_version_num")
-            .fetch_one(&self.pool)
+            .fetch_one(&mut *conn)
             .await?
             .get(0);
 
         let nv: i64 = version
             .parse()
-            .map_err(|e| DBError::Unknow(format!("PG VERSION ERROR:{e}")))?;
+            .map_err(|e| DBError::unsupported_version(format!("PG VERSION ERROR:{e}")))?;
         let (marjor, minor, patch) = (nv / 10000, (nv / 100) % 100, nv % 100);
         Ok(format!("{marjor}.{minor}.{patch}"))
     }
 
-    async fn load_database(&self) -> Result<Vec<db::store::DatabaseSchemaMetadata>, DBError> {
+    async fn load_database(
+        &self,
+        conn: &mut PgConnection,
+    ) -> Result<Vec<db::store::DatabaseSchemaMetadata>, DBError> {
         let query = "
     SELECT datname, 
         pg_encoding_to_char(encoding) as character_set, 
@@ -141,7 +256,7 @@ impl Driver {
     FROM pg_database;
         ";
 
-        let databases = sqlx::query(query).fetch_all(&self.pool).await?;
+        let databases = sqlx::query(query).fetch_all(&mut *conn).await?;
 
         let db_metadatas = databases
             .iter()
@@ -167,7 +282,110 @@ impl Driver {
         Ok(db_metadatas)
     }
 
-    async fn load_schema(&self) -> Result<Vec<SchemaInfo>, DBError> {
+    async fn load_instance_role(
+        &self,
+        conn: &mut PgConnection,
+    ) -> Result<Vec<db::store::InstanceRoleMetadata>, DBError> {
+        // Predefined roles such as pg_monitor are skipped.
+        let query = "
+    SELECT r.rolname, quote_ident(r.rolname) AS quoted_name,
+        r.rolsuper, r.rolinherit, r.rolcreaterole, r.rolcreatedb, r.rolcanlogin, r.rolreplication, r.rolbypassrls,
+        r.rolconnlimit, r.rolvaliduntil::text AS rolvaliduntil,
+        ARRAY(SELECT format('GRANT %I TO %I%s;', g.rolname, r.rolname,
+                CASE WHEN m.admin_option THEN ' WITH ADMIN OPTION' ELSE '' END)
+            FROM pg_catalog.pg_auth_members AS m
+                JOIN pg_catalog.pg_roles AS g ON g.oid = m.roleid
+            WHERE m.member = r.oid
+            ORDER BY g.rolname) AS memberships
+    FROM pg_catalog.pg_roles AS r
+    WHERE r.rolname !~ '^pg_'
+    ORDER BY r.rolname;
+        ";
+
+        let list = sqlx::query(query).fetch_all(&mut *conn).await?;
+
+        let mut roles = Vec::<db::store::InstanceRoleMetadata>::new();
+        for row in list {
+            let name: String = row.get("rolname");
+            let quoted_name: String = row.get("quoted_name");
+            let conn_limit: i32 = row.get("rolconnlimit");
+            let valid_until: Option<String> = row.get("rolvaliduntil");
+            let memberships: Vec<String> = row.get("memberships");
+
+            let mut options = vec![];
+            for (column, option) in [
+                ("rolsuper", "SUPERUSER"),
+                ("rolcreatedb", "CREATEDB"),
+                ("rolcreaterole", "CREATEROLE"),
+                ("rolinherit", "INHERIT"),
+                ("rolcanlogin", "LOGIN"),
+                ("rolreplication", "REPLICATION"),
+                ("rolbypassrls", "BYPASSRLS"),
+            ] {
+                let enabled: bool = row.get(column);
+                options.push(if enabled {
+                    option.to_string()
+                } else {
+                    format!("NO{option}")
+                });
+            }
+            if conn_limit != -1 {
+                options.push(format!("CONNECTION LIMIT {conn_limit}"));
+            }
+            if let Some(valid_until) = valid_until {
+                options.push(format!("VALID UNTIL '{valid_until}'"));
+            }
+
+            let mut grants = vec![format!(
+                "CREATE ROLE {quoted_name} WITH {};",
+                options.join(" ")
+            )];
+            grants.extend(memberships);
+
+            roles.push(db::store::InstanceRoleMetadata {
+                name,
+                grant: grants.join("\n"),
+            });
+        }
+
+        Ok(roles)
+    }
+
+    async fn load_extension(
+        &self,
+        conn: &mut PgConnection,
+    ) -> Result<Vec<db::store::ExtensionMetadata>, DBError> {
+        let query = "
+    SELECT e.extname, n.nspname, e.extversion, ae.comment
+    FROM pg_catalog.pg_extension AS e
+        JOIN pg_catalog.pg_namespace AS n ON n.oid = e.extnamespace
+        LEFT JOIN pg_catalog.pg_available_extensions AS ae ON ae.name = e.extname
+    ORDER BY e.extname;
+        ";
+
+        let list = sqlx::query(query).fetch_all(&mut *conn).await?;
+
+        let extensions = list
+            .iter()
+            .map(|row| {
+                let name: String = row.get("extname");
+                let schema: String = row.get("nspname");
+                let version: String = row.get("extversion");
+                let description: Option<String> = row.get("comment");
+
+                db::store::ExtensionMetadata {
+                    name,
+                    schema,
+                    version,
+                    description: description.unwrap_or_default(),
+                }
+            })
+            .collect();
+
+        Ok(extensions)
+    }
+
+    async fn load_schema(&self, conn: &mut PgConnection) -> Result<Vec<SchemaInfo>, DBError> {
         let query = format!(
             "
     SELECT nspname, pg_catalog.pg_get_userbyid(nspowner) as schema_owner, 
@@ -179,7 +397,7 @@ impl Driver {
             *system::SYSTEM_SCHEMAS_STRING
         );
 
-        let list = sqlx::query(&query).fetch_all(&self.pool).await?;
+        let list = sqlx::query(&query).fetch_all(&mut *conn).await?;
 
         let mut schema_vec = Vec::<SchemaInfo>::new();
         for row in list {
@@ -201,6 +419,8 @@ impl Driver {
 
     async fn load_column(
         &self,
+        conn: &mut PgConnection,
+        warnings: &mut util::Warnings,
     ) -> Result<HashMap<util::TableKey, Vec<db::store::ColumnMetadata>>, DBError> {
         let query = format!(
             r"
@@ -225,7 +445,7 @@ impl Driver {
             *system::SYSTEM_SCHEMAS_STRING
         );
 
-        let list = sqlx::query(&query).fetch_all(&self.pool).await?;
+        let list = sqlx::query(&query).fetch_all(&mut *conn).await?;
 
         let mut column_map = HashMap::<util::TableKey, Vec<db::store::ColumnMetadata>>::new();
 
@@ -263,12 +483,19 @@ impl Driver {
                 _ => data_type.clone(),
             };
 
+            // An unrecognized is_nullable keeps the column, assumed nullable.
+            let nullable = warnings
+                .check(
+                    &[&schema_name, &table_name, &column_name],
+                    util::convert_yes_no(&nullable_str),
+                )?
+                .unwrap_or(true);
             let col = db::store::ColumnMetadata {
                 name: column_name,
                 position,
                 default: default.unwrap_or_default(),
                 on_update: None,
-                nullable: util::convert_yes_no(&nullable_str)?,
+                nullable,
                 r#type,
                 character_set: String::new(), // Postgres does not have character set
                 collation: collation.unwrap_or_default(),
@@ -293,24 +520,35 @@ impl Driver {
 
     async fn load_index(
         &self,
+        conn: &mut PgConnection,
     ) -> Result<HashMap<util::TableKey, Vec<db::store::IndexMetadata>>, DBError> {
+        // pg_get_indexdef(oid, k, true) returns the k-th column or expression of an index,
+        // the first indnkeyatts of them are key columns and the rest are INCLUDE columns.
         let query = format!(
             r"
-    SELECT idx.schemaname, idx.tablename, idx.indexname, idx.indexdef, (SELECT 1
-        FROM information_schema.table_constraints
-        WHERE constraint_schema = idx.schemaname
-        AND constraint_name = idx.indexname
-        AND table_schema = idx.schemaname
-        AND table_name = idx.tablename
-        AND constraint_type = 'PRIMARY KEY') AS primary,
-        obj_description(format('%s.%s', quote_ident(idx.schemaname), quote_ident(idx.indexname))::regclass) AS comment
-    FROM pg_indexes AS idx WHERE idx.schemaname NOT IN ({})
-    ORDER BY idx.schemaname, idx.tablename, idx.indexname;
+    SELECT n.nspname AS schemaname, tbl.relname AS tablename, idx.relname AS indexname,
+        pg_catalog.pg_get_indexdef(ix.indexrelid) AS indexdef,
+        am.amname AS method,
+        ix.indisunique AS is_unique,
+        ix.indisprimary AS is_primary,
+        ix.indnkeyatts AS key_count,
+        ARRAY(SELECT pg_catalog.pg_get_indexdef(ix.indexrelid, k, true)
+            FROM generate_series(1, ix.indnatts) AS k
+            ORDER BY k) AS columns,
+        pg_catalog.pg_get_expr(ix.indpred, ix.indrelid, true) AS predicate,
+        obj_description(ix.indexrelid, 'pg_class') AS comment
+    FROM pg_catalog.pg_index AS ix
+        JOIN pg_catalog.pg_class AS idx ON idx.oid = ix.indexrelid
+        JOIN pg_catalog.pg_class AS tbl ON tbl.oid = ix.indrelid
+        JOIN pg_catalog.pg_namespace AS n ON n.oid = tbl.relnamespace
+        JOIN pg_catalog.pg_am AS am ON am.oid = idx.relam
+    WHERE n.nspname NOT IN ({})
+    ORDER BY n.nspname, tbl.relname, idx.relname;
         ",
             *system::SYSTEM_SCHEMAS_STRING
         );
 
-        let list = sqlx::query(&query).fetch_all(&self.pool).await?;
+        let list = sqlx::query(&query).fetch_all(&mut *conn).await?;
 
         let mut index_map = HashMap::<util::TableKey, Vec<db::store::IndexMetadata>>::new();
 
@@ -319,19 +557,29 @@ impl Driver {
             let table_name: String = row.get("tablename");
             let index_name: String = row.get("indexname");
             let index_def: String = row.get("indexdef");
-            let is_primary: Option<i32> = row.get("primary");
+            let method: String = row.get("method");
+            let is_unique: bool = row.get("is_unique");
+            let is_primary: bool = row.get("is_primary");
+            let key_count: i16 = row.get("key_count");
+            let mut expressions: Vec<String> = row.get("columns");
+            let predicate: Option<String> = row.get("predicate");
             let comment: Option<String> = row.get("comment");
 
+            let include_columns =
+                expressions.split_off((key_count as usize).min(expressions.len()));
+
             let idx = db::store::IndexMetadata {
                 name: index_name.clone(),
-                expressions: vec![],
-                key_length: vec![],
-                r#type: get_index_method_type(&index_def).unwrap_or_default(),
-                unique: false, //TODO: need to parse this from index_def
-                primary: is_primary.map(|v| v == 1).unwrap_or(false),
+                key_length: vec![-1; expressions.len()],
+                expressions,
+                r#type: method,
+                unique: is_unique,
+                primary: is_primary,
                 visible: true,
                 comment: comment.unwrap_or_default(),
-                definition: index_def.clone(),
+                definition: index_def,
+                include_columns,
+                predicate: predicate.unwrap_or_default(),
             };
 
             let key = util::TableKey {
@@ -345,10 +593,249 @@ impl Driver {
         Ok(index_map)
     }
 
+    async fn load_foreign_key(
+        &self,
+        conn: &mut PgConnection,
+        warnings: &mut util::Warnings,
+    ) -> Result<HashMap<util::TableKey, Vec<db::store::ForeignKeyMetadata>>, DBError> {
+        // A foreign key referencing a partitioned table is cloned internally for each partition,
+        // the clones have a parent constraint and are left out.
+        let query = format!(
+            r"
+    SELECT n.nspname AS table_schema, cl.relname AS table_name, con.conname AS constraint_name,
+        ARRAY(SELECT att.attname::text
+            FROM unnest(con.conkey) WITH ORDINALITY AS k(attnum, ord)
+            JOIN pg_attribute AS att ON att.attrelid = con.conrelid AND att.attnum = k.attnum
+            ORDER BY k.ord) AS columns,
+        rn.nspname AS referenced_schema, rcl.relname AS referenced_table,
+        ARRAY(SELECT att.attname::text
+            FROM unnest(con.confkey) WITH ORDINALITY AS k(attnum, ord)
+            JOIN pg_attribute AS att ON att.attrelid = con.confrelid AND att.attnum = k.attnum
+            ORDER BY k.ord) AS referenced_columns,
+        con.confdeltype::text AS on_delete,
+        con.confupdtype::text AS on_update,
+        con.confmatchtype::text AS match_type
+    FROM pg_catalog.pg_constraint AS con
+        JOIN pg_class AS cl ON cl.oid = con.conrelid
+        JOIN pg_namespace AS n ON n.oid = cl.relnamespace
+        JOIN pg_class AS rcl ON rcl.oid = con.confrelid
+        JOIN pg_namespace AS rn ON rn.oid = rcl.relnamespace
+    WHERE con.contype = 'f' AND con.conparentid = 0 AND n.nspname NOT IN ({})
+    ORDER BY n.nspname, cl.relname, con.conname;
+        ",
+            *system::SYSTEM_SCHEMAS_STRING
+        );
+
+        let list = sqlx::query(&query).fetch_all(&mut *conn).await?;
+
+        let mut fk_map = HashMap::<util::TableKey, Vec<db::store::ForeignKeyMetadata>>::new();
+
+        for row in list {
+            let schema_name: String = row.get("table_schema");
+            let table_name: String = row.get("table_name");
+            let fk_name: String = row.get("constraint_name");
+            let columns: Vec<String> = row.get("columns");
+            let ref_schema: String = row.get("referenced_schema");
+            let ref_table: String = row.get("referenced_table");
+            let ref_columns: Vec<String> = row.get("referenced_columns");
+            let on_delete: String = row.get("on_delete");
+            let on_update: String = row.get("on_update");
+            let match_type: String = row.get("match_type");
+
+            // A foreign key with an unrecognized action or match type is skipped.
+            let convert = || -> Result<_, DBError> {
+                Ok((
+                    convert_fk_action(&on_delete)?,
+                    convert_fk_action(&on_update)?,
+                    convert_fk_match_type(&match_type)?,
+                ))
+            };
+            let Some((on_delete, on_update, match_type)) =
+                warnings.check(&[&schema_name, &table_name, &fk_name], convert())?
+            else {
+                continue;
+            };
+
+            let fk = db::store::ForeignKeyMetadata {
+                name: fk_name,
+                columns,
+                referenced_schema: ref_schema,
+                referenced_table: ref_table,
+                referenced_columns: ref_columns,
+                on_delete,
+                on_update,
+                match_type,
+            };
+
+            fk_map
+                .entry(util::TableKey {
+                    schema: schema_name,
+                    table: table_name,
+                })
+                .or_default()
+                .push(fk);
+        }
+
+        Ok(fk_map)
+    }
+
+    // load_trigger loads the triggers of tables. The internal triggers enforcing foreign keys
+    // are skipped.
+    async fn load_trigger(
+        &self,
+        conn: &mut PgConnection,
+    ) -> Result<HashMap<util::TableKey, Vec<db::store::TriggerMetadata>>, DBError> {
+        let query = format!(
+            r"
+    SELECT n.nspname, c.relname, t.tgname, t.tgtype::integer AS tgtype,
+        t.tgenabled::text AS tgenabled, pg_catalog.pg_get_triggerdef(t.oid) AS definition
+    FROM pg_catalog.pg_trigger AS t
+        JOIN pg_catalog.pg_class AS c ON c.oid = t.tgrelid
+        JOIN pg_catalog.pg_namespace AS n ON n.oid = c.relnamespace
+    WHERE NOT t.tgisinternal AND n.nspname NOT IN ({})
+    ORDER BY n.nspname, c.relname, t.tgname;
+        ",
+            *system::SYSTEM_SCHEMAS_STRING
+        );
+
+        let list = sqlx::query(&query).fetch_all(&mut *conn).await?;
+
+        let mut trigger_map = HashMap::<util::TableKey, Vec<db::store::TriggerMetadata>>::new();
+        for row in list {
+            let schema_name: String = row.get("nspname");
+            let table_name: String = row.get("relname");
+            let tgtype: i32 = row.get("tgtype");
+            let enabled: String = row.get("tgenabled");
+
+            trigger_map
+                .entry(util::TableKey {
+                    schema: schema_name,
+                    table: table_name,
+                })
+                .or_default()
+                .push(db::store::TriggerMetadata {
+                    name: row.get("tgname"),
+                    timing: trigger_timing(tgtype).to_string(),
+                    events: trigger_events(tgtype),
+                    for_each_row: tgtype & TRIGGER_TYPE_ROW != 0,
+                    definition: row.get("definition"),
+                    // A trigger is disabled with D, the other states fire it at least outside
+                    // of replication.
+                    enabled: enabled != "D",
+                });
+        }
+
+        Ok(trigger_map)
+    }
+
+    // load_partition loads the partitioning of partitioned tables, keyed by the partitioned table.
+    // A partition that is partitioned itself carries its own partitioning as subpartitioning.
+    async fn load_partition(
+        &self,
+        conn: &mut PgConnection,
+        warnings: &mut util::Warnings,
+    ) -> Result<HashMap<util::TableKey, db::store::PartitioningMetadata>, DBError> {
+        let query = format!(
+            r"
+    SELECT n.nspname, c.relname, pt.partstrat::text AS partstrat,
+        pg_catalog.pg_get_partkeydef(c.oid) AS partkeydef
+    FROM pg_catalog.pg_partitioned_table AS pt
+        JOIN pg_catalog.pg_class AS c ON c.oid = pt.partrelid
+        JOIN pg_catalog.pg_namespace AS n ON n.oid = c.relnamespace
+    WHERE n.nspname NOT IN ({});
+        ",
+            *system::SYSTEM_SCHEMAS_STRING
+        );
+
+        let list = sqlx::query(&query).fetch_all(&mut *conn).await?;
+
+        let mut partitioned_map =
+            HashMap::<util::TableKey, (db::store::PartitionStrategy, String)>::new();
+        for row in list {
+            let schema_name: String = row.get("nspname");
+            let table_name: String = row.get("relname");
+            let strategy: String = row.get("partstrat");
+            let key_definition: String = row.get("partkeydef");
+
+            let Some(strategy) = warnings.check(
+                &[&schema_name, &table_name],
+                convert_partition_strategy(&strategy),
+            )?
+            else {
+                continue;
+            };
+            // pg_get_partkeydef writes the strategy before the parenthesized key, e.g.
+            // RANGE (created_at).
+            let key = key_definition
+                .split_once(' ')
+                .map_or(key_definition.as_str(), |(_, key)| key);
+            let expression = key
+                .strip_prefix('(')
+                .and_then(|key| key.strip_suffix(')'))
+                .unwrap_or(key)
+                .to_string();
+            partitioned_map.insert(
+                util::TableKey {
+                    schema: schema_name,
+                    table: table_name,
+                },
+                (strategy, expression),
+            );
+        }
+
+        let query = format!(
+            r"
+    SELECT pn.nspname AS parent_schema, p.relname AS parent_name, n.nspname, c.relname,
+        pg_catalog.pg_get_expr(c.relpartbound, c.oid) AS bound,
+        GREATEST(c.reltuples::bigint, 0::BIGINT) AS estimate
+    FROM pg_catalog.pg_inherits AS i
+        JOIN pg_catalog.pg_class AS c ON c.oid = i.inhrelid
+        JOIN pg_catalog.pg_namespace AS n ON n.oid = c.relnamespace
+        JOIN pg_catalog.pg_class AS p ON p.oid = i.inhparent
+        JOIN pg_catalog.pg_namespace AS pn ON pn.oid = p.relnamespace
+    WHERE c.relispartition AND c.relkind IN ('r', 'p', 'f') AND pn.nspname NOT IN ({})
+    ORDER BY pn.nspname, p.relname, n.nspname, c.relname;
+        ",
+            *system::SYSTEM_SCHEMAS_STRING
+        );
+
+        let list = sqlx::query(&query).fetch_all(&mut *conn).await?;
+
+        let mut partition_map = HashMap::<util::TableKey, Vec<PgPartition>>::new();
+        for row in list {
+            let parent = util::TableKey {
+                schema: row.get("parent_schema"),
+                table: row.get("parent_name"),
+            };
+            partition_map.entry(parent).or_default().push(PgPartition {
+                key: util::TableKey {
+                    schema: row.get("nspname"),
+                    table: row.get("relname"),
+                },
+                bound: row.get("bound"),
+                row_count: row.get("estimate"),
+            });
+        }
+
+        Ok(partitioned_map
+            .keys()
+            .filter_map(|key| {
+                build_partitioning(key, &partitioned_map, &partition_map)
+                    .map(|partitioning| (key.clone(), partitioning))
+            })
+            .collect())
+    }
+
+    // load_table loads the tables. Partitions are left out, they are listed in the partitioning
+    // of their parent.
     async fn load_table(
         &self,
+        conn: &mut PgConnection,
         column_map: &HashMap<util::TableKey, Vec<db::store::ColumnMetadata>>,
         index_map: &HashMap<util::TableKey, Vec<db::store::IndexMetadata>>,
+        fk_map: &HashMap<util::TableKey, Vec<db::store::ForeignKeyMetadata>>,
+        trigger_map: &HashMap<util::TableKey, Vec<db::store::TriggerMetadata>>,
+        partition_map: &HashMap<util::TableKey, db::store::PartitioningMetadata>,
     ) -> Result<HashMap<String, Vec<db::store::TableMetadata>>, DBError> {
         let query = format!(
             r"
@@ -360,13 +847,13 @@ impl Driver {
         tbl.tableowner
     FROM pg_catalog.pg_tables tbl
     LEFT JOIN pg_class as pc ON pc.oid = format('%s.%s', quote_ident(tbl.schemaname), quote_ident(tbl.tablename))::regclass
-    WHERE tbl.schemaname NOT IN ({})
+    WHERE tbl.schemaname NOT IN ({}) AND NOT pc.relispartition
     ORDER BY tbl.schemaname, tbl.tablename;
             ",
             *system::SYSTEM_SCHEMAS_STRING
         );
 
-        let list = sqlx::query(&query).fetch_all(&self.pool).await?;
+        let list = sqlx::query(&query).fetch_all(&mut *conn).await?;
 
         let mut table_map = HashMap::<String, Vec<db::store::TableMetadata>>::new();
         for row in list {
@@ -385,6 +872,9 @@ impl Driver {
 
             let columns = column_map.get(&key).cloned().unwrap_or_default();
             let indexes = index_map.get(&key).cloned().unwrap_or_default();
+            let foreign_keys = fk_map.get(&key).cloned().unwrap_or_default();
+            let triggers = trigger_map.get(&key).cloned().unwrap_or_default();
+            let partitioning = partition_map.get(&key).cloned();
 
             let table_metadata = db::store::TableMetadata {
                 name: table_name,
@@ -399,7 +889,9 @@ impl Driver {
                 create_options: String::new(), // Postgres does not have create options like MySQL
                 comment: comment.unwrap_or_default(),
                 owner,
-                foreign_keys: vec![],
+                foreign_keys,
+                triggers,
+                partitioning,
             };
 
             table_map
@@ -411,7 +903,117 @@ impl Driver {
         Ok(table_map)
     }
 
-    async fn load_view(&self) -> Result<HashMap<String, Vec<db::store::ViewMetadata>>, DBError> {
+    async fn load_external_table(
+        &self,
+        conn: &mut PgConnection,
+        column_map: &HashMap<util::TableKey, Vec<db::store::ColumnMetadata>>,
+    ) -> Result<HashMap<String, Vec<db::store::ExternalTableMetadata>>, DBError> {
+        // The remote database comes from the dbname option of the server, e.g. postgres_fdw.
+        let query = format!(
+            r"
+    SELECT n.nspname AS schemaname, c.relname AS tablename, srv.srvname,
+        (SELECT opt.option_value
+            FROM pg_catalog.pg_options_to_table(srv.srvoptions) AS opt
+            WHERE opt.option_name = 'dbname') AS dbname
+    FROM pg_catalog.pg_foreign_table AS ft
+        JOIN pg_catalog.pg_class AS c ON c.oid = ft.ftrelid
+        JOIN pg_catalog.pg_namespace AS n ON n.oid = c.relnamespace
+        JOIN pg_catalog.pg_foreign_server AS srv ON srv.oid = ft.ftserver
+    WHERE n.nspname NOT IN ({})
+    ORDER BY n.nspname, c.relname;
+        ",
+            *system::SYSTEM_SCHEMAS_STRING
+        );
+
+        let list = sqlx::query(&query).fetch_all(&mut *conn).await?;
+
+        let mut external_table_map =
+            HashMap::<String, Vec<db::store::ExternalTableMetadata>>::new();
+        for row in list {
+            let schema_name: String = row.get("schemaname");
+            let table_name: String = row.get("tablename");
+            let server_name: String = row.get("srvname");
+            let database_name: Option<String> = row.get("dbname");
+
+            let key = util::TableKey {
+                schema: schema_name.clone(),
+                table: table_name.clone(),
+            };
+
+            let external_table = db::store::ExternalTableMetadata {
+                name: table_name,
+                external_server_name: server_name,
+                external_database_name: database_name.unwrap_or_default(),
+                columns: column_map.get(&key).cloned().unwrap_or_default(),
+            };
+
+            external_table_map
+                .entry(schema_name)
+                .or_default()
+                .push(external_table);
+        }
+
+        Ok(external_table_map)
+    }
+
+    async fn load_view_dependency(
+        &self,
+        conn: &mut PgConnection,
+    ) -> Result<HashMap<util::TableKey, Vec<db::store::DependentColumn>>, DBError> {
+        // Each view and materialized view owns a _RETURN rewrite rule, the rule depends on
+        // every column the view query references.
+        let query = format!(
+            r"
+    SELECT DISTINCT vn.nspname AS view_schema, v.relname AS view_name,
+        tn.nspname AS table_schema, t.relname AS table_name, a.attname::text AS column_name, a.attnum
+    FROM pg_catalog.pg_rewrite AS r
+        JOIN pg_catalog.pg_class AS v ON v.oid = r.ev_class
+        JOIN pg_catalog.pg_namespace AS vn ON vn.oid = v.relnamespace
+        JOIN pg_catalog.pg_depend AS d ON d.classid = 'pg_catalog.pg_rewrite'::regclass
+            AND d.objid = r.oid
+            AND d.refclassid = 'pg_catalog.pg_class'::regclass
+            AND d.refobjsubid > 0
+        JOIN pg_catalog.pg_class AS t ON t.oid = d.refobjid
+        JOIN pg_catalog.pg_namespace AS tn ON tn.oid = t.relnamespace
+        JOIN pg_catalog.pg_attribute AS a ON a.attrelid = t.oid AND a.attnum = d.refobjsubid
+    WHERE v.relkind IN ('v', 'm') AND t.oid <> v.oid AND vn.nspname NOT IN ({})
+    ORDER BY vn.nspname, v.relname, tn.nspname, t.relname, a.attnum;
+        ",
+            *system::SYSTEM_SCHEMAS_STRING
+        );
+
+        let list = sqlx::query(&query).fetch_all(&mut *conn).await?;
+
+        let mut dependency_map = HashMap::<util::TableKey, Vec<db::store::DependentColumn>>::new();
+
+        for row in list {
+            let view_schema: String = row.get("view_schema");
+            let view_name: String = row.get("view_name");
+            let table_schema: String = row.get("table_schema");
+            let table_name: String = row.get("table_name");
+            let column_name: String = row.get("column_name");
+
+            dependency_map
+                .entry(util::TableKey {
+                    schema: view_schema,
+                    table: view_name,
+                })
+                .or_default()
+                .push(db::store::DependentColumn {
+                    schema: table_schema,
+                    table: table_name,
+                    column: column_name,
+                });
+        }
+
+        Ok(dependency_map)
+    }
+
+    async fn load_view(
+        &self,
+        conn: &mut PgConnection,
+        dependency_map: &HashMap<util::TableKey, Vec<db::store::DependentColumn>>,
+    ) -> Result<HashMap<String, Vec<db::store::ViewMetadata>>, DBError> {
         let query = format!(
             r"
     SELECT pc.oid, schemaname, viewname, definition, obj_description(format('%s.%s', quote_ident(schemaname), quote_ident(viewname))::regclass) as comment
@@ -423,7 +1025,7 @@ impl Driver {
             *system::SYSTEM_SCHEMAS_STRING
         );
 
-        let list = sqlx::query(&query).fetch_all(&self.pool).await?;
+        let list = sqlx::query(&query).fetch_all(&mut *conn).await?;
 
         let mut view_map = HashMap::<String, Vec<db::store::ViewMetadata>>::new();
 
@@ -433,11 +1035,16 @@ impl Driver {
             let definition: String = row.get("definition");
             let comment: Option<String> = row.get("comment");
 
+            let key = util::TableKey {
+                schema: schema_name.clone(),
+                table: view_name.clone(),
+            };
+
             let view_metadata = db::store::ViewMetadata {
                 name: view_name,
                 definition,
                 comment: comment.unwrap_or_default(),
-                dependent_columns: vec![], //TODO we can implement this later
+                dependent_columns: dependency_map.get(&key).cloned().unwrap_or_default(),
             };
 
             view_map.entry(schema_name).or_default().push(view_metadata);
@@ -448,6 +1055,8 @@ impl Driver {
 
     async fn get_materialized_view(
         &self,
+        conn: &mut PgConnection,
+        dependency_map: &HashMap<util::TableKey, Vec<db::store::DependentColumn>>,
     ) -> Result<HashMap<String, Vec<db::store::MaterializedViewMetadata>>, DBError> {
         let query = format!(
             r"
@@ -459,7 +1068,7 @@ impl Driver {
             ",
             *system::SYSTEM_SCHEMAS_STRING
         );
-        let list = sqlx::query(&query).fetch_all(&self.pool).await?;
+        let list = sqlx::query(&query).fetch_all(&mut *conn).await?;
 
         let mut matview_map = HashMap::<String, Vec<db::store::MaterializedViewMetadata>>::new();
 
@@ -469,11 +1078,16 @@ impl Driver {
             let definition: String = row.get("definition");
             let comment: Option<String> = row.get("comment");
 
+            let key = util::TableKey {
+                schema: schema_name.clone(),
+                table: matview_name.clone(),
+            };
+
             let matview_metadata = db::store::MaterializedViewMetadata {
                 name: matview_name,
                 definition,
                 comment: comment.unwrap_or_default(),
-                dependent_columns: vec![], //TODO we can implement this later
+                dependent_columns: dependency_map.get(&key).cloned().unwrap_or_default(),
             };
 
             matview_map
@@ -484,28 +1098,389 @@ impl Driver {
 
         Ok(matview_map)
     }
+
+    async fn load_routines(
+        &self,
+        conn: &mut PgConnection,
+    ) -> Result<
+        (
+            HashMap<String, Vec<db::store::FunctionMetadata>>,
+            HashMap<String, Vec<db::store::ProcedureMetadata>>,
+        ),
+        DBError,
+    > {
+        // Routines owned by an extension are skipped, they are recreated by the extension itself.
+        let query = format!(
+            r"
+    SELECT n.nspname, p.proname, p.prokind::text AS prokind,
+        pg_catalog.pg_get_function_identity_arguments(p.oid) AS arguments,
+        pg_catalog.pg_get_functiondef(p.oid) AS definition,
+        COALESCE(p.proargnames, ARRAY[]::text[]) AS argument_names,
+        COALESCE(p.proargmodes::text[], ARRAY[]::text[]) AS argument_modes,
+        ARRAY(SELECT pg_catalog.format_type(a.type, NULL)
+            FROM unnest(COALESCE(p.proallargtypes, p.proargtypes::oid[]))
+                WITH ORDINALITY AS a(type, ord)
+            ORDER BY a.ord) AS argument_types,
+        COALESCE(pg_catalog.pg_get_function_result(p.oid), '') AS return_type,
+        l.lanname, p.provolatile::text AS provolatile, p.prosecdef,
+        pg_catalog.pg_get_userbyid(p.proowner) AS owner,
+        COALESCE(pg_catalog.obj_description(p.oid, 'pg_proc'), '') AS comment
+    FROM pg_catalog.pg_proc AS p
+        JOIN pg_catalog.pg_namespace AS n ON n.oid = p.pronamespace
+        JOIN pg_catalog.pg_language AS l ON l.oid = p.prolang
+    WHERE n.nspname NOT IN ({})
+        AND p.prokind IN ('f', 'p')
+        AND NOT EXISTS (SELECT 1 FROM pg_catalog.pg_depend AS d
+            WHERE d.classid = 'pg_catalog.pg_proc'::regclass AND d.objid = p.oid AND d.deptype = 'e')
+    ORDER BY n.nspname, p.proname, arguments;
+        ",
+            *system::SYSTEM_SCHEMAS_STRING
+        );
+
+        let list = sqlx::query(&query).fetch_all(&mut *conn).await?;
+
+        let mut function_map = HashMap::<String, Vec<db::store::FunctionMetadata>>::new();
+        let mut procedure_map = HashMap::<String, Vec<db::store::ProcedureMetadata>>::new();
+
+        for row in list {
+            let schema_name: String = row.get("nspname");
+            let name: String = row.get("proname");
+            let kind: String = row.get("prokind");
+            let arguments: String = row.get("arguments");
+            let definition: String = row.get("definition");
+            let argument_names: Vec<String> = row.get("argument_names");
+            let argument_modes: Vec<String> = row.get("argument_modes");
+            let argument_types: Vec<String> = row.get("argument_types");
+            let return_type: String = row.get("return_type");
+            let language: String = row.get("lanname");
+            let volatility = match row.get::<String, _>("provolatile").as_str() {
+                "i" => db::store::Volatility::Immutable,
+                "s" => db::store::Volatility::Stable,
+                _ => db::store::Volatility::Volatile,
+            };
+            let security = if row.get("prosecdef") {
+                db::store::SecurityType::Definer
+            } else {
+                db::store::SecurityType::Invoker
+            };
+            let owner: String = row.get("owner");
+            let comment: String = row.get("comment");
+
+            let signature = format!("{name}({arguments})");
+            // The names and modes are empty when no argument has one.
+            let parameters = argument_types
+                .into_iter()
+                .enumerate()
+                .map(|(i, r#type)| db::store::RoutineParameter {
+                    name: argument_names.get(i).cloned().unwrap_or_default(),
+                    mode: match argument_modes.get(i).map(String::as_str) {
+                        Some("o") => db::store::ParameterMode::Out,
+                        Some("b") => db::store::ParameterMode::InOut,
+                        Some("v") => db::store::ParameterMode::Variadic,
+                        Some("t") => db::store::ParameterMode::Table,
+                        _ => db::store::ParameterMode::In,
+                    },
+                    r#type,
+                })
+                .collect();
+
+            if kind == "p" {
+                procedure_map
+                    .entry(schema_name)
+                    .or_default()
+                    .push(db::store::ProcedureMetadata {
+                        name,
+                        signature,
+                        definition,
+                        parameters,
+                        language,
+                        volatility,
+                        security,
+                        owner,
+                        comment,
+                    });
+            } else {
+                function_map
+                    .entry(schema_name)
+                    .or_default()
+                    .push(db::store::FunctionMetadata {
+                        name,
+                        signature,
+                        definition,
+                        parameters,
+                        return_type,
+                        language,
+                        volatility,
+                        security,
+                        owner,
+                        comment,
+                    });
+            }
+        }
+
+        Ok((function_map, procedure_map))
+    }
 }
 
-fn get_index_method_type(stmt: &str) -> Option<String> {
-    let re = Regex::new(r"USING (\w+) ").unwrap();
-    re.captures(stmt)
-        .and_then(|caps| caps.get(1))
-        .map(|m| m.as_str().to_string())
+// PgPartition is a partition of a partitioned table.
+struct PgPartition {
+    key: util::TableKey,
+    // The bound is the FOR VALUES clause of a partition or DEFAULT.
+    bound: String,
+    row_count: i64,
+}
+
+// build_partitioning returns the partitioning of a partitioned table, recursing into the
+// partitions that are partitioned themselves.
+fn build_partitioning(
+    key: &util::TableKey,
+    partitioned_map: &HashMap<util::TableKey, (db::store::PartitionStrategy, String)>,
+    partition_map: &HashMap<util::TableKey, Vec<PgPartition>>,
+) -> Option<db::store::PartitioningMetadata> {
+    let (strategy, expression) = partitioned_map.get(key)?;
+    let partitions = partition_map
+        .get(key)
+        .map(|partitions| {
+            partitions
+                .iter()
+                .map(|partition| db::store::PartitionMetadata {
+                    // A partition may live in another schema than its parent.
+                    name: if partition.key.schema == key.schema {
+                        partition.key.table.clone()
+                    } else {
+                        format!("{}.{}", partition.key.schema, partition.key.table)
+                    },
+                    value: partition.bound.clone(),
+                    subpartitioning: build_partitioning(
+                        &partition.key,
+                        partitioned_map,
+                        partition_map,
+                    ),
+                    row_count: partition.row_count,
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    Some(db::store::PartitioningMetadata {
+        strategy: strategy.clone(),
+        expression: expression.clone(),
+        partitions,
+    })
+}
+
+// convert_partition_strategy converts pg_partitioned_table.partstrat to the partition strategy.
+fn convert_partition_strategy(strategy: &str) -> Result<db::store::PartitionStrategy, DBError> {
+    match strategy {
+        "r" => Ok(db::store::PartitionStrategy::Range),
+        "l" => Ok(db::store::PartitionStrategy::List),
+        "h" => Ok(db::store::PartitionStrategy::Hash),
+        _ => Err(DBError::unexpected_catalog(format!(
+            "unrecognized partition strategy {strategy}"
+        ))),
+    }
+}
+
+// The bits of pg_trigger.tgtype, see include/catalog/pg_trigger.h.
+const TRIGGER_TYPE_ROW: i32 = 1 << 0;
+const TRIGGER_TYPE_BEFORE: i32 = 1 << 1;
+const TRIGGER_TYPE_INSERT: i32 = 1 << 2;
+const TRIGGER_TYPE_DELETE: i32 = 1 << 3;
+const TRIGGER_TYPE_UPDATE: i32 = 1 << 4;
+const TRIGGER_TYPE_TRUNCATE: i32 = 1 << 5;
+const TRIGGER_TYPE_INSTEAD: i32 = 1 << 6;
+
+// trigger_timing converts pg_trigger.tgtype to the timing of a trigger.
+fn trigger_timing(tgtype: i32) -> &'static str {
+    if tgtype & TRIGGER_TYPE_INSTEAD != 0 {
+        "INSTEAD OF"
+    } else if tgtype & TRIGGER_TYPE_BEFORE != 0 {
+        "BEFORE"
+    } else {
+        "AFTER"
+    }
+}
+
+// trigger_events converts pg_trigger.tgtype to the events of a trigger, in the order
+// pg_get_triggerdef writes them.
+fn trigger_events(tgtype: i32) -> Vec<String> {
+    [
+        (TRIGGER_TYPE_INSERT, "INSERT"),
+        (TRIGGER_TYPE_DELETE, "DELETE"),
+        (TRIGGER_TYPE_UPDATE, "UPDATE"),
+        (TRIGGER_TYPE_TRUNCATE, "TRUNCATE"),
+    ]
+    .into_iter()
+    .filter(|(bit, _)| tgtype & bit != 0)
+    .map(|(_, event)| event.to_string())
+    .collect()
+}
+
+// convert_fk_action converts pg_constraint.confdeltype/confupdtype to the referential action.
+fn convert_fk_action(action: &str) -> Result<String, DBError> {
+    match action {
+        "a" => Ok("NO ACTION".to_string()),
+        "r" => Ok("RESTRICT".to_string()),
+        "c" => Ok("CASCADE".to_string()),
+        "n" => Ok("SET NULL".to_string()),
+        "d" => Ok("SET DEFAULT".to_string()),
+        _ => Err(DBError::unexpected_catalog(format!(
+            "unrecognized foreign key action {action}"
+        ))),
+    }
+}
+
+// convert_fk_match_type converts pg_constraint.confmatchtype to the match type.
+fn convert_fk_match_type(match_type: &str) -> Result<String, DBError> {
+    match match_type {
+        "f" => Ok("FULL".to_string()),
+        "p" => Ok("PARTIAL".to_string()),
+        "s" => Ok("SIMPLE".to_string()),
+        _ => Err(DBError::unexpected_catalog(format!(
+            "unrecognized foreign key match type {match_type}"
+        ))),
+    }
 }
 
 #[cfg(test)]
 mod test {
 
-    use crate::db::DB;
+    use crate::db::{self, DB};
     use crate::tests::{init_pg_test_service, init_postgres_test_schema};
 
     use super::Driver;
+    use sqlx::Executor;
+    use std::time::Duration;
 
     async fn get_driver() -> Driver {
         let cfg = init_pg_test_service().unwrap();
         Driver::create_driver(&cfg).await.unwrap()
     }
 
+    async fn is_encrypted(driver: &Driver) -> bool {
+        sqlx::query_scalar("SELECT ssl FROM pg_stat_ssl WHERE pid = pg_backend_pid()")
+            .fetch_one(&driver.pool)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_pool_config() {
+        let mut cfg = init_pg_test_service().unwrap();
+        cfg.pool = db::PoolConfig {
+            max_connections: Some(2),
+            connect_timeout: Some(Duration::from_secs(10)),
+            acquire_timeout: Some(Duration::from_secs(10)),
+            statement_timeout: Some(Duration::from_millis(200)),
+        };
+        let driver = Driver::create_driver(&cfg).await.unwrap();
+        assert_eq!(driver.pool.options().get_max_connections(), 2);
+
+        let timeout: String = sqlx::query_scalar("SHOW statement_timeout")
+            .fetch_one(&driver.pool)
+            .await
+            .unwrap();
+        assert_eq!(timeout, "200ms");
+        let slow = sqlx::query("SELECT pg_sleep(2)")
+            .execute(&driver.pool)
+            .await;
+        let err = db::DBError::from(slow.unwrap_err());
+        assert_eq!(err.kind(), db::ErrorKind::Timeout);
+        assert!(err.is_transient());
+        driver.sync_database().await.unwrap();
+
+        // A connection to an address that never answers gives up with the connect timeout.
+        cfg.host = "10.255.255.1".to_string();
+        cfg.socket = None;
+        cfg.pool.connect_timeout = Some(Duration::from_millis(300));
+        let start = std::time::Instant::now();
+        let err = Driver::create_driver(&cfg).await.unwrap_err();
+        assert!(err.is_transient());
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+
+    #[tokio::test]
+    async fn test_sync_database_lenient() {
+        init_postgres_test_schema().await.unwrap();
+        let driver = get_driver().await;
+        let (database, warnings) = driver.sync_database_lenient().await.unwrap();
+        assert!(warnings.is_empty(), "{warnings:?}");
+        let schema = database.schemas.iter().find(|s| s.name == "sales").unwrap();
+        assert!(schema.tables.iter().any(|t| !t.foreign_keys.is_empty()));
+    }
+
+    #[tokio::test]
+    async fn test_error_kind() {
+        let mut cfg = init_pg_test_service().unwrap();
+        cfg.database = "dbmeta_missing_database".to_string();
+        let err = Driver::create_driver(&cfg).await.unwrap_err();
+        assert_eq!(err.kind(), db::ErrorKind::Database);
+        assert!(std::error::Error::source(&err).is_some());
+
+        // Nothing listens on the discard port.
+        cfg.host = "127.0.0.1".to_string();
+        cfg.port = 9;
+        cfg.socket = None;
+        let err = Driver::create_driver(&cfg).await.unwrap_err();
+        assert_eq!(err.kind(), db::ErrorKind::ConnectionRefused);
+    }
+
+    #[tokio::test]
+    async fn test_unix_socket() {
+        let mut cfg = init_pg_test_service().unwrap();
+        let directories: String = sqlx::query_scalar("SHOW unix_socket_directories")
+            .fetch_one(&get_driver().await.pool)
+            .await
+            .unwrap();
+        // The socket is only reachable when the server runs on this host.
+        let Some(directory) = directories.split(',').map(str::trim).find(|d| {
+            std::path::Path::new(d)
+                .join(format!(".s.PGSQL.{}", cfg.port))
+                .exists()
+        }) else {
+            return;
+        };
+
+        cfg.socket = Some(directory.to_string());
+        cfg.host = "unreachable.invalid".to_string();
+        let driver = Driver::create_driver(&cfg).await.unwrap();
+        let tcp: bool = sqlx::query_scalar("SELECT inet_server_addr() IS NOT NULL")
+            .fetch_one(&driver.pool)
+            .await
+            .unwrap();
+        assert!(
+            !tcp,
+            "The connection should go through the Unix domain socket"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_tls() {
+        let mut cfg = init_pg_test_service().unwrap();
+        let ssl: String = sqlx::query_scalar("SHOW ssl")
+            .fetch_one(&get_driver().await.pool)
+            .await
+            .unwrap();
+
+        // The server only negotiates TLS over TCP.
+        cfg.socket = None;
+        // The default encrypts whenever the server offers TLS.
+        cfg.tls.mode = db::SslMode::default();
+        let driver = Driver::create_driver(&cfg).await.unwrap();
+        assert_eq!(is_encrypted(&driver).await, ssl == "on");
+
+        cfg.tls.mode = db::SslMode::Disable;
+        let driver = Driver::create_driver(&cfg).await.unwrap();
+        assert!(!is_encrypted(&driver).await);
+
+        // Require fails against a server without TLS instead of falling back to plain text.
+        cfg.tls.mode = db::SslMode::Require;
+        let driver = Driver::create_driver(&cfg).await;
+        assert_eq!(driver.is_ok(), ssl == "on", "{driver:?}");
+        if let Ok(driver) = driver {
+            assert!(is_encrypted(&driver).await);
+        }
+    }
+
     #[tokio::test]
     async fn test_postgres_schema_validation() {
         // Initialize the test schema
@@ -518,7 +1493,8 @@ mod test {
         let test_config = init_pg_test_service().unwrap();
 
         // Test 1: Verify database version
-        let version = driver.get_version().await.unwrap();
+        let mut conn = driver.pool.acquire().await.unwrap();
+        let version = driver.get_version(&mut conn).await.unwrap();
         println!("PostgreSQL version: {}", version);
         assert!(!version.is_empty(), "Version should not be empty");
 
@@ -529,6 +1505,22 @@ mod test {
         // Test 3: Verify database name
         assert_eq!(db.name, test_config.database, "Database name should match");
 
+        // Test 3.1: Verify extensions
+        let pg_trgm = db
+            .extensions
+            .iter()
+            .find(|e| e.name == "pg_trgm")
+            .expect("pg_trgm extension should exist");
+        assert_eq!(pg_trgm.schema, "public");
+        assert!(
+            !pg_trgm.version.is_empty(),
+            "Extension version should not be empty"
+        );
+        assert!(
+            !pg_trgm.description.is_empty(),
+            "Extension description should not be empty"
+        );
+
         // Test 4: Verify schemas
         // Should have at least 2 custom schemas: sales and inventory
         assert!(
@@ -551,8 +1543,23 @@ mod test {
         // Test 5: Verify tables in sales schema
         assert_eq!(
             sales_schema.tables.len(),
-            3,
-            "sales schema should have 3 tables"
+            5,
+            "sales schema should have 5 tables, partitions are not listed"
+        );
+        let notes_table = sales_schema
+            .tables
+            .iter()
+            .find(|t| t.name == "order_event_notes")
+            .expect("order_event_notes table should exist");
+        let notes_fks: Vec<(&str, &str)> = notes_table
+            .foreign_keys
+            .iter()
+            .map(|fk| (fk.name.as_str(), fk.referenced_table.as_str()))
+            .collect();
+        assert_eq!(
+            notes_fks,
+            vec![("fk_order_event_notes_event", "order_events")],
+            "Partition clones of a foreign key should not be listed"
         );
 
         let customers_table = sales_schema
@@ -607,6 +1614,18 @@ mod test {
             .find(|i| i.primary)
             .expect("PRIMARY index should exist");
         assert!(primary_idx.primary, "Should be marked as primary");
+        assert!(primary_idx.unique, "PRIMARY should be unique");
+        assert_eq!(primary_idx.expressions, vec!["customer_id"]);
+        assert_eq!(primary_idx.key_length, vec![-1]);
+        assert_eq!(primary_idx.r#type, "btree");
+
+        let name_idx = customers_table
+            .indexes
+            .iter()
+            .find(|i| i.name == "idx_customers_name")
+            .expect("idx_customers_name should exist");
+        assert!(!name_idx.unique, "idx_customers_name should not be unique");
+        assert_eq!(name_idx.expressions, vec!["last_name", "first_name"]);
 
         // Test 6: Verify orders table
         let orders_table = sales_schema
@@ -616,6 +1635,21 @@ mod test {
             .expect("orders table should exist in sales schema");
 
         assert_eq!(orders_table.comment, "Customer orders");
+        assert_eq!(
+            orders_table.foreign_keys.len(),
+            1,
+            "orders table should have 1 foreign key"
+        );
+
+        let fk_orders_customer = &orders_table.foreign_keys[0];
+        assert_eq!(fk_orders_customer.name, "fk_orders_customer");
+        assert_eq!(fk_orders_customer.columns, vec!["customer_id"]);
+        assert_eq!(fk_orders_customer.referenced_schema, "sales");
+        assert_eq!(fk_orders_customer.referenced_table, "customers");
+        assert_eq!(fk_orders_customer.referenced_columns, vec!["customer_id"]);
+        assert_eq!(fk_orders_customer.on_delete, "CASCADE");
+        assert_eq!(fk_orders_customer.on_update, "CASCADE");
+        assert_eq!(fk_orders_customer.match_type, "SIMPLE");
 
         // Verify status column uses custom enum type
         let status_col = orders_table
@@ -642,12 +1676,68 @@ mod test {
             .expect("products table should exist in inventory schema");
 
         assert_eq!(products_table.comment, "Product catalog");
+
+        // Verify expression index
+        let lower_name_idx = products_table
+            .indexes
+            .iter()
+            .find(|i| i.name == "idx_products_lower_name")
+            .expect("idx_products_lower_name should exist");
+        assert!(
+            lower_name_idx.unique,
+            "idx_products_lower_name should be unique"
+        );
+        assert_eq!(
+            lower_name_idx.expressions,
+            vec!["lower(product_name::text)"]
+        );
+
+        // Verify partial covering index
+        let in_stock_idx = products_table
+            .indexes
+            .iter()
+            .find(|i| i.name == "idx_products_in_stock")
+            .expect("idx_products_in_stock should exist");
+        assert_eq!(in_stock_idx.expressions, vec!["category"]);
+        assert_eq!(in_stock_idx.include_columns, vec!["price"]);
+        assert_eq!(in_stock_idx.predicate, "stock_quantity > 0");
         assert_eq!(
             products_table.columns.len(),
             7,
             "products table should have 7 columns"
         );
 
+        // Verify the trigger, the internal foreign key triggers are skipped
+        assert_eq!(products_table.triggers.len(), 1);
+        let trigger = &products_table.triggers[0];
+        assert_eq!(trigger.name, "trg_products_trim_name");
+        assert_eq!(trigger.timing, "BEFORE");
+        assert_eq!(trigger.events, vec!["INSERT", "UPDATE"]);
+        assert!(trigger.for_each_row);
+        assert!(trigger.enabled);
+        assert_eq!(
+            trigger.definition,
+            "CREATE TRIGGER trg_products_trim_name BEFORE INSERT OR UPDATE OF product_name ON inventory.products FOR EACH ROW EXECUTE FUNCTION inventory.trim_product_name()"
+        );
+        assert!(sales_schema.tables.iter().all(|t| t.triggers.is_empty()));
+
+        // Test 7.1: Verify foreign tables in inventory schema
+        assert_eq!(
+            inventory_schema.external_tables.len(),
+            1,
+            "inventory schema should have 1 foreign table"
+        );
+        let remote_products = &inventory_schema.external_tables[0];
+        assert_eq!(remote_products.name, "remote_products");
+        assert_eq!(remote_products.external_server_name, "loopback");
+        assert_eq!(remote_products.external_database_name, test_config.database);
+        let remote_columns: Vec<&str> = remote_products
+            .columns
+            .iter()
+            .map(|c| c.name.as_str())
+            .collect();
+        assert_eq!(remote_columns, vec!["product_id", "product_name", "price"]);
+
         // Test 8: Verify order_items table
         let order_items_table = sales_schema
             .tables
@@ -656,6 +1746,70 @@ mod test {
             .expect("order_items table should exist in sales schema");
 
         assert_eq!(order_items_table.comment, "Items in orders");
+        assert_eq!(
+            order_items_table.foreign_keys.len(),
+            2,
+            "order_items table should have 2 foreign keys"
+        );
+
+        // Verify cross-schema foreign key to inventory.products
+        let fk_to_products = order_items_table
+            .foreign_keys
+            .iter()
+            .find(|fk| fk.name == "fk_order_items_product")
+            .expect("fk_order_items_product should exist");
+        assert_eq!(fk_to_products.referenced_schema, "inventory");
+        assert_eq!(fk_to_products.referenced_table, "products");
+        assert_eq!(fk_to_products.on_delete, "RESTRICT");
+        assert!(order_items_table.partitioning.is_none());
+
+        // Test 8.1: Verify partitions are nested under order_events
+        let order_events_table = sales_schema
+            .tables
+            .iter()
+            .find(|t| t.name == "order_events")
+            .expect("order_events table should exist in sales schema");
+        let partitioning = order_events_table
+            .partitioning
+            .as_ref()
+            .expect("order_events should be partitioned");
+        assert_eq!(partitioning.strategy, db::store::PartitionStrategy::Range);
+        assert_eq!(partitioning.expression, "created_at");
+        let partitions: Vec<(&str, &str)> = partitioning
+            .partitions
+            .iter()
+            .map(|p| (p.name.as_str(), p.value.as_str()))
+            .collect();
+        assert_eq!(
+            partitions,
+            vec![
+                (
+                    "order_events_2024",
+                    "FOR VALUES FROM ('2024-01-01') TO ('2025-01-01')"
+                ),
+                (
+                    "order_events_2025",
+                    "FOR VALUES FROM ('2025-01-01') TO ('2026-01-01')"
+                ),
+                ("order_events_default", "DEFAULT"),
+            ]
+        );
+        assert!(partitioning.partitions[0].subpartitioning.is_none());
+        let subpartitioning = partitioning.partitions[1]
+            .subpartitioning
+            .as_ref()
+            .expect("order_events_2025 should be partitioned");
+        assert_eq!(subpartitioning.strategy, db::store::PartitionStrategy::Hash);
+        assert_eq!(subpartitioning.expression, "event_id");
+        let subpartitions: Vec<&str> = subpartitioning
+            .partitions
+            .iter()
+            .map(|p| p.name.as_str())
+            .collect();
+        assert_eq!(
+            subpartitions,
+            vec!["order_events_2025_0", "order_events_2025_1"]
+        );
 
         // Test 9: Verify views in sales schema
         assert_eq!(
@@ -670,6 +1824,23 @@ mod test {
             !customer_orders_view.definition.is_empty(),
             "View definition should not be empty"
         );
+        let depends_on = |schema: &str, table: &str, column: &str| db::store::DependentColumn {
+            schema: schema.to_string(),
+            table: table.to_string(),
+            column: column.to_string(),
+        };
+        assert_eq!(
+            customer_orders_view.dependent_columns,
+            vec![
+                depends_on("sales", "customers", "customer_id"),
+                depends_on("sales", "customers", "email"),
+                depends_on("sales", "customers", "first_name"),
+                depends_on("sales", "customers", "last_name"),
+                depends_on("sales", "orders", "order_id"),
+                depends_on("sales", "orders", "customer_id"),
+                depends_on("sales", "orders", "total_amount"),
+            ]
+        );
 
         // Test 10: Verify materialized views in sales schema
         assert_eq!(
@@ -684,11 +1855,79 @@ mod test {
             !monthly_sales_mv.definition.is_empty(),
             "Materialized view definition should not be empty"
         );
+        assert_eq!(
+            monthly_sales_mv.dependent_columns,
+            vec![
+                depends_on("sales", "orders", "order_date"),
+                depends_on("sales", "orders", "total_amount"),
+            ]
+        );
+
+        // Test 11: Verify functions in sales schema, overloads are kept apart by signature
+        assert_eq!(
+            sales_schema.functions.len(),
+            2,
+            "sales schema should have 2 functions"
+        );
+        let calculate_order_total_func = sales_schema
+            .functions
+            .iter()
+            .find(|f| f.signature == "calculate_order_total(order_id_param integer)")
+            .expect("calculate_order_total(integer) should exist");
+        assert_eq!(calculate_order_total_func.name, "calculate_order_total");
+        assert!(
+            calculate_order_total_func
+                .definition
+                .contains("CREATE OR REPLACE FUNCTION sales.calculate_order_total"),
+            "Function definition should be a CREATE FUNCTION statement"
+        );
+        assert_eq!(
+            calculate_order_total_func.parameters,
+            vec![db::store::RoutineParameter {
+                name: "order_id_param".to_string(),
+                mode: db::store::ParameterMode::In,
+                r#type: "integer".to_string(),
+            }]
+        );
+        assert_eq!(calculate_order_total_func.return_type, "numeric");
+        assert_eq!(calculate_order_total_func.language, "plpgsql");
+        assert_eq!(
+            calculate_order_total_func.volatility,
+            db::store::Volatility::Stable
+        );
+        assert_eq!(
+            calculate_order_total_func.security,
+            db::store::SecurityType::Invoker
+        );
+        assert!(!calculate_order_total_func.owner.is_empty());
+        assert!(
+            sales_schema.functions.iter().any(|f| f.signature
+                == "calculate_order_total(order_id_param integer, apply_discount boolean)"),
+            "calculate_order_total(integer, boolean) should exist"
+        );
 
-        // Test 11: Verify functions in sales schema
-        println!("Functions count: {}", sales_schema.functions.len());
-        // Note: Functions are commented out in the current implementation
-        // When implemented, we can verify: calculate_order_total function exists
+        // Test 12: Verify procedures in sales schema
+        assert_eq!(
+            sales_schema.procedures.len(),
+            1,
+            "sales schema should have 1 procedure"
+        );
+        let cancel_order_proc = &sales_schema.procedures[0];
+        assert_eq!(cancel_order_proc.name, "cancel_order");
+        assert_eq!(
+            cancel_order_proc.signature,
+            "cancel_order(IN order_id_param integer)"
+        );
+        assert!(
+            !cancel_order_proc.definition.is_empty(),
+            "Procedure definition should not be empty"
+        );
+        assert_eq!(cancel_order_proc.language, "sql");
+        assert_eq!(cancel_order_proc.parameters.len(), 1);
+        assert_eq!(
+            cancel_order_proc.parameters[0].mode,
+            db::store::ParameterMode::In
+        );
 
         println!("✓ All PostgreSQL schema validation tests passed!");
     }
@@ -696,32 +1935,71 @@ mod test {
     #[tokio::test]
     async fn test_schema() {
         let d = get_driver().await;
-        let v = d.get_version().await.unwrap();
+        let mut conn = d.pool.acquire().await.unwrap();
+        let v = d.get_version(&mut conn).await.unwrap();
         println!("Postgres version: {}", v);
 
-        let databases = d.load_database().await.unwrap();
+        let databases = d.load_database(&mut conn).await.unwrap();
         println!("Databases: {:?}", databases);
 
-        let schemas = d.load_schema().await.unwrap();
+        let schemas = d.load_schema(&mut conn).await.unwrap();
         println!("Schemas: {:?}", schemas);
+
+        let extensions = d.load_extension(&mut conn).await.unwrap();
+        println!("Extensions: {:?}", extensions);
     }
 
     #[tokio::test]
     async fn test_table() {
         let d = get_driver().await;
-        let column_map = d.load_column().await.unwrap();
+        let mut conn = d.pool.acquire().await.unwrap();
+        let column_map = d
+            .load_column(&mut conn, &mut db::util::Warnings::new(false))
+            .await
+            .unwrap();
         println!("Columns: {:?} \n", column_map);
 
-        let index_map = d.load_index().await.unwrap();
+        let index_map = d.load_index(&mut conn).await.unwrap();
         println!("Indexes: {:?} \n", index_map);
 
-        let table_map = d.load_table(&column_map, &index_map).await.unwrap();
+        let fk_map = d
+            .load_foreign_key(&mut conn, &mut crate::db::util::Warnings::new(false))
+            .await
+            .unwrap();
+        println!("Foreign Keys: {:?} \n", fk_map);
+
+        let trigger_map = d.load_trigger(&mut conn).await.unwrap();
+        println!("Triggers: {:?} \n", trigger_map);
+
+        let partition_map = d
+            .load_partition(&mut conn, &mut crate::db::util::Warnings::new(false))
+            .await
+            .unwrap();
+        println!("Partitions: {:?} \n", partition_map);
+
+        let table_map = d
+            .load_table(
+                &mut conn,
+                &column_map,
+                &index_map,
+                &fk_map,
+                &trigger_map,
+                &partition_map,
+            )
+            .await
+            .unwrap();
         println!("Tables: {:?} \n", table_map);
 
-        let view_map = d.load_view().await.unwrap();
+        let view_dependencies = d.load_view_dependency(&mut conn).await.unwrap();
+        println!("View Dependencies: {:?} \n", view_dependencies);
+
+        let view_map = d.load_view(&mut conn, &view_dependencies).await.unwrap();
         println!("Views: {:?} \n", view_map);
 
-        let mat_view_map = d.get_materialized_view().await.unwrap();
+        let mat_view_map = d
+            .get_materialized_view(&mut conn, &view_dependencies)
+            .await
+            .unwrap();
         println!("Materialized Views: {:?} \n", mat_view_map);
     }
 
@@ -731,7 +2009,250 @@ mod test {
         let ins = d.sync_instance().await.unwrap();
         println!("Instance Metadata: {:?}", ins);
 
+        let cfg = init_pg_test_service().unwrap();
+        let current_user = ins
+            .instance_roles
+            .iter()
+            .find(|r| r.name == cfg.username)
+            .expect("current user should be listed as an instance role");
+        assert!(
+            current_user.grant.starts_with("CREATE ROLE "),
+            "Role grant should be rendered as CREATE ROLE statement"
+        );
+        assert!(
+            current_user.grant.contains(" LOGIN"),
+            "Current user should be able to login"
+        );
+        assert!(
+            !ins.instance_roles.iter().any(|r| r.name.starts_with("pg_")),
+            "Predefined roles should be skipped"
+        );
+
         let s = d.sync_database().await.unwrap();
         println!("Database Metadata: {:?}", s);
     }
+
+    // Syncs the schemas created by the fixture. Foreign tables are left out, they are not
+    // rendered as DDL.
+    async fn sync_fixture_schemas(driver: &Driver) -> db::store::DatabaseSchemaMetadata {
+        let mut database = driver.sync_database().await.unwrap();
+        database
+            .schemas
+            .retain(|s| s.name == "sales" || s.name == "inventory");
+        for schema in database.schemas.iter_mut() {
+            schema.external_tables.clear();
+        }
+        database
+    }
+
+    // Creates a scratch database and recreates the fixture schemas of the source in it.
+    async fn create_scratch_database(
+        driver: &Driver,
+        suffix: &str,
+        source: &db::store::DatabaseSchemaMetadata,
+    ) -> Driver {
+        let mut cfg = init_pg_test_service().unwrap();
+        cfg.database = format!("{}_{suffix}", cfg.database);
+        driver
+            .pool
+            .execute(format!("DROP DATABASE IF EXISTS \"{}\" WITH (FORCE)", cfg.database).as_str())
+            .await
+            .unwrap();
+        driver
+            .pool
+            .execute(format!("CREATE DATABASE \"{}\"", cfg.database).as_str())
+            .await
+            .unwrap();
+
+        let scratch = Driver::create_driver(&cfg).await.unwrap();
+        // Types are not part of the store, the enum used by sales.orders is created by hand.
+        scratch
+            .pool
+            .execute(
+                "CREATE SCHEMA sales;
+                CREATE TYPE sales.order_status AS ENUM ('pending', 'processing', 'shipped', 'delivered', 'cancelled');",
+            )
+            .await
+            .unwrap();
+        for name in ["inventory", "sales"] {
+            let schema = source.schemas.iter().find(|s| s.name == name).unwrap();
+            for stmt in db::ddl::postgres::create_schema(schema) {
+                if let Err(e) = scratch.pool.execute(stmt.as_str()).await {
+                    panic!("Failed to execute {stmt}: {e}");
+                }
+            }
+        }
+        scratch
+    }
+
+    async fn drop_scratch_database(driver: &Driver, scratch: Driver) {
+        scratch.pool.close().await;
+        driver
+            .pool
+            .execute(format!("DROP DATABASE \"{}\" WITH (FORCE)", scratch.database_name).as_str())
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_create_schema_round_trip() {
+        init_postgres_test_schema()
+            .await
+            .expect("Failed to initialize test schema");
+
+        let d = get_driver().await;
+        let source = sync_fixture_schemas(&d).await;
+
+        let scratch = create_scratch_database(&d, "ddl_round_trip", &source).await;
+        let target = sync_fixture_schemas(&scratch).await;
+        // An index on a partitioned table is only valid once every partition is indexed.
+        let invalid_indexes: i64 =
+            sqlx::query_scalar("SELECT count(*) FROM pg_index WHERE NOT indisvalid")
+                .fetch_one(&scratch.pool)
+                .await
+                .unwrap();
+        drop_scratch_database(&d, scratch).await;
+        assert_eq!(invalid_indexes, 0);
+
+        let changes = db::diff::diff_database(&source, &target);
+        assert!(
+            changes.is_empty(),
+            "Recreated schemas should match the source, got {changes:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_plan_migration() {
+        init_postgres_test_schema()
+            .await
+            .expect("Failed to initialize test schema");
+
+        let d = get_driver().await;
+        let source = sync_fixture_schemas(&d).await;
+
+        let mut target = source.clone();
+        let sales = target
+            .schemas
+            .iter_mut()
+            .find(|s| s.name == "sales")
+            .unwrap();
+        let customers = sales
+            .tables
+            .iter_mut()
+            .find(|t| t.name == "customers")
+            .unwrap();
+        // The email column is used by sales.customer_order_summary.
+        let email = customers
+            .columns
+            .iter_mut()
+            .find(|c| c.name == "email")
+            .unwrap();
+        email.r#type = "character varying(320)".to_string();
+        customers.columns.push(db::store::ColumnMetadata {
+            name: "loyalty_points".to_string(),
+            position: customers.columns.len() as i32 + 1,
+            default: "0".to_string(),
+            on_update: None,
+            nullable: false,
+            r#type: "integer".to_string(),
+            character_set: String::new(),
+            collation: String::new(),
+            comment: "Reward points".to_string(),
+            identity_generation: db::store::IdentityGeneration::UNSPECIFIED,
+        });
+        customers.indexes.retain(|i| i.name != "idx_customers_city");
+        let orders = sales
+            .tables
+            .iter_mut()
+            .find(|t| t.name == "orders")
+            .unwrap();
+        orders.comment = "Orders placed by customers".to_string();
+
+        let steps = db::migration::plan_migration(
+            db::migration::Dialect::from(&d.get_engine()),
+            &source,
+            &target,
+        );
+        assert!(
+            steps
+                .iter()
+                .any(|s| s.statement == r#"DROP VIEW "sales"."customer_order_summary";"#),
+            "The view using the changed column should be recreated"
+        );
+        assert!(
+            steps.iter().all(|s| !s.is_destructive()),
+            "Widening a column should not be destructive"
+        );
+
+        let scratch = create_scratch_database(&d, "migration", &source).await;
+        for step in &steps {
+            if let Err(e) = scratch.pool.execute(step.statement.as_str()).await {
+                panic!("Failed to execute {}: {e}", step.statement);
+            }
+        }
+        let migrated = sync_fixture_schemas(&scratch).await;
+        drop_scratch_database(&d, scratch).await;
+
+        let changes = db::diff::diff_database(&target, &migrated);
+        assert!(
+            changes.is_empty(),
+            "Migrated schemas should match the target, got {changes:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_parse_fixture() {
+        init_postgres_test_schema()
+            .await
+            .expect("Failed to initialize test schema");
+
+        let d = get_driver().await;
+        let synced = sync_fixture_schemas(&d).await;
+
+        let sql = std::fs::read_to_string("tests/fixtures/postgres_schema.sql").unwrap();
+        let mut parsed = db::parser::postgres::parse_database(&synced.name, &sql).unwrap();
+        parsed
+            .schemas
+            .retain(|s| s.name == "sales" || s.name == "inventory");
+        for schema in parsed.schemas.iter_mut() {
+            schema.external_tables.clear();
+        }
+
+        // Definitions are kept as written by the parser and deparsed by the server, and the file
+        // sets no owners.
+        let options = db::diff::DiffOptions {
+            ignore_definitions: true,
+            ignore_owners: true,
+        };
+        let changes = db::diff::diff_database_with(&synced, &parsed, &options);
+        assert!(
+            changes.is_empty(),
+            "Parsed schemas should match the synced ones, got {changes:#?}"
+        );
+
+        // Compared with their definitions, the parsed schemas only differ by the definitions
+        // parse_database keeps as written.
+        let options = db::diff::DiffOptions {
+            ignore_definitions: false,
+            ignore_owners: true,
+        };
+        let mut changes: Vec<String> = db::diff::diff_database_with(&synced, &parsed, &options)
+            .iter()
+            .map(|c| c.to_string())
+            .collect();
+        changes.sort();
+        assert_eq!(
+            changes,
+            vec![
+                "inventory.products: index idx_products_lower_name changed",
+                "inventory.products: trigger trg_products_trim_name changed",
+                "inventory: function trim_product_name() changed",
+                "sales.customer_order_summary: view changed",
+                "sales.monthly_sales: materialized view changed",
+                "sales: function calculate_order_total(order_id_param integer) changed",
+                "sales: function calculate_order_total(order_id_param integer, apply_discount boolean) changed",
+                "sales: procedure cancel_order(IN order_id_param integer) changed",
+            ]
+        );
+    }
 }
@@ -1,4 +1,5 @@
 #[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct InstanceMetadata {
     pub version: String,
     pub instance_roles: Vec<InstanceRoleMetadata>,
@@ -7,14 +8,17 @@ pub struct InstanceMetadata {
 }
 
 #[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct InstanceRoleMetadata {
     // The role name. It's unique within the instance.
     pub name: String,
-    // The grant display string on the instance. It's generated by database engine.
+    // The grant display string on the instance, one statement per line. It's generated by database
+    // engine.
     pub grant: String,
 }
 
 #[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DatabaseSchemaMetadata {
     // The name is the name of the database.
     pub name: String,
@@ -34,7 +38,19 @@ pub struct DatabaseSchemaMetadata {
     pub owner: String,
 }
 
+// SyncWarning is an object a lenient sync skipped or loaded only partially.
 #[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SyncWarning {
+    // The path names the object within the database, outermost first, e.g. the schema, table
+    // and column. MySQL objects have no schema.
+    pub path: Vec<String>,
+    // The reason is why the object was skipped or degraded.
+    pub reason: String,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ExtensionMetadata {
     // The name is the name of an extension.
     pub name: String,
@@ -47,6 +63,7 @@ pub struct ExtensionMetadata {
 }
 
 #[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SchemaMetadata {
     // The name is the schema name.
     // It is an empty string for databases without such concept such as MySQL.
@@ -63,12 +80,15 @@ pub struct SchemaMetadata {
     pub procedures: Vec<ProcedureMetadata>,
     // The materialized_views is the list of materialized views in a schema.
     pub materialized_views: Vec<MaterializedViewMetadata>,
+    // The events is the list of scheduled events in a schema. Only MySQL has events.
+    pub events: Vec<EventMetadata>,
 
     pub owner: String,
     pub comment: String,
 }
 
 #[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ExternalTableMetadata {
     // The name is the name of a external table.
     pub name: String,
@@ -81,6 +101,7 @@ pub struct ExternalTableMetadata {
 }
 
 #[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ViewMetadata {
     // The name is the name of a view.
     pub name: String,
@@ -93,22 +114,132 @@ pub struct ViewMetadata {
 }
 
 #[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FunctionMetadata {
     // The name is the name of a function.
     pub name: String,
+    // The signature is the name with the argument list of a function, e.g. add(a integer, b integer).
+    // It identifies overloaded functions. It is an empty string for databases without overloading such as MySQL.
+    pub signature: String,
     // The definition is the definition of a function.
     pub definition: String,
+    // The parameters is the ordered list of parameters of a function.
+    pub parameters: Vec<RoutineParameter>,
+    // The return_type is the type a function returns, e.g. integer or SETOF record.
+    pub return_type: String,
+    // The language is the language a function is written in, e.g. SQL or plpgsql.
+    pub language: String,
+    // The volatility is IMMUTABLE for a MySQL function declared DETERMINISTIC.
+    pub volatility: Volatility,
+    // The security tells whose privileges a function runs with.
+    pub security: SecurityType,
+    // The owner is the owner of a function, the definer user@host on MySQL.
+    pub owner: String,
+    // The comment is the comment of a function.
+    pub comment: String,
 }
 
 #[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ProcedureMetadata {
     // The name is the name of a function.
     pub name: String,
+    // The signature is the name with the argument list of a procedure.
+    // It identifies overloaded procedures. It is an empty string for databases without overloading such as MySQL.
+    pub signature: String,
     // The definition is the definition of a function.
     pub definition: String,
+    // The parameters is the ordered list of parameters of a procedure.
+    pub parameters: Vec<RoutineParameter>,
+    // The language is the language a procedure is written in, e.g. SQL or plpgsql.
+    pub language: String,
+    // The volatility is IMMUTABLE for a MySQL procedure declared DETERMINISTIC.
+    pub volatility: Volatility,
+    // The security tells whose privileges a procedure runs with.
+    pub security: SecurityType,
+    // The owner is the owner of a procedure, the definer user@host on MySQL.
+    pub owner: String,
+    // The comment is the comment of a procedure.
+    pub comment: String,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EventMetadata {
+    // The name is the name of an event.
+    pub name: String,
+    // The definition is the CREATE EVENT statement of an event.
+    pub definition: String,
+    // The schedule is the ON SCHEDULE clause of an event, e.g. EVERY 1 DAY STARTS '2024-01-01 03:00:00'.
+    pub schedule: String,
+    // The time_zone is the time zone the schedule is given in.
+    pub time_zone: String,
+    // The status is ENABLED, DISABLED or SLAVESIDE_DISABLED for an event disabled on a replica.
+    pub status: String,
+    // The on_completion is PRESERVE or NOT PRESERVE, i.e. whether an event is kept once it expires.
+    pub on_completion: String,
+    // The definer is the user@host whose privileges an event runs with.
+    pub definer: String,
+    // The body is the statement an event runs.
+    pub body: String,
+    // The comment is the comment of an event.
+    pub comment: String,
 }
 
 #[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RoutineParameter {
+    // The name is the name of a parameter. It is empty for an unnamed PG parameter.
+    pub name: String,
+    // The mode is how a parameter passes its value.
+    pub mode: ParameterMode,
+    // The type is the data type of a parameter.
+    pub r#type: String,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ParameterMode {
+    #[cfg_attr(feature = "serde", serde(rename = "IN"))]
+    In,
+    #[cfg_attr(feature = "serde", serde(rename = "OUT"))]
+    Out,
+    #[cfg_attr(feature = "serde", serde(rename = "INOUT"))]
+    InOut,
+    // Variadic is PG only.
+    #[cfg_attr(feature = "serde", serde(rename = "VARIADIC"))]
+    Variadic,
+    // Table is a column of a PG RETURNS TABLE function.
+    #[cfg_attr(feature = "serde", serde(rename = "TABLE"))]
+    Table,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Volatility {
+    #[cfg_attr(feature = "serde", serde(rename = "UNSPECIFIED"))]
+    UNSPECIFIED,
+    #[cfg_attr(feature = "serde", serde(rename = "IMMUTABLE"))]
+    Immutable,
+    #[cfg_attr(feature = "serde", serde(rename = "STABLE"))]
+    Stable,
+    #[cfg_attr(feature = "serde", serde(rename = "VOLATILE"))]
+    Volatile,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SecurityType {
+    #[cfg_attr(feature = "serde", serde(rename = "UNSPECIFIED"))]
+    UNSPECIFIED,
+    #[cfg_attr(feature = "serde", serde(rename = "DEFINER"))]
+    Definer,
+    #[cfg_attr(feature = "serde", serde(rename = "INVOKER"))]
+    Invoker,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MaterializedViewMetadata {
     // The name is the name of a materialized view.
     pub name: String,
@@ -121,6 +252,7 @@ pub struct MaterializedViewMetadata {
 }
 
 #[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DependentColumn {
     // The schema is the schema of a reference column.
     pub schema: String,
@@ -131,6 +263,7 @@ pub struct DependentColumn {
 }
 
 #[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TableMetadata {
     // The name is the name of a table.
     pub name: String,
@@ -158,9 +291,82 @@ pub struct TableMetadata {
     pub foreign_keys: Vec<ForeignKeyMetadata>,
     // The owner is the owner of a table.
     pub owner: String,
+    // The triggers is the list of triggers on a table.
+    pub triggers: Vec<TriggerMetadata>,
+    // The partitioning is the partition strategy, key and partitions of a partitioned table.
+    // PG partitions are listed here instead of as tables of their own.
+    pub partitioning: Option<PartitioningMetadata>,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TriggerMetadata {
+    // The name is the name of a trigger.
+    pub name: String,
+    // The timing is BEFORE, AFTER or INSTEAD OF.
+    pub timing: String,
+    // The events is the list of events firing a trigger, e.g. INSERT and UPDATE.
+    pub events: Vec<String>,
+    // The for_each_row is true for a row level trigger and false for a statement level one.
+    pub for_each_row: bool,
+    // The definition is the CREATE TRIGGER statement of a trigger.
+    pub definition: String,
+    // The enabled is false for a disabled PG trigger. MySQL triggers are always enabled.
+    pub enabled: bool,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PartitioningMetadata {
+    // The strategy is how rows are assigned to partitions.
+    pub strategy: PartitionStrategy,
+    // The expression is the partition key, e.g. a column list or YEAR(order_date).
+    pub expression: String,
+    // The partitions is the ordered list of partitions.
+    pub partitions: Vec<PartitionMetadata>,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PartitionMetadata {
+    // The name is the name of a partition, the table name of a PG partition.
+    pub name: String,
+    // The value is the bound of a partition as the database reports it, e.g. 2024 for a MySQL
+    // VALUES LESS THAN (2024) partition or FOR VALUES FROM ('2024-01-01') TO ('2025-01-01') on PG.
+    // It is empty for MySQL HASH and KEY partitions.
+    pub value: String,
+    // The subpartitioning is how a partition is partitioned itself.
+    pub subpartitioning: Option<PartitioningMetadata>,
+    // The row_count is the estimated number of rows of a partition.
+    pub row_count: i64,
 }
 
 #[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PartitionStrategy {
+    #[cfg_attr(feature = "serde", serde(rename = "RANGE"))]
+    Range,
+    // RangeColumns is MySQL only.
+    #[cfg_attr(feature = "serde", serde(rename = "RANGE_COLUMNS"))]
+    RangeColumns,
+    #[cfg_attr(feature = "serde", serde(rename = "LIST"))]
+    List,
+    // ListColumns is MySQL only.
+    #[cfg_attr(feature = "serde", serde(rename = "LIST_COLUMNS"))]
+    ListColumns,
+    #[cfg_attr(feature = "serde", serde(rename = "HASH"))]
+    Hash,
+    // LinearHash, Key and LinearKey are MySQL only.
+    #[cfg_attr(feature = "serde", serde(rename = "LINEAR_HASH"))]
+    LinearHash,
+    #[cfg_attr(feature = "serde", serde(rename = "KEY"))]
+    Key,
+    #[cfg_attr(feature = "serde", serde(rename = "LINEAR_KEY"))]
+    LinearKey,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ColumnMetadata {
     // The name is the name of a column.
     pub name: String,
@@ -186,13 +392,18 @@ pub struct ColumnMetadata {
 }
 
 #[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum IdentityGeneration {
+    #[cfg_attr(feature = "serde", serde(rename = "UNSPECIFIED"))]
     UNSPECIFIED,
+    #[cfg_attr(feature = "serde", serde(rename = "ALWAYS"))]
     Always,
+    #[cfg_attr(feature = "serde", serde(rename = "BY_DEFAULT"))]
     ByDefault,
 }
 
 #[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct IndexMetadata {
     // The name is the name of an index.
     pub name: String,
@@ -214,9 +425,14 @@ pub struct IndexMetadata {
     pub comment: String,
     // The definition of an index.
     pub definition: String,
+    // The include_columns are the ordered non-key columns of a covering index, PG only.
+    pub include_columns: Vec<String>,
+    // The predicate is the WHERE condition of a partial index, PG only.
+    pub predicate: String,
 }
 
 #[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ForeignKeyMetadata {
     // The name is the name of a foreign key.
     pub name: String,
@@ -237,3 +453,150 @@ pub struct ForeignKeyMetadata {
     // The match_type is the PostgreSQL specific field.
     pub match_type: String,
 }
+
+#[cfg(test)]
+#[cfg(feature = "serde")]
+mod test {
+    use super::*;
+
+    fn sample_database() -> DatabaseSchemaMetadata {
+        let column = ColumnMetadata {
+            name: "id".to_string(),
+            position: 1,
+            default: String::new(),
+            on_update: None,
+            nullable: false,
+            r#type: "integer".to_string(),
+            character_set: String::new(),
+            collation: String::new(),
+            comment: "Unique identifier".to_string(),
+            identity_generation: IdentityGeneration::ByDefault,
+        };
+        let table = TableMetadata {
+            name: "customers".to_string(),
+            columns: vec![column.clone()],
+            indexes: vec![IndexMetadata {
+                name: "customers_pkey".to_string(),
+                expressions: vec!["id".to_string()],
+                key_length: vec![-1],
+                r#type: "btree".to_string(),
+                unique: true,
+                primary: true,
+                visible: true,
+                comment: String::new(),
+                definition:
+                    "CREATE UNIQUE INDEX customers_pkey ON sales.customers USING btree (id)"
+                        .to_string(),
+                include_columns: vec![],
+                predicate: String::new(),
+            }],
+            engine: String::new(),
+            collation: None,
+            row_count: 3,
+            data_size: 8192,
+            index_size: 16384,
+            data_free: 0,
+            create_options: String::new(),
+            comment: "Customer information".to_string(),
+            foreign_keys: vec![ForeignKeyMetadata {
+                name: "fk_customers_region".to_string(),
+                columns: vec!["region_id".to_string()],
+                referenced_schema: "sales".to_string(),
+                referenced_table: "regions".to_string(),
+                referenced_columns: vec!["id".to_string()],
+                on_delete: "CASCADE".to_string(),
+                on_update: "NO ACTION".to_string(),
+                match_type: "SIMPLE".to_string(),
+            }],
+            owner: "postgres".to_string(),
+            triggers: vec![],
+            partitioning: None,
+        };
+        let schema = SchemaMetadata {
+            name: "sales".to_string(),
+            tables: vec![table],
+            external_tables: vec![ExternalTableMetadata {
+                name: "remote_customers".to_string(),
+                external_server_name: "loopback".to_string(),
+                external_database_name: "test_db".to_string(),
+                columns: vec![column],
+            }],
+            views: vec![ViewMetadata {
+                name: "customer_ids".to_string(),
+                definition: "SELECT id FROM sales.customers".to_string(),
+                comment: String::new(),
+                dependent_columns: vec![DependentColumn {
+                    schema: "sales".to_string(),
+                    table: "customers".to_string(),
+                    column: "id".to_string(),
+                }],
+            }],
+            functions: vec![FunctionMetadata {
+                name: "one".to_string(),
+                signature: "one()".to_string(),
+                definition:
+                    "CREATE FUNCTION sales.one() RETURNS integer LANGUAGE sql AS 'SELECT 1'"
+                        .to_string(),
+                parameters: vec![],
+                return_type: "integer".to_string(),
+                language: "sql".to_string(),
+                volatility: Volatility::Volatile,
+                security: SecurityType::Invoker,
+                owner: "postgres".to_string(),
+                comment: String::new(),
+            }],
+            procedures: vec![],
+            materialized_views: vec![],
+            events: vec![],
+            owner: "postgres".to_string(),
+            comment: String::new(),
+        };
+        DatabaseSchemaMetadata {
+            name: "test_db".to_string(),
+            schemas: vec![schema],
+            character_set: "UTF8".to_string(),
+            collation: "en_US.utf8".to_string(),
+            extensions: vec![ExtensionMetadata {
+                name: "pg_trgm".to_string(),
+                schema: "public".to_string(),
+                version: "1.6".to_string(),
+                description: "text similarity measurement".to_string(),
+            }],
+            datashare: false,
+            service_name: String::new(),
+            owner: "postgres".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let database = sample_database();
+
+        let json = serde_json::to_string(&database).unwrap();
+        let decoded: DatabaseSchemaMetadata = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, database);
+
+        let value = serde_json::to_value(&database).unwrap();
+        let column = &value["schemas"][0]["tables"][0]["columns"][0];
+        assert_eq!(column["type"], "integer");
+        assert_eq!(column["identity_generation"], "BY_DEFAULT");
+    }
+
+    #[test]
+    fn test_yaml_round_trip() {
+        let instance = InstanceMetadata {
+            version: "15.4.0".to_string(),
+            instance_roles: vec![InstanceRoleMetadata {
+                name: "test_user".to_string(),
+                grant: "CREATE ROLE test_user WITH LOGIN;".to_string(),
+            }],
+            databases: vec![sample_database()],
+            last_sync: 0,
+        };
+
+        let yaml = serde_yaml::to_string(&instance).unwrap();
+        let decoded: InstanceMetadata = serde_yaml::from_str(&yaml).unwrap();
+        assert_eq!(decoded, instance);
+        assert!(yaml.contains("identity_generation: BY_DEFAULT"));
+    }
+}
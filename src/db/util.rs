@@ -1,4 +1,8 @@
-use super::error::DBError;
+use super::error::{DBError, ErrorKind};
+use crate::db;
+use sqlx::pool::{Pool, PoolOptions};
+use sqlx::Connection;
+use std::time::Duration;
 
 // TableKey is the map key for table metadata.
 #[derive(PartialEq, Eq, Hash, Clone, Debug)]
@@ -9,10 +13,119 @@ pub(crate) struct TableKey {
     pub table: String,
 }
 
+// Warnings collects the objects a lenient sync skips or degrades. A strict sync fails on them
+// instead.
+pub(crate) struct Warnings {
+    lenient: bool,
+    warnings: Vec<db::store::SyncWarning>,
+}
+
+impl Warnings {
+    pub fn new(lenient: bool) -> Warnings {
+        Warnings {
+            lenient,
+            warnings: vec![],
+        }
+    }
+
+    // check passes a loaded value through. A failure fails a strict sync, while a lenient sync
+    // records it against the object at path and goes on without the value.
+    pub fn check<T>(
+        &mut self,
+        path: &[&str],
+        result: Result<T, DBError>,
+    ) -> Result<Option<T>, DBError> {
+        match result {
+            Ok(value) => Ok(Some(value)),
+            Err(e) if self.lenient => {
+                self.warnings.push(db::store::SyncWarning {
+                    path: path.iter().map(|name| name.to_string()).collect(),
+                    reason: e.to_string(),
+                });
+                Ok(None)
+            }
+            Err(e) => Err(e.with_context(path.join("."))),
+        }
+    }
+
+    pub fn into_inner(self) -> Vec<db::store::SyncWarning> {
+        self.warnings
+    }
+}
+
 pub(crate) fn convert_yes_no(s: &str) -> Result<bool, DBError> {
     match s {
         "YES" | "Y" | "1" => Ok(true),
         "NO" | "N" | "0" => Ok(false),
-        _ => Err(DBError::Unknow(format!("unrecognized isNullable type {s}"))),
+        _ => Err(DBError::unexpected_catalog(format!(
+            "unrecognized isNullable type {s}"
+        ))),
+    }
+}
+
+// pool_options returns the sqlx pool options of a pool config.
+pub(crate) fn pool_options<DB: sqlx::Database>(cfg: &db::PoolConfig) -> PoolOptions<DB> {
+    let mut options = PoolOptions::new();
+    if let Some(max_connections) = cfg.max_connections {
+        options = options.max_connections(max_connections);
+    }
+    if let Some(acquire_timeout) = cfg.acquire_timeout {
+        options = options.acquire_timeout(acquire_timeout);
+    }
+    options
+}
+
+// connect_pool opens a pool, giving up once the connect timeout elapses.
+pub(crate) async fn connect_pool<DB: sqlx::Database>(
+    options: PoolOptions<DB>,
+    connect_options: <DB::Connection as sqlx::Connection>::Options,
+    connect_timeout: Option<Duration>,
+) -> Result<Pool<DB>, DBError> {
+    let connect = async {
+        // The pool keeps retrying a refused connection and then only reports a pool timeout, so
+        // a plain connection is made first to surface the actual failure.
+        sqlx::ConnectOptions::connect(&connect_options)
+            .await?
+            .close()
+            .await?;
+        Ok::<_, DBError>(options.connect_with(connect_options).await?)
+    };
+    match connect_timeout {
+        Some(timeout) => tokio::time::timeout(timeout, connect).await.map_err(|_| {
+            DBError::new(
+                ErrorKind::Timeout,
+                format!("connect timed out after {timeout:?}"),
+            )
+        })?,
+        None => connect.await,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{convert_yes_no, Warnings};
+
+    #[test]
+    fn test_warnings() {
+        let mut strict = Warnings::new(false);
+        assert_eq!(
+            strict.check(&["t", "c"], convert_yes_no("YES")).unwrap(),
+            Some(true)
+        );
+        let err = strict
+            .check(&["t", "c"], convert_yes_no("MAYBE"))
+            .unwrap_err();
+        assert_eq!(err.to_string(), "t.c: unrecognized isNullable type MAYBE");
+        assert!(strict.into_inner().is_empty());
+
+        let mut lenient = Warnings::new(true);
+        assert_eq!(
+            lenient.check(&["t", "c"], convert_yes_no("MAYBE")).unwrap(),
+            None
+        );
+        let warnings = lenient.into_inner();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].path, vec!["t", "c"]);
+        assert_eq!(warnings[0].reason, "unrecognized isNullable type MAYBE");
     }
 }
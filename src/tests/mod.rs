@@ -1,4 +1,5 @@
 #[cfg(test)]
+#[cfg(any(feature = "db-mysql", feature = "db-tidb", feature = "db-postgres"))]
 mod utils;
 
 #[cfg(test)]
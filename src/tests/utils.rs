@@ -19,13 +19,26 @@ macro_rules! init_db_test_service {
                 .unwrap_or_default();
             let database = env::var(concat!("TEST_", stringify!($db_type), "_DB_DATABASE"))
                 .unwrap_or_default();
+            let socket = env::var(concat!("TEST_", stringify!($db_type), "_DB_SOCKET")).ok();
+            let tls = crate::db::TlsConfig {
+                mode: env::var(concat!("TEST_", stringify!($db_type), "_DB_SSL_MODE"))
+                    .map(|mode| mode.parse().map_err(|_| VarError::NotPresent))
+                    .unwrap_or(Ok(crate::db::SslMode::default()))?,
+                ca: env::var(concat!("TEST_", stringify!($db_type), "_DB_SSL_CA")).ok(),
+                cert: env::var(concat!("TEST_", stringify!($db_type), "_DB_SSL_CERT")).ok(),
+                key: env::var(concat!("TEST_", stringify!($db_type), "_DB_SSL_KEY")).ok(),
+            };
             Ok(ConnectionConfig {
                 engine: crate::db::Engine::$db_type,
                 host,
                 port,
+                socket,
                 username,
                 password,
                 database,
+                tls,
+                pool: crate::db::PoolConfig::default(),
+                params: vec![],
             })
         }
     };
@@ -35,6 +48,22 @@ init_db_test_service!(MYSQL, init_mysql_test_service, "3306");
 #[cfg(feature = "db-postgres")]
 init_db_test_service!(POSTGRES, init_pg_test_service, "5432");
 
+// Returns the arguments of the mysql command line client reaching the server of a config.
+#[cfg(any(feature = "db-mysql", feature = "db-tidb"))]
+fn mysql_client_args(config: &ConnectionConfig) -> Vec<String> {
+    match &config.socket {
+        Some(socket) => vec![
+            "--protocol=SOCKET".to_string(),
+            format!("--socket={socket}"),
+        ],
+        None => vec![
+            "--protocol=TCP".to_string(),
+            format!("--host={}", config.host),
+            format!("--port={}", config.port),
+        ],
+    }
+}
+
 #[cfg(any(feature = "db-mysql", feature = "db-tidb"))]
 pub async fn init_mysql_test_schema() -> Result<(), Box<dyn std::error::Error>> {
     let config = init_mysql_test_service()?;
@@ -46,9 +75,7 @@ pub async fn init_mysql_test_schema() -> Result<(), Box<dyn std::error::Error>>
     // Use MYSQL_PWD environment variable instead of command line argument for security
     let status = std::process::Command::new("mysql")
         .env("MYSQL_PWD", &config.password)
-        .arg("--protocol=TCP")
-        .arg(format!("--host={}", config.host))
-        .arg(format!("--port={}", config.port))
+        .args(mysql_client_args(&config))
         .arg(format!("--user={}", config.username))
         .arg(&config.database)
         .stdin(std::process::Stdio::from(std::fs::File::open(
@@ -71,9 +98,7 @@ pub async fn init_mysql_test_schema() -> Result<(), Box<dyn std::error::Error>>
     // Use --delimiter to handle procedure/function definitions
     let routines_status = std::process::Command::new("mysql")
         .env("MYSQL_PWD", &config.password)
-        .arg("--protocol=TCP")
-        .arg(format!("--host={}", config.host))
-        .arg(format!("--port={}", config.port))
+        .args(mysql_client_args(&config))
         .arg(format!("--user={}", config.username))
         .arg(&config.database)
         .stdin(std::process::Stdio::from(std::fs::File::open(
@@ -103,7 +128,10 @@ pub async fn init_postgres_test_schema() -> Result<(), Box<dyn std::error::Error
     // Set environment variable for password
     let status = std::process::Command::new("psql")
         .env("PGPASSWORD", &config.password)
-        .arg(format!("--host={}", config.host))
+        .arg(format!(
+            "--host={}",
+            config.socket.as_ref().unwrap_or(&config.host)
+        ))
         .arg(format!("--port={}", config.port))
         .arg(format!("--username={}", config.username))
         .arg(format!("--dbname={}", config.database))
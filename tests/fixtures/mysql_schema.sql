@@ -2,11 +2,13 @@
 -- This schema is designed to exercise all metadata features that dbmeta extracts
 
 -- Drop existing objects if they exist
+DROP TABLE IF EXISTS order_archive;
 DROP TABLE IF EXISTS order_items;
 DROP TABLE IF EXISTS orders;
 DROP TABLE IF EXISTS customers;
 DROP TABLE IF EXISTS products;
 DROP VIEW IF EXISTS customer_orders;
+DROP EVENT IF EXISTS purge_cancelled_orders;
 
 -- Create customers table with various column types and constraints
 CREATE TABLE customers (
@@ -39,6 +41,10 @@ CREATE TABLE products (
     INDEX idx_price (price)
 ) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci COMMENT='Product catalog';
 
+-- Create a trigger on products
+CREATE TRIGGER trg_products_trim_name BEFORE INSERT ON products
+    FOR EACH ROW SET NEW.product_name = TRIM(NEW.product_name);
+
 -- Create orders table with foreign key
 CREATE TABLE orders (
     order_id INT AUTO_INCREMENT PRIMARY KEY COMMENT 'Unique order identifier',
@@ -80,6 +86,18 @@ CREATE TABLE order_items (
         ON UPDATE CASCADE
 ) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci COMMENT='Items in orders';
 
+-- Create a range partitioned archive table, each partition is hash subpartitioned
+CREATE TABLE order_archive (
+    order_id INT NOT NULL,
+    order_date DATE NOT NULL,
+    total_amount DECIMAL(10, 2)
+) ENGINE=InnoDB
+PARTITION BY RANGE (YEAR(order_date))
+SUBPARTITION BY HASH (order_id) SUBPARTITIONS 2 (
+    PARTITION p2023 VALUES LESS THAN (2024),
+    PARTITION pmax VALUES LESS THAN MAXVALUE
+);
+
 -- Create a view
 CREATE VIEW customer_orders AS
 SELECT 
@@ -94,6 +112,14 @@ SELECT
 FROM customers c
 JOIN orders o ON c.customer_id = o.customer_id;
 
+-- Create a disabled event purging cancelled orders
+CREATE EVENT purge_cancelled_orders
+    ON SCHEDULE EVERY 1 DAY STARTS '2024-01-01 03:00:00'
+    ON COMPLETION PRESERVE
+    DISABLE
+    COMMENT 'Purge cancelled orders'
+    DO DELETE FROM orders WHERE status = 'cancelled';
+
 -- Insert some test data
 INSERT INTO customers (email, first_name, last_name, phone, city, country) VALUES
     ('john.doe@example.com', 'John', 'Doe', '555-0001', 'New York', 'USA'),
@@ -5,6 +5,9 @@
 CREATE SCHEMA IF NOT EXISTS sales;
 CREATE SCHEMA IF NOT EXISTS inventory;
 
+-- Install an extension
+CREATE EXTENSION IF NOT EXISTS pg_trgm SCHEMA public;
+
 -- Set search path to include our schemas
 SET search_path TO sales, inventory, public;
 
@@ -19,11 +22,15 @@ END$$;
 -- Drop existing objects if they exist (in reverse dependency order)
 DROP VIEW IF EXISTS sales.customer_order_summary CASCADE;
 DROP MATERIALIZED VIEW IF EXISTS sales.monthly_sales CASCADE;
+DROP TABLE IF EXISTS sales.order_event_notes CASCADE;
+DROP TABLE IF EXISTS sales.order_events CASCADE;
 DROP TABLE IF EXISTS sales.order_items CASCADE;
 DROP TABLE IF EXISTS sales.orders CASCADE;
 DROP TABLE IF EXISTS sales.customers CASCADE;
 DROP TABLE IF EXISTS inventory.products CASCADE;
 DROP FUNCTION IF EXISTS sales.calculate_order_total(INT) CASCADE;
+DROP FUNCTION IF EXISTS sales.calculate_order_total(INT, BOOLEAN) CASCADE;
+DROP PROCEDURE IF EXISTS sales.cancel_order(INT) CASCADE;
 
 -- Create customers table in sales schema
 CREATE TABLE sales.customers (
@@ -66,6 +73,37 @@ COMMENT ON COLUMN inventory.products.product_id IS 'Unique product identifier';
 -- Create indexes on products
 CREATE INDEX idx_products_category ON inventory.products(category);
 CREATE INDEX idx_products_price ON inventory.products(price);
+CREATE UNIQUE INDEX idx_products_lower_name ON inventory.products(lower(product_name));
+CREATE INDEX idx_products_in_stock ON inventory.products(category) INCLUDE (price) WHERE stock_quantity > 0;
+
+-- Create a trigger
+CREATE OR REPLACE FUNCTION inventory.trim_product_name() RETURNS trigger
+LANGUAGE plpgsql
+AS $$
+BEGIN
+    NEW.product_name := trim(NEW.product_name);
+    RETURN NEW;
+END;
+$$;
+
+CREATE TRIGGER trg_products_trim_name
+    BEFORE INSERT OR UPDATE OF product_name ON inventory.products
+    FOR EACH ROW EXECUTE FUNCTION inventory.trim_product_name();
+
+-- Create a foreign table through postgres_fdw, the server points back to the current database
+CREATE EXTENSION IF NOT EXISTS postgres_fdw SCHEMA public;
+DROP SERVER IF EXISTS loopback CASCADE;
+DO $$
+BEGIN
+    EXECUTE format('CREATE SERVER loopback FOREIGN DATA WRAPPER postgres_fdw OPTIONS (host %L, dbname %L)',
+        'localhost', current_database());
+END$$;
+
+CREATE FOREIGN TABLE inventory.remote_products (
+    product_id INTEGER,
+    product_name VARCHAR(200),
+    price NUMERIC(10, 2)
+) SERVER loopback OPTIONS (schema_name 'inventory', table_name 'products');
 
 -- Create orders table with foreign key and custom enum type
 CREATE TABLE sales.orders (
@@ -117,6 +155,39 @@ COMMENT ON TABLE sales.order_items IS 'Items in orders';
 CREATE INDEX idx_order_items_order ON sales.order_items(order_id);
 CREATE INDEX idx_order_items_product ON sales.order_items(product_id);
 
+-- Create a range partitioned table, the 2025 partition is hash partitioned itself
+CREATE TABLE sales.order_events (
+    event_id INTEGER NOT NULL,
+    created_at DATE NOT NULL,
+    payload TEXT,
+    PRIMARY KEY (event_id, created_at)
+) PARTITION BY RANGE (created_at);
+
+CREATE TABLE sales.order_events_2024 PARTITION OF sales.order_events
+    FOR VALUES FROM ('2024-01-01') TO ('2025-01-01');
+CREATE TABLE sales.order_events_2025 PARTITION OF sales.order_events
+    FOR VALUES FROM ('2025-01-01') TO ('2026-01-01') PARTITION BY HASH (event_id);
+CREATE TABLE sales.order_events_2025_0 PARTITION OF sales.order_events_2025
+    FOR VALUES WITH (MODULUS 2, REMAINDER 0);
+CREATE TABLE sales.order_events_2025_1 PARTITION OF sales.order_events_2025
+    FOR VALUES WITH (MODULUS 2, REMAINDER 1);
+CREATE TABLE sales.order_events_default PARTITION OF sales.order_events DEFAULT;
+
+-- Create an index on the partitioned table, which indexes every partition
+CREATE INDEX idx_order_events_event ON sales.order_events(event_id);
+
+-- Create a table referencing the partitioned table, the server clones the foreign key for each
+-- partition internally
+CREATE TABLE sales.order_event_notes (
+    event_id INTEGER NOT NULL,
+    created_at DATE NOT NULL,
+    note TEXT,
+    CONSTRAINT fk_order_event_notes_event
+        FOREIGN KEY (event_id, created_at)
+        REFERENCES sales.order_events(event_id, created_at)
+        ON DELETE CASCADE
+);
+
 -- Create a regular view
 CREATE VIEW sales.customer_order_summary AS
 SELECT 
@@ -164,7 +235,25 @@ BEGIN
 END;
 $$;
 
-COMMENT ON FUNCTION sales.calculate_order_total IS 'Calculate the total amount for an order';
+COMMENT ON FUNCTION sales.calculate_order_total(INTEGER) IS 'Calculate the total amount for an order';
+
+-- Create an overload of the function
+CREATE OR REPLACE FUNCTION sales.calculate_order_total(order_id_param INTEGER, apply_discount BOOLEAN)
+RETURNS NUMERIC(10,2)
+LANGUAGE sql
+STABLE
+AS $$
+    SELECT COALESCE(SUM(quantity * unit_price * (CASE WHEN apply_discount THEN 1 - discount/100 ELSE 1 END)), 0.00)
+    FROM sales.order_items
+    WHERE order_id = order_id_param;
+$$;
+
+-- Create a procedure
+CREATE OR REPLACE PROCEDURE sales.cancel_order(order_id_param INTEGER)
+LANGUAGE sql
+AS $$
+    UPDATE sales.orders SET status = 'cancelled' WHERE order_id = order_id_param;
+$$;
 
 -- Insert test data
 INSERT INTO sales.customers (email, first_name, last_name, phone, city, country) VALUES